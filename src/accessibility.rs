@@ -0,0 +1,125 @@
+use crate::{collisions::Damaging, energybars::EnergyBar, preferences::Preferences};
+use bevy::prelude::*;
+
+///[Plugin] taking care of accessibility [Preferences] which change how gameplay [entities](Entity) look:
+/// [Outline]s of [Damaging] [entities](Entity) and the tint of [EnergyBar]s. Never affects the game itself.
+pub struct AccessibilityPlugin;
+
+///A black silhouette drawn slightly larger right behind a [Damaging] [Entity], so it stands out from the background.
+/// Kept as a separate [Entity] following its target, since enemies are despawned without their children.
+/// # Fields
+/// * `0` - The outlined [Damaging] [Entity].
+#[derive(Component)]
+struct Outline(Entity);
+
+///Color of [Outline]s. The sprite is tinted by it, so black turns it into a silhouette.
+const OUTLINE_COLOR: Color = Color::BLACK;
+///How much larger an [Outline] is than its target.
+const OUTLINE_SCALE: f32 = 1.12;
+
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            ((spawn_outlines, follow_outlines).chain(), tint_energy_bars),
+        );
+    }
+}
+
+///Returns [Transform] of an [Outline] of an [Entity] at `target`.
+/// # Arguments
+/// * `target` - [Transform] of the outlined [Entity].
+fn outline_transform(target: &Transform) -> Transform {
+    let mut transform = *target;
+    transform.translation.z -= 0.01;
+    transform.scale *= OUTLINE_SCALE;
+    transform
+}
+
+///Spawns an [Outline] for every new [Damaging] [Entity] while [Preferences] `outlines` is on. Once [Preferences]
+/// change, all [Outline]s are despawned and spawned again if still turned on.
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `preferences` - [Preferences].
+/// * `damaging_q` - [Query] for [Damaging] [entities](Entity) and their sprites.
+/// * `outline_q` - [Query] for [Outline]s.
+fn spawn_outlines(
+    mut commands: Commands,
+    preferences: Res<Preferences>,
+    damaging_q: Query<(
+        Entity,
+        Ref<Damaging>,
+        &TextureAtlasSprite,
+        &Handle<TextureAtlas>,
+        &Transform,
+    )>,
+    outline_q: Query<Entity, With<Outline>>,
+) {
+    if preferences.is_changed() {
+        for outline in &outline_q {
+            commands.entity(outline).despawn();
+        }
+    }
+    if !preferences.outlines {
+        return;
+    }
+    for (entity, damaging, sprite, atlas, transform) in &damaging_q {
+        if !preferences.is_changed() && !damaging.is_added() {
+            continue;
+        }
+        commands
+            .spawn(SpriteSheetBundle {
+                sprite: TextureAtlasSprite {
+                    color: OUTLINE_COLOR,
+                    ..sprite.clone()
+                },
+                texture_atlas: atlas.clone(),
+                transform: outline_transform(transform),
+                ..default()
+            })
+            .insert(Outline(entity));
+    }
+}
+
+///Moves every [Outline] to its target and copies its animation frame. [Outline]s of despawned
+/// [entities](Entity) are despawned.
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `outline_q` - [Query] for [Outline]s.
+/// * `target_q` - [Query] for [Damaging] [entities](Entity).
+fn follow_outlines(
+    mut commands: Commands,
+    mut outline_q: Query<
+        (Entity, &Outline, &mut Transform, &mut TextureAtlasSprite),
+        Without<Damaging>,
+    >,
+    target_q: Query<(&Transform, &TextureAtlasSprite), With<Damaging>>,
+) {
+    for (entity, outline, mut transform, mut sprite) in &mut outline_q {
+        let Ok((target_transform, target_sprite)) = target_q.get(outline.0) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        *transform = outline_transform(target_transform);
+        if sprite.index != target_sprite.index || sprite.flip_x != target_sprite.flip_x {
+            sprite.index = target_sprite.index;
+            sprite.flip_x = target_sprite.flip_x;
+        }
+    }
+}
+
+///Tints every new [EnergyBar] by [Preferences] `palette`, all of them once [Preferences] change.
+/// # Arguments
+/// * `preferences` - [Preferences].
+/// * `bar_q` - [Query] for [EnergyBar]s.
+fn tint_energy_bars(
+    preferences: Res<Preferences>,
+    mut bar_q: Query<(Ref<EnergyBar>, &mut TextureAtlasSprite)>,
+) {
+    let tint = preferences.palette.energy_tint();
+    for (bar, mut sprite) in &mut bar_q {
+        if preferences.is_changed() || bar.is_added() {
+            sprite.color = tint;
+        }
+    }
+}
@@ -1,26 +1,114 @@
+use crate::{rng::GameRng, volume::Volume};
 use bevy::prelude::*;
-use bevy_kira_audio::Audio;
+use bevy::utils::HashMap;
 use bevy_kira_audio::*;
-use std::time::Duration;
-///[Plugin] taking care of audio related functionalities.
+use rand::Rng;
+
+///[Plugin] taking care of sound effects. Music is played by [crate::music::MusicPlugin].
 pub struct GameAudioPlugin;
 
+///[AudioChannel] of sound effects, kept apart from the music so both can be controlled separately.
+#[derive(Resource)]
+pub struct SfxChannel;
+
+///Gameplay events which have a sound effect. Sent by gameplay systems, played by [play_sfx].
+/// # Fields
+/// * `Hit` - [crate::player::Player] lost hp.
+/// * `Death` - [crate::player::Player] ran out of hp.
+/// * `Pickup` - [crate::player::Player] picked up an [crate::energybars::EnergyBar] or a [crate::fruit::Fruit].
+/// * `PlaneSpawn` - A [crate::plane::PlaneSensor] spawned a plane.
+/// * `Capture` - [crate::player::Player] got trapped in a [crate::blackhole::Hole].
+/// * `Hide` - [crate::player::Player] hid.
+/// * `Unhide` - [crate::player::Player] stopped hiding.
+#[derive(Event, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SfxEvent {
+    Hit,
+    Death,
+    Pickup,
+    PlaneSpawn,
+    Capture,
+    Hide,
+    Unhide,
+}
+
+///How a single [SfxEvent] sounds.
+/// # Fields
+/// * `file` - Sound in the assets folder.
+/// * `volume` - Volume it is played at.
+/// * `pitch` - Playback rate is picked randomly from `1 ± pitch`, so repeated sounds do not get monotonous.
+/// * `cooldown` - Minimum number of seconds between two plays, more events in between are dropped.
+pub struct SoundDef {
+    pub file: &'static str,
+    pub volume: f64,
+    pub pitch: f64,
+    pub cooldown: f32,
+}
+
+impl SfxEvent {
+    ///Returns the [SoundDef] of the event.
+    pub fn sound(&self) -> SoundDef {
+        let (file, volume, pitch, cooldown) = match self {
+            SfxEvent::Hit => ("sfx/hit.wav", 0.6, 0.1, 0.1),
+            SfxEvent::Death => ("sfx/death.wav", 0.7, 0., 0.5),
+            SfxEvent::Pickup => ("sfx/pickup.wav", 0.4, 0.15, 0.05),
+            SfxEvent::PlaneSpawn => ("sfx/plane.wav", 0.35, 0.1, 0.3),
+            SfxEvent::Capture => ("sfx/capture.wav", 0.5, 0.05, 0.5),
+            SfxEvent::Hide => ("sfx/hide.wav", 0.25, 0.05, 0.08),
+            SfxEvent::Unhide => ("sfx/unhide.wav", 0.25, 0.05, 0.08),
+        };
+        SoundDef {
+            file,
+            volume,
+            pitch,
+            cooldown,
+        }
+    }
+}
+
+///Elapsed [Time] at which each [SfxEvent] was last played, used for rate limiting.
+#[derive(Resource, Default)]
+struct LastPlayed(HashMap<SfxEvent, f32>);
+
 impl Plugin for GameAudioPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, play_background_music);
+        app.add_audio_channel::<SfxChannel>()
+            .init_resource::<LastPlayed>()
+            .add_systems(Update, play_sfx);
     }
 }
-///Turns on background music on repeat.
+
+///Plays the sound of every [SfxEvent] on [SfxChannel], unless the same sound played less than its cooldown ago.
+/// Scaled by [Volume].
 /// # Arguments
-/// * `asset_server` - [AssetServer], used to load the music in.
-/// * `audio` - [Audio].
-fn play_background_music(asset_server: Res<AssetServer>, audio: Res<Audio>) {
-    audio
-        .play(asset_server.load("bckgrnd_msc.wav")) //Music by Vojtěch Klhůfek
-        .fade_in(AudioTween::new(
-            Duration::from_secs_f32(0.5),
-            AudioEasing::OutPowi(2),
-        ))
-        .with_volume(0.3)
-        .looped();
+/// * `events` - Reads [SfxEvent]s.
+/// * `asset_server` - [AssetServer], sounds are preloaded by [crate::loading::LoadingPlugin].
+/// * `channel` - [SfxChannel].
+/// * `time` - [Time].
+/// * `last_played` - [LastPlayed].
+/// * `rng` - [GameRng], pitch is picked from its cosmetic stream.
+/// * `volume` - [Volume].
+fn play_sfx(
+    mut events: EventReader<SfxEvent>,
+    asset_server: Res<AssetServer>,
+    channel: Res<AudioChannel<SfxChannel>>,
+    time: Res<Time>,
+    mut last_played: ResMut<LastPlayed>,
+    mut rng: ResMut<GameRng>,
+    volume: Res<Volume>,
+) {
+    let now = time.elapsed_seconds();
+    for event in events.iter() {
+        let sound = event.sound();
+        if let Some(last) = last_played.0.get(event) {
+            if now - last < sound.cooldown {
+                continue;
+            }
+        }
+        last_played.0.insert(*event, now);
+        let rate = 1. + rng.cosmetic.gen_range(-1. ..=1.) * sound.pitch;
+        channel
+            .play(asset_server.load(sound.file))
+            .with_volume(sound.volume * volume.sfx())
+            .with_playback_rate(rate);
+    }
 }
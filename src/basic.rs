@@ -1,10 +1,16 @@
+use crate::catalogue::EnemyCatalogue;
 use crate::collisions::Damaging;
-use crate::falling::FallTimer;
-use crate::Object;
+use crate::falling::{FallSpeed, FallTimer};
+use crate::map_layout::{Enemy, ObjectParams};
+use crate::playfield::Playfield;
+use crate::walls::enemy_groups;
+use crate::{Gravitating, Object};
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
+use rand::{rngs::StdRng, Rng};
 
-///Labels falling damaging [entities](Entity) which have no special effects.
+///Labels falling [entities](Entity) which have no special effects, usually damaging. Their kinds are defined in
+/// [EnemyCatalogue].
 #[derive(Component)]
 pub struct BasicObject;
 
@@ -12,238 +18,79 @@ pub struct BasicObject;
 /// # Fields
 /// * `object` - [BasicObject]
 /// * `timer` - [FallTimer]
+/// * `fall_speed` - [FallSpeed]
 /// * `body` - [RigidBody]
-/// * `dmg` - [Damaging]
 #[derive(Bundle)]
 struct BasicBundle {
     object: BasicObject,
     timer: FallTimer,
+    fall_speed: FallSpeed,
     body: RigidBody,
-    dmg: Damaging,
 }
-/// Spawns [BasicObject] object.
-/// Calls one of the following function:
-/// * [create_full_choc]
-/// * [create_part_choc]
-/// * [create_egg]
-/// * [create_lolly]
-/// * [create_love]
-/// * [create_drink]
+
+/// Spawns [BasicObject] of a kind from [EnemyCatalogue].
 /// # Arguments
-/// * `x` - if [None], a random `x` within resolution is chosen.
-/// * `y` - if [None], it is set 100px above upper bound.
+/// * `x` - if [None], a random `x` within [Playfield] is chosen.
+/// * `y` - if [None], it is set to [Playfield::spawn_y], above the upper bound.
+/// * `params` - [ObjectParams], `sprite` (below [EnemyCatalogue::sprites]) picks both the kind and its sprite, otherwise
+/// the kind is picked by its `weight`. `rotation`, `gravity` (of kinds which have gravity) and `fall_speed` are used too.
 /// * `commands` - [Commands].
-/// * `full_choc_t` ... `drink_t` - [Handle]s for different [TextureAtlas].
-
+/// * `rng` - Gameplay stream of [crate::rng::GameRng].
+/// * `playfield` - [Playfield].
+/// * `catalogue` - [EnemyCatalogue].
 pub fn create_basic(
     x: Option<f32>,
     y: Option<f32>,
+    params: &ObjectParams,
     commands: &mut Commands,
-    full_choc_t: &Handle<TextureAtlas>,
-    part_choc_t: &Handle<TextureAtlas>,
-    egg_t: &Handle<TextureAtlas>,
-    lolly_t: &Handle<TextureAtlas>,
-    love_t: &Handle<TextureAtlas>,
-    drink_t: &Handle<TextureAtlas>,
+    rng: &mut StdRng,
+    playfield: &Playfield,
+    catalogue: &EnemyCatalogue,
 ) {
-    let random_num: usize = rand::random();
-    let x = x.unwrap_or((rand::random::<f32>() - 0.5) * (1920. / 3.));
-    let y = y.unwrap_or(600.);
-
-    let basic_bundle = BasicBundle {
-        object: BasicObject,
-        timer: FallTimer(Timer::from_seconds(7., TimerMode::Once)),
-        body: RigidBody::Fixed,
-        dmg: Damaging,
-    };
+    let random_num: usize = rng.gen();
+    let x = x.unwrap_or(playfield.random_x(rng));
+    let y = y.unwrap_or(playfield.spawn_y());
 
     let transform = Transform {
-        translation: Vec3::new(x, y, 900. + rand::random::<f32>()),
-        rotation: Quat::from_rotation_z((random_num % 360) as f32 / 180.),
+        translation: Vec3::new(x, y, 900. + rng.gen::<f32>()),
+        rotation: params.rotation_or(Quat::from_rotation_z((random_num % 360) as f32 / 180.)),
         ..Default::default()
     };
 
-    let object = match random_num % 6 {
-        0 => create_full_choc(transform, basic_bundle, full_choc_t, commands),
-        1 => create_part_choc(transform, basic_bundle, part_choc_t, commands),
-        2 => create_egg(transform, basic_bundle, egg_t, commands),
-        3 => create_lolly(transform, basic_bundle, lolly_t, commands),
-        4 => create_love(transform, basic_bundle, love_t, commands),
-        5 => create_drink(transform, basic_bundle, drink_t, commands),
-        _ =>
-        /* never happens */
-        {
-            create_drink(transform, basic_bundle, drink_t, commands)
+    let (kind, sprite) = match params.sprite {
+        Some(variant) => catalogue.variant(variant),
+        None => {
+            let kind = catalogue.pick(random_num);
+            (kind, rng.gen::<usize>() % catalogue.kinds[kind].frames)
         }
     };
+    let def = &catalogue.kinds[kind];
 
-    commands.entity(object);
-}
-
-/// Creates full chocolate bar [BasicObject].
-/// #Arguments
-/// * `transform` - Enemy's [Transform].
-/// * `basic_bundle` - [BasicBundle].
-/// * `texture` - [Handle] for enemy's [TextureAtlas].
-/// * `commands`- [Commands].
-fn create_full_choc(
-    transform: Transform,
-    basic_bundle: BasicBundle,
-    texture: &Handle<TextureAtlas>,
-
-    commands: &mut Commands,
-) -> Entity {
-    let sprite = TextureAtlasSprite::new(rand::random::<usize>() % 2);
-    commands
-        .spawn(basic_bundle)
-        .insert(SpriteSheetBundle {
-            sprite,
-            texture_atlas: texture.clone(),
-            transform,
-            ..Default::default()
-        })
-        .insert(Collider::cuboid(48., 22.))
-        .insert(Object)
-        .id()
-}
-
-/// Creates partial chocolate bar [BasicObject].
-/// #Arguments
-/// * `transform` - Enemy's [Transform].
-/// * `basic_bundle` - [BasicBundle].
-/// * `texture` - [Handle] for enemy's [TextureAtlas].
-/// * `commands`- [Commands].
-fn create_part_choc(
-    transform: Transform,
-    basic_bundle: BasicBundle,
-    texture: &Handle<TextureAtlas>,
-    commands: &mut Commands,
-) -> Entity {
-    let sprite = TextureAtlasSprite::new(rand::random::<usize>() % 2);
-    commands
-        .spawn(basic_bundle)
-        .insert(SpriteSheetBundle {
-            sprite,
-            texture_atlas: texture.clone(),
-            transform,
-            ..Default::default()
-        })
-        .insert(Collider::compound(vec![(
-            Vec2::new(-5., 0.),
-            0.,
-            Collider::cuboid(40., 22.),
-        )]))
-        .insert(Object)
-        .id()
-}
-
-/// Creates chocolate egg [BasicObject].
-/// #Arguments
-/// * `transform` - Enemy's [Transform].
-/// * `basic_bundle` - [BasicBundle].
-/// * `texture` - [Handle] for enemy's [TextureAtlas].
-/// * `commands`- [Commands].
-fn create_egg(
-    transform: Transform,
-    basic_bundle: BasicBundle,
-    texture: &Handle<TextureAtlas>,
-
-    commands: &mut Commands,
-) -> Entity {
-    let sprite = TextureAtlasSprite::new(rand::random::<usize>() % 2);
-    commands
-        .spawn(basic_bundle)
-        .insert(SpriteSheetBundle {
-            sprite,
-            texture_atlas: texture.clone(),
-            transform,
-            ..Default::default()
-        })
-        .insert(Collider::capsule_y(20., 27.))
-        .insert(Object)
-        .id()
-}
-
-/// Creates lollipop [BasicObject].
-/// #Arguments
-/// * `transform` - Enemy's [Transform].
-/// * `basic_bundle` - [BasicBundle].
-/// * `texture` - [Handle] for enemy's [TextureAtlas].
-/// * `commands`- [Commands].
-fn create_lolly(
-    transform: Transform,
-    basic_bundle: BasicBundle,
-    texture: &Handle<TextureAtlas>,
-
-    commands: &mut Commands,
-) -> Entity {
-    let sprite = TextureAtlasSprite::new(rand::random::<usize>() % 4);
-    commands
-        .spawn(basic_bundle)
-        .insert(SpriteSheetBundle {
-            sprite,
-            texture_atlas: texture.clone(),
-            transform,
-            ..Default::default()
-        })
-        .insert(Collider::compound(vec![
-            (Vec2::new(0., 25.), 0., Collider::ball(25.)),
-            (Vec2::new(0., -10.), 0., Collider::cuboid(3., 37.)),
-        ]))
-        .insert(Object)
-        .id()
-}
-
-/// Creates round gingerbread [BasicObject].
-/// #Arguments
-/// * `transform` - Enemy's [Transform].
-/// * `basic_bundle` - [BasicBundle].
-/// * `texture` - [Handle] for enemy's [TextureAtlas].
-/// * `commands`- [Commands].
-fn create_love(
-    transform: Transform,
-    basic_bundle: BasicBundle,
-    texture: &Handle<TextureAtlas>,
-
-    commands: &mut Commands,
-) -> Entity {
-    let sprite = TextureAtlasSprite::new(rand::random::<usize>() % 2);
-    commands
-        .spawn(basic_bundle)
-        .insert(SpriteSheetBundle {
-            sprite,
-            texture_atlas: texture.clone(),
-            transform,
-            ..Default::default()
-        })
-        .insert(Collider::ball(49.))
-        .insert(Object)
-        .id()
-}
-
-/// Creates drink [BasicObject].
-/// #Arguments
-/// * `transform` - Enemy's [Transform].
-/// * `basic_bundle` - [BasicBundle].
-/// * `texture` - [Handle] for enemy's [TextureAtlas].
-/// * `commands`- [Commands].
-fn create_drink(
-    transform: Transform,
-    basic_bundle: BasicBundle,
-    texture: &Handle<TextureAtlas>,
-
-    commands: &mut Commands,
-) -> Entity {
-    let sprite = TextureAtlasSprite::new(rand::random::<usize>() % 1);
-    commands
-        .spawn(basic_bundle)
+    let mut object = commands.spawn(BasicBundle {
+        object: BasicObject,
+        timer: FallTimer(Timer::from_seconds(7., TimerMode::Once)),
+        fall_speed: FallSpeed(params.fall_speed.unwrap_or(def.fall_speed)),
+        body: RigidBody::Fixed,
+    });
+    object
         .insert(SpriteSheetBundle {
-            sprite,
-            texture_atlas: texture.clone(),
+            sprite: TextureAtlasSprite::new(sprite),
+            texture_atlas: catalogue.atlases.get(kind).cloned().unwrap_or_default(),
             transform,
             ..Default::default()
         })
-        .insert(Collider::cuboid(10., 40.))
-        .insert(Object)
-        .id()
+        .insert(def.collider())
+        .insert(enemy_groups())
+        .insert(Object);
+    if def.damaging {
+        object.insert(Damaging(Enemy::BasicE));
+    }
+    if def.sensor {
+        object.insert(Sensor);
+    }
+    if let Some(strength) = def.gravity {
+        object.insert(Gravitating {
+            strength: params.gravity.unwrap_or(strength),
+        });
+    }
 }
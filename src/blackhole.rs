@@ -1,5 +1,9 @@
-use crate::{falling::FallTimer, AnimationTimer, Gravitating, Object};
+use crate::{
+    falling::FallTimer, map_layout::ObjectParams, playfield::Playfield, AnimationTimer,
+    Gravitating, Object,
+};
 use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng};
 
 /// Size of [Hole] enemies
 pub const BLACKHOLE_SIZE: f32 = 70.;
@@ -10,35 +14,45 @@ pub struct Hole;
 
 ///Spawns a [Hole] object.
 /// # Arguments
-/// * `x` - if [None], a random `x` within resolution is chosen.
-/// * `y` - if [None], it is set 100px above upper bound.
+/// * `x` - if [None], a random `x` within [Playfield] is chosen.
+/// * `y` - if [None], it is set to [Playfield::spawn_y], above the upper bound.
+/// * `params` - [ObjectParams], `rotation`, `gravity` and `fall_speed` are used.
 /// * `commands` - [Commands].
+/// * `rng` - Gameplay stream of [crate::rng::GameRng].
+/// * `playfield` - [Playfield].
 /// * `texture` - Handle for black hole [TextureAtlas]
 pub fn create_hole(
     x: Option<f32>,
     y: Option<f32>,
+    params: &ObjectParams,
     commands: &mut Commands,
+    rng: &mut StdRng,
+    playfield: &Playfield,
     texture: &Handle<TextureAtlas>,
 ) {
     let mut sprite = TextureAtlasSprite::new(0);
     sprite.custom_size = Some(Vec2::splat(BLACKHOLE_SIZE));
 
-    let x = x.unwrap_or((rand::random::<f32>() - 0.5) * (1920. / 3.));
-    let y = y.unwrap_or(600.);
+    let x = x.unwrap_or(playfield.random_x(rng));
+    let y = y.unwrap_or(playfield.spawn_y());
 
     let hole = commands
         .spawn(SpriteSheetBundle {
             sprite,
             texture_atlas: texture.clone(),
             transform: Transform {
-                translation: Vec3::new(x, y, 900. + rand::random::<f32>()),
+                translation: Vec3::new(x, y, 900. + rng.gen::<f32>()),
+                rotation: params.rotation_or(Quat::IDENTITY),
                 ..Default::default()
             },
             ..Default::default()
         })
         .insert(Hole)
         .insert(FallTimer(Timer::from_seconds(7., TimerMode::Once)))
-        .insert(Gravitating { strength: 2.5 })
+        .insert(params.fall_speed())
+        .insert(Gravitating {
+            strength: params.gravity.unwrap_or(2.5),
+        })
         .insert(AnimationTimer(Timer::from_seconds(
             0.15,
             TimerMode::Repeating,
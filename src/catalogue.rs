@@ -0,0 +1,488 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::Collider;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::read_to_string;
+
+///File [EnemyCatalogue] is loaded from, unless a different one is passed with `--enemies PATH`.
+pub const CATALOGUE_FILE: &str = "./assets/enemies.ron";
+
+///Shape of a single part of an enemy's collider, in px.
+/// * `Ball` - Circle with given radius.
+/// * `Cuboid` - Rectangle with given half width and half height.
+/// * `CapsuleX` - Horizontal capsule with given half length and radius.
+/// * `CapsuleY` - Vertical capsule with given half length and radius.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Shape {
+    Ball(f32),
+    Cuboid(f32, f32),
+    CapsuleX(f32, f32),
+    CapsuleY(f32, f32),
+}
+
+impl Shape {
+    ///[Collider] of the shape alone.
+    pub fn collider(&self) -> Collider {
+        match *self {
+            Shape::Ball(radius) => Collider::ball(radius),
+            Shape::Cuboid(half_width, half_height) => Collider::cuboid(half_width, half_height),
+            Shape::CapsuleX(half_length, radius) => Collider::capsule_x(half_length, radius),
+            Shape::CapsuleY(half_length, radius) => Collider::capsule_y(half_length, radius),
+        }
+    }
+
+    ///Whether all sizes of the shape are finite and greater than 0.
+    fn is_valid(&self) -> bool {
+        let sizes = match *self {
+            Shape::Ball(radius) => [radius, radius],
+            Shape::Cuboid(a, b) | Shape::CapsuleX(a, b) | Shape::CapsuleY(a, b) => [a, b],
+        };
+        sizes.iter().all(|size| size.is_finite() && *size > 0.)
+    }
+}
+
+///A single part of an enemy's collider.
+/// # Fields
+/// * `shape` - [Shape].
+/// * `offset` - Position relative to the enemy's center.
+/// * `angle` - Rotation in radians.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct ColliderPart {
+    pub shape: Shape,
+    #[serde(default)]
+    pub offset: (f32, f32),
+    #[serde(default)]
+    pub angle: f32,
+}
+
+///Definition of one kind of [crate::basic::BasicObject].
+/// # Fields
+/// * `name` - Name used in errors.
+/// * `sheet` - Sprite sheet in the assets folder, a single row of sprites.
+/// * `tile` - Size of a single sprite in the sheet.
+/// * `frames` - Number of sprites in the sheet. A random one is picked, unless a structure picks one.
+/// * `colliders` - [ColliderPart]s making up its collider.
+/// * `damaging` - Whether it damages the player, see [crate::collisions::Damaging].
+/// * `sensor` - Whether the player flies through it instead of bouncing off.
+/// * `gravity` - [crate::Gravitating] strength, [None] if it does not pull the player.
+/// * `fall_speed` - Multiplier of its falling speed, see [crate::falling::FallSpeed].
+/// * `weight` - How often it is picked compared to other kinds when a structure does not pick one.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct EnemyDef {
+    pub name: String,
+    pub sheet: String,
+    pub tile: (f32, f32),
+    pub frames: usize,
+    pub colliders: Vec<ColliderPart>,
+    #[serde(default = "default_damaging")]
+    pub damaging: bool,
+    #[serde(default)]
+    pub sensor: bool,
+    #[serde(default)]
+    pub gravity: Option<f32>,
+    #[serde(default = "default_fall_speed")]
+    pub fall_speed: f32,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+///Default of [EnemyDef] `damaging`.
+fn default_damaging() -> bool {
+    true
+}
+
+///Default of [EnemyDef] `fall_speed`.
+fn default_fall_speed() -> f32 {
+    1.
+}
+
+///Default of [EnemyDef] `weight`.
+fn default_weight() -> u32 {
+    1
+}
+
+impl EnemyDef {
+    ///[Collider] made of all `colliders`. A single part without offset and angle is used as is.
+    pub fn collider(&self) -> Collider {
+        match self.colliders.as_slice() {
+            [part] if part.offset == (0., 0.) && part.angle == 0. => part.shape.collider(),
+            parts => Collider::compound(
+                parts
+                    .iter()
+                    .map(|part| {
+                        (
+                            Vec2::new(part.offset.0, part.offset.1),
+                            part.angle,
+                            part.shape.collider(),
+                        )
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    ///Checks that every value makes sense, returning the name of the first wrong field and what is wrong with it.
+    fn validate(&self) -> Result<(), (&'static str, &'static str)> {
+        if self.frames == 0 {
+            return Err(("frames", "must be at least 1"));
+        }
+        if !(self.tile.0.is_finite()
+            && self.tile.0 > 0.
+            && self.tile.1.is_finite()
+            && self.tile.1 > 0.)
+        {
+            return Err(("tile", "must be greater than 0"));
+        }
+        if self.colliders.is_empty() {
+            return Err(("colliders", "must contain at least one shape"));
+        }
+        if !self.colliders.iter().all(|part| part.shape.is_valid()) {
+            return Err(("colliders", "sizes must be greater than 0"));
+        }
+        if self.gravity.is_some_and(|gravity| !gravity.is_finite()) {
+            return Err(("gravity", "must be a number"));
+        }
+        if !self.fall_speed.is_finite() || self.fall_speed < 0. {
+            return Err(("fall_speed", "must be a number greater than or equal to 0"));
+        }
+        Ok(())
+    }
+}
+
+///Reasons why [EnemyCatalogue] could not be loaded.
+/// * `Io` - The file could not be read, for example because it does not exist.
+/// * `Parse` - The file is not valid RON or contains unknown fields.
+/// * `Invalid` - A value of an enemy is out of its allowed range.
+/// * `Empty` - No enemy could ever be picked.
+#[derive(Debug)]
+pub enum CatalogueError {
+    Io {
+        path: String,
+        error: std::io::Error,
+    },
+    Parse {
+        path: String,
+        error: ron::error::SpannedError,
+    },
+    Invalid {
+        path: String,
+        enemy: String,
+        field: &'static str,
+        reason: &'static str,
+    },
+    Empty {
+        path: String,
+    },
+}
+
+impl fmt::Display for CatalogueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogueError::Io { path, error } => write!(f, "{}: {}", path, error),
+            CatalogueError::Parse { path, error } => write!(
+                f,
+                "{}:{}:{}: {}",
+                path, error.position.line, error.position.col, error.code
+            ),
+            CatalogueError::Invalid {
+                path,
+                enemy,
+                field,
+                reason,
+            } => write!(f, "{}: `{}` of {} {}", path, field, enemy, reason),
+            CatalogueError::Empty { path } => {
+                write!(
+                    f,
+                    "{}: at least one enemy needs a weight greater than 0",
+                    path
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CatalogueError {}
+
+///All kinds of [crate::basic::BasicObject] the game can spawn. Loaded from [CATALOGUE_FILE] at startup, so a new
+/// obstacle only needs a sprite sheet and a few lines in the file.
+/// # Fields
+/// * `kinds` - [EnemyDef] of every kind.
+/// * `atlases` - [TextureAtlas] of every kind, in the same order. Empty without a window.
+#[derive(Resource, Clone, Debug)]
+pub struct EnemyCatalogue {
+    pub kinds: Vec<EnemyDef>,
+    pub atlases: Vec<Handle<TextureAtlas>>,
+}
+
+impl EnemyCatalogue {
+    ///Loads [EnemyCatalogue] from a RON file containing a list of [EnemyDef]s.
+    pub fn load(path: &str) -> Result<EnemyCatalogue, CatalogueError> {
+        let content = read_to_string(path).map_err(|error| CatalogueError::Io {
+            path: path.to_string(),
+            error,
+        })?;
+        let kinds: Vec<EnemyDef> =
+            ron::from_str(&content).map_err(|error| CatalogueError::Parse {
+                path: path.to_string(),
+                error,
+            })?;
+        for kind in &kinds {
+            kind.validate()
+                .map_err(|(field, reason)| CatalogueError::Invalid {
+                    path: path.to_string(),
+                    enemy: kind.name.clone(),
+                    field,
+                    reason,
+                })?;
+        }
+        if kinds.iter().all(|kind| kind.weight == 0) {
+            return Err(CatalogueError::Empty {
+                path: path.to_string(),
+            });
+        }
+        Ok(EnemyCatalogue {
+            kinds,
+            atlases: Vec::new(),
+        })
+    }
+
+    ///Hash of `kinds`, the same in every game with the same catalogue. Computed with FNV-1a over the RON of `kinds`,
+    /// so it does not depend on the build like [std::hash::Hash] would.
+    pub fn fingerprint(&self) -> u64 {
+        ron::to_string(&self.kinds)
+            .expect("EnemyDef is always serializable.")
+            .bytes()
+            .fold(0xcbf29ce484222325, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            })
+    }
+
+    ///Number of all sprite variants. Variants are numbered kind by kind, in the order of `kinds`.
+    pub fn sprites(&self) -> usize {
+        self.kinds.iter().map(|kind| kind.frames).sum()
+    }
+
+    ///Splits a sprite variant, numbered as described in [EnemyCatalogue::sprites], into the kind and the sprite within
+    /// its sheet. Variants past the last one wrap around.
+    /// # Arguments
+    /// * `variant` - Sprite variant.
+    pub fn variant(&self, variant: usize) -> (usize, usize) {
+        let mut sprite = variant % self.sprites().max(1);
+        for (kind, def) in self.kinds.iter().enumerate() {
+            if sprite < def.frames {
+                return (kind, sprite);
+            }
+            sprite -= def.frames;
+        }
+        (0, 0)
+    }
+
+    ///Picks a kind by `weight`, using an already drawn random number.
+    /// # Arguments
+    /// * `random_num` - Random number.
+    pub fn pick(&self, random_num: usize) -> usize {
+        let total: usize = self.kinds.iter().map(|kind| kind.weight as usize).sum();
+        let mut roll = random_num % total.max(1);
+        for (kind, def) in self.kinds.iter().enumerate() {
+            if roll < def.weight as usize {
+                return kind;
+            }
+            roll -= def.weight as usize;
+        }
+        0
+    }
+}
+
+///Reads the catalogue file path from command line arguments, given as `--enemies PATH`, and loads [EnemyCatalogue]
+/// from it. Without the argument, [CATALOGUE_FILE] is used.
+/// # Arguments
+/// * `args` - Command line arguments.
+pub fn catalogue_from_args(args: &[String]) -> Result<EnemyCatalogue, String> {
+    let path = match args.iter().position(|arg| arg == "--enemies") {
+        Some(position) => args
+            .get(position + 1)
+            .ok_or_else(|| "--enemies expects a path".to_string())?,
+        None => CATALOGUE_FILE,
+    };
+    EnemyCatalogue::load(path).map_err(|err| err.to_string())
+}
+
+///Loads sprite sheets of every kind in [EnemyCatalogue].
+/// # Arguments
+/// * `catalogue` - [EnemyCatalogue].
+/// * `assets` - [AssetServer].
+/// * `texture_atlases` - [Assets] of type [TextureAtlas].
+pub fn load_catalogue_sheets(
+    mut catalogue: ResMut<EnemyCatalogue>,
+    assets: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+) {
+    catalogue.atlases = catalogue
+        .kinds
+        .iter()
+        .map(|kind| {
+            let image = assets.load(kind.sheet.as_str());
+            texture_atlases.add(TextureAtlas::from_grid(
+                image,
+                Vec2::new(kind.tile.0, kind.tile.1),
+                kind.frames,
+                1,
+                None,
+                None,
+            ))
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{remove_file, write};
+
+    ///Writes `content` to a file named `name` in the temporary folder, loads [EnemyCatalogue] from it and removes it.
+    fn load(name: &str, content: &str) -> Result<EnemyCatalogue, CatalogueError> {
+        let path = std::env::temp_dir().join(name);
+        write(&path, content).unwrap();
+        let catalogue = EnemyCatalogue::load(path.to_str().unwrap());
+        remove_file(&path).unwrap();
+        catalogue
+    }
+
+    ///[EnemyDef] named `name` with given number of `frames` and `weight`.
+    fn def(name: &str, frames: usize, weight: u32) -> EnemyDef {
+        EnemyDef {
+            name: name.to_string(),
+            sheet: format!("{}.png", name),
+            tile: (10., 10.),
+            frames,
+            colliders: vec![ColliderPart {
+                shape: Shape::Ball(5.),
+                offset: (0., 0.),
+                angle: 0.,
+            }],
+            damaging: true,
+            sensor: false,
+            gravity: None,
+            fall_speed: 1.,
+            weight,
+        }
+    }
+
+    ///Change making an [EnemyDef] invalid.
+    type Change = fn(&mut EnemyDef);
+
+    ///[EnemyCatalogue] made of given kinds, without atlases.
+    fn catalogue(kinds: Vec<EnemyDef>) -> EnemyCatalogue {
+        EnemyCatalogue {
+            kinds,
+            atlases: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn shipped_file_loads() {
+        let catalogue = EnemyCatalogue::load(CATALOGUE_FILE).unwrap();
+        assert_eq!(catalogue.kinds.len(), 6);
+        assert_eq!(catalogue.sprites(), 13);
+        assert!(catalogue.kinds.iter().all(|kind| kind.validate().is_ok()));
+    }
+
+    #[test]
+    fn missing_file_is_io_error() {
+        let error = EnemyCatalogue::load("./no_such_enemies.ron").unwrap_err();
+        assert!(matches!(error, CatalogueError::Io { .. }));
+    }
+
+    #[test]
+    fn unknown_field_is_parse_error() {
+        let error = load(
+            "pupik_unknown_enemies.ron",
+            "[\n    (\n        name: \"a\",\n        colour: 1,\n    ),\n]",
+        )
+        .unwrap_err();
+        match &error {
+            CatalogueError::Parse { error, .. } => assert_eq!(error.position.line, 4),
+            _ => panic!("expected a parse error, got {:?}", error),
+        }
+    }
+
+    #[test]
+    fn invalid_value_names_enemy_and_field() {
+        let error = load(
+            "pupik_invalid_enemies.ron",
+            "[(name: \"brick\", sheet: \"brick.png\", tile: (10.0, 10.0), frames: 0, \
+             colliders: [(shape: Ball(5.0))])]",
+        )
+        .unwrap_err();
+        match &error {
+            CatalogueError::Invalid { enemy, field, .. } => {
+                assert_eq!(enemy, "brick");
+                assert_eq!(*field, "frames");
+            }
+            _ => panic!("expected an invalid value, got {:?}", error),
+        }
+        assert!(error.to_string().contains("`frames` of brick"));
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        let cases: [(Change, &str); 6] = [
+            (|def| def.tile = (0., 10.), "tile"),
+            (|def| def.colliders.clear(), "colliders"),
+            (
+                |def| def.colliders[0].shape = Shape::Cuboid(5., -1.),
+                "colliders",
+            ),
+            (|def| def.gravity = Some(f32::NAN), "gravity"),
+            (|def| def.fall_speed = -1., "fall_speed"),
+            (|def| def.fall_speed = f32::INFINITY, "fall_speed"),
+        ];
+        for (change, field) in cases {
+            let mut kind = def("a", 1, 1);
+            change(&mut kind);
+            assert_eq!(kind.validate().unwrap_err().0, field);
+        }
+    }
+
+    #[test]
+    fn zero_weights_are_empty() {
+        let error = load(
+            "pupik_empty_enemies.ron",
+            "[(name: \"a\", sheet: \"a.png\", tile: (10.0, 10.0), frames: 1, \
+             colliders: [(shape: Ball(5.0))], weight: 0)]",
+        )
+        .unwrap_err();
+        assert!(matches!(error, CatalogueError::Empty { .. }));
+        let error = load("pupik_no_enemies.ron", "[]").unwrap_err();
+        assert!(matches!(error, CatalogueError::Empty { .. }));
+    }
+
+    #[test]
+    fn pick_respects_weights() {
+        let catalogue = catalogue(vec![def("a", 1, 2), def("b", 1, 0), def("c", 1, 1)]);
+        let picks: Vec<usize> = (0..6).map(|roll| catalogue.pick(roll)).collect();
+        assert_eq!(picks, [0, 0, 2, 0, 0, 2]);
+    }
+
+    #[test]
+    fn variants_are_numbered_kind_by_kind() {
+        let catalogue = catalogue(vec![def("a", 2, 1), def("b", 1, 1), def("c", 3, 1)]);
+        assert_eq!(catalogue.sprites(), 6);
+        let variants: Vec<(usize, usize)> =
+            (0..8).map(|variant| catalogue.variant(variant)).collect();
+        assert_eq!(
+            variants,
+            [
+                (0, 0),
+                (0, 1),
+                (1, 0),
+                (2, 0),
+                (2, 1),
+                (2, 2),
+                (0, 0),
+                (0, 1)
+            ]
+        );
+    }
+}
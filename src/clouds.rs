@@ -1,5 +1,9 @@
-use crate::{falling::FallTimer, speed::Speed, CloudSheet};
+use crate::{
+    falling::FallTimer, loading::Atlases, pause::PauseState, playfield::Playfield, rng::GameRng,
+    speed::Speed,
+};
 use bevy::prelude::*;
+use rand::Rng;
 
 ///[Plugin] taking care of background [Cloud] functionalities
 pub struct CloudPlugin;
@@ -10,36 +14,41 @@ pub struct Cloud;
 
 impl Plugin for CloudPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, spawn_clouds);
+        app.add_systems(Update, spawn_clouds.run_if(in_state(PauseState::Running)));
     }
 }
 
 ///Labels clouds, [entities](Entity) with no collision and low z coordinate
 /// # Arguments
 /// * `commands` - [Commands].
-/// * `cloud_sheet` - [Resource] containing handle for cloud [TextureAtlas].
+/// * `atlases` - [Atlases], containing cloud [TextureAtlas].
 /// * `time` - [Time]. Used to spawn clouds regularly.
 /// * `speed` - [Speed]. Used to spawn clouds more often once speed gets high.
+/// * `rng` - [GameRng], clouds use its cosmetic stream.
+/// * `playfield` - [Playfield].
 fn spawn_clouds(
     mut commands: Commands,
-    cloud_sheet: Res<CloudSheet>,
+    atlases: Res<Atlases>,
     time: Res<Time>,
     speed: Res<Speed>,
+    mut rng: ResMut<GameRng>,
+    playfield: Res<Playfield>,
 ) {
     if time.elapsed_seconds() % (0.5 / speed.speed) < time.delta_seconds() {
-        let scale_num: f32 = rand::random::<f32>() / 2.;
-        let x_num = rand::random::<f32>() - 0.5;
-        let sprite_num: usize = rand::random();
-        //let rotation_num = rand::random::<f32>(); <- maybe with better cloud textures
+        let rng = &mut rng.cosmetic;
+        let scale_num: f32 = rng.gen::<f32>() / 2.;
+        let x_num = rng.gen::<f32>() - 0.5;
+        let sprite_num: usize = rng.gen();
+        //let rotation_num = rng.gen::<f32>(); <- maybe with better cloud textures
         let sprite = TextureAtlasSprite::new(sprite_num % 8);
         let cloud = commands
             .spawn(SpriteSheetBundle {
                 sprite,
-                texture_atlas: cloud_sheet.0.clone(),
+                texture_atlas: atlases.get("clouds"),
                 transform: Transform {
                     translation: Vec3::new(
-                        x_num * 1920. / 3.,
-                        600.,
+                        x_num * playfield.width,
+                        playfield.spawn_y(),
                         100.0 + time.elapsed_seconds() % 200.,
                     ),
                     scale: Vec3::splat(0.3 + scale_num * scale_num), //Bigger chance for smaller clouds looks and runs better
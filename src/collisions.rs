@@ -1,8 +1,16 @@
 use crate::{
+    audio::SfxEvent,
     energybars::EnergyBar,
+    fruit::Fruit,
+    loading::Atlases,
+    map_layout::Enemy,
     plane::{create_plane, PlaneSensor},
-    player::{Hidden, Player, ScreenShaker},
-    GameState, PlanesSheet, Settings,
+    player::{damage_sprite, Hidden, Player, ScreenShaker},
+    playfield::Playfield,
+    rng::GameRng,
+    score::Score,
+    versus::{PlayerResult, PlayerResults},
+    GameState, GameplaySet, Settings,
 };
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
@@ -14,38 +22,50 @@ use bevy_rapier2d::prelude::*;
 pub struct CollPlugin;
 
 ///Labels an [Entity] which can damage the player by collision.
+/// # Fields
+/// * `0` - Type of the enemy dealing the damage. See [Enemy].
 #[derive(Component)]
-pub struct Damaging;
+pub struct Damaging(pub Enemy);
 
 impl Plugin for CollPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
-            Update,
-            (manage_special_collisions, deal_damage).run_if(in_state(GameState::Game)),
+            FixedUpdate,
+            (manage_special_collisions, deal_damage).in_set(GameplaySet::Collisions),
         );
     }
 }
 
-/// Takes care of all collisions with [entities](Entity) that have a unique special effect. It is run on update in the [GameState::Game].
+/// Takes care of all collisions with [entities](Entity) that have a unique special effect. It is run every gameplay tick in the [GameState::Game].
 /// # Arguments
 /// * `commands` - [Commands].
 /// * `events` - Reads [CollisionEvent]s.
 /// * `player_q` - [Query] for [entities](Entity) with the [Player] component.
 /// * `plane_sensor_q` - [Query] for [entities](Entity) with the [PlaneSensor] component.
 /// * `energybar_q` - [Query] for [entities](Entity) with the [EnergyBar] component.
-/// * `plane_texture` - [Resource] containing handle for plane [TextureAtlas].
+/// * `fruit_q` - [Query] for [entities](Entity) with the [Fruit] component.
+/// * `atlases` - [Atlases], containing plane [TextureAtlas].
+/// * `rng` - [GameRng], planes use its gameplay stream.
+/// * `settings` - [Settings], used to access the `energy_pickup` and `max_hp` fields.
+/// * `playfield` - [Playfield].
+/// * `sfx` - Sends [SfxEvent]s.
 fn manage_special_collisions(
     mut commands: Commands,
     mut events: EventReader<CollisionEvent>,
-    mut player_q: Query<&mut Hidden, With<Player>>,
+    mut player_q: Query<(&mut Player, &mut Hidden, &mut TextureAtlasSprite), With<Player>>,
     plane_sensor_q: Query<(&PlaneSensor, &Transform), With<PlaneSensor>>,
     energybar_q: Query<&EnergyBar, With<EnergyBar>>,
-    plane_texture: Res<PlanesSheet>,
+    fruit_q: Query<&Fruit, With<Fruit>>,
+    atlases: Res<Atlases>,
+    mut rng: ResMut<GameRng>,
+    settings: Res<Settings>,
+    playfield: Res<Playfield>,
+    mut sfx: EventWriter<SfxEvent>,
 ) {
     for event in events.iter() {
         if let CollisionEvent::Started(handle1, handle2, _) = event {
             //properly name handles
-            let (_player, thing) = if let Ok(_player) = player_q.get(*handle1) {
+            let (player_entity, thing) = if let Ok(_player) = player_q.get(*handle1) {
                 (handle1, handle2)
             } else {
                 (handle2, handle1)
@@ -56,24 +76,34 @@ fn manage_special_collisions(
                     plane_sensor.dir,
                     transform.translation.y,
                     &mut commands,
-                    &plane_texture.0,
+                    &mut rng.gameplay,
+                    &playfield,
+                    &atlases.get("planes"),
                 );
                 commands.entity(*thing).despawn();
+                sfx.send(SfxEvent::PlaneSpawn);
             } else if let Ok(_bar) = energybar_q.get(*thing) {
-                if let Ok(mut player) = player_q.get_single_mut() {
-                    if player.energy > 40. {
-                        player.energy = 100.;
-                    } else {
-                        player.energy += 60.;
+                if let Ok((_, mut hidden, _)) = player_q.get_mut(*player_entity) {
+                    hidden.energy = (hidden.energy + settings.energy_pickup).min(100.);
+                };
+                commands.entity(*thing).despawn();
+                sfx.send(SfxEvent::Pickup);
+            } else if let Ok(_fruit) = fruit_q.get(*thing) {
+                if let Ok((mut player, hidden, mut sprite)) = player_q.get_mut(*player_entity) {
+                    if player.hp < settings.max_hp {
+                        player.hp += 1;
+                        sprite.index = damage_sprite(player.hp, hidden.hidden);
                     }
                 };
                 commands.entity(*thing).despawn();
+                sfx.send(SfxEvent::Pickup);
             }
         }
     }
 }
 
-/// Takes care of all collisions with [entities](Entity) that have a unique special effect. It is run on update in the [GameState::Game].
+/// Takes care of all collisions with [Damaging] [entities](Entity), remembering the type of the last one that hit [Player]. It is run every gameplay tick in the [GameState::Game].
+/// A [Player] who runs out of hp gets his [PlayerResult]. Once every [Player] has one, the game ends, until then the dead ones are despawned.
 /// # Arguments
 /// * `commands` - [Commands].
 /// * `events` - Reads [CollisionEvent]s.
@@ -81,18 +111,25 @@ fn manage_special_collisions(
 /// * `damaging_q` - [Query] for [entities](Entity) with the [Damaging] component.
 /// * `state` - Resource containing [State]. This game's states are defined in the [GameState] enum.
 /// * `settings` - [Resource] containing [Settings].
+/// * `score` - [Score], stored in [PlayerResult].
+/// * `results` - [PlayerResults].
+/// * `sfx` - Sends [SfxEvent]s.
 fn deal_damage(
     mut commands: Commands,
     mut events: EventReader<CollisionEvent>,
-    mut player_q: Query<(&mut Player, &mut Hidden), With<Player>>,
+    mut player_q: Query<(Entity, &mut Player, &mut Hidden), With<Player>>,
     damaging_q: Query<&Damaging, With<Damaging>>,
     mut next: ResMut<NextState<GameState>>,
     settings: Res<Settings>,
+    score: Res<Score>,
+    mut results: ResMut<PlayerResults>,
+    mut sfx: EventWriter<SfxEvent>,
 ) {
+    let mut died = false;
     for event in events.iter() {
         if let CollisionEvent::Started(handle1, handle2, _) = event {
             //identify handles
-            let ((mut player, mut hidden), damaging_handle) =
+            let ((_, mut player, mut hidden), damaging_handle) =
                 if let Ok(player) = player_q.get_mut(*handle1) {
                     (player, handle2)
                 } else {
@@ -104,9 +141,11 @@ fn deal_damage(
                     )
                 };
 
-            if let Ok(_dmging) = damaging_q.get(*damaging_handle) {
+            if let Ok(damaging) = damaging_q.get(*damaging_handle) {
                 if !hidden.hit {
                     player.hp -= 1;
+                    player.hits += 1;
+                    player.last_hit = Some(damaging.0);
                     hidden.hit = true;
                     hidden.hit_energy = settings.hit_resistence;
 
@@ -114,14 +153,34 @@ fn deal_damage(
                         shakes: settings.shakes * 2 - 1, //must be odd to not shift camera
                     });
 
-                    if player.hp < 0 {
-                        next.set(GameState::EndScreen); /*
-                                                        state
-                                                            .set(GameState::EndScreen)
-                                                            .expect("Unexpected state set error."); */
+                    if player.hp < 0 && results.0[player.index].is_none() {
+                        results.0[player.index] = Some(PlayerResult {
+                            score: score.score,
+                            hits: player.hits,
+                            last_hit: player.last_hit,
+                        });
+                        died = true;
+                        sfx.send(SfxEvent::Death);
+                    } else {
+                        sfx.send(SfxEvent::Hit);
                     }
                 }
             }
         }
     }
+    if !died {
+        return;
+    }
+    if player_q
+        .iter()
+        .all(|(_, player, _)| results.0[player.index].is_some())
+    {
+        next.set(GameState::EndScreen);
+    } else {
+        for (entity, player, _) in &player_q {
+            if results.0[player.index].is_some() {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
 }
@@ -0,0 +1,417 @@
+use crate::{replay::PendingInputs, versus::GameMode};
+use bevy::input::{mouse::MouseMotion, InputSystem};
+use bevy::prelude::*;
+use bevy_pkv::PkvStore;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::Debug;
+
+///[PkvStore] key [Controls] are stored under.
+pub const CONTROLS_KEY: &str = "controls";
+
+///[Plugin] filling [PendingInputs] from the [InputDevice]s picked in [Controls].
+pub struct ControlsPlugin;
+
+///Device a player controls the unicorn with. Only the picked ones are read.
+/// * `Mouse` - Mouse movement moves, the left button hides.
+/// * `Keyboard` - Bound keys move and hide, WASD or arrows and space by default.
+/// * `Gamepad` - The left stick or the d-pad moves, a bound button hides.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum InputDevice {
+    #[default]
+    Mouse,
+    Keyboard,
+    Gamepad,
+}
+
+impl InputDevice {
+    ///Every [InputDevice].
+    pub const ALL: [InputDevice; 3] = [
+        InputDevice::Mouse,
+        InputDevice::Keyboard,
+        InputDevice::Gamepad,
+    ];
+
+    ///Name shown in menus.
+    pub fn name(self) -> &'static str {
+        match self {
+            InputDevice::Mouse => "Mouse",
+            InputDevice::Keyboard => "Keyboard",
+            InputDevice::Gamepad => "Gamepad",
+        }
+    }
+}
+
+///Something the player does, which can be bound to keys.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Hide,
+}
+
+impl Action {
+    ///Every [Action], in the order used by [Controls] `keys`.
+    pub const ALL: [Action; 5] = [
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Hide,
+    ];
+
+    ///Name shown in the controls screen.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Up => "Up",
+            Action::Down => "Down",
+            Action::Left => "Left",
+            Action::Right => "Right",
+            Action::Hide => "Hide",
+        }
+    }
+}
+
+///Keys or buttons which can be bound in [Controls].
+pub trait Bindable: Copy + PartialEq + Debug + 'static {
+    ///Every key or button which can be bound.
+    const ALL: &'static [Self];
+}
+
+impl Bindable for KeyCode {
+    //Escape is left out, it pauses the game
+    const ALL: &'static [KeyCode] = &[
+        KeyCode::A,
+        KeyCode::B,
+        KeyCode::C,
+        KeyCode::D,
+        KeyCode::E,
+        KeyCode::F,
+        KeyCode::G,
+        KeyCode::H,
+        KeyCode::I,
+        KeyCode::J,
+        KeyCode::K,
+        KeyCode::L,
+        KeyCode::M,
+        KeyCode::N,
+        KeyCode::O,
+        KeyCode::P,
+        KeyCode::Q,
+        KeyCode::R,
+        KeyCode::S,
+        KeyCode::T,
+        KeyCode::U,
+        KeyCode::V,
+        KeyCode::W,
+        KeyCode::X,
+        KeyCode::Y,
+        KeyCode::Z,
+        KeyCode::Key1,
+        KeyCode::Key2,
+        KeyCode::Key3,
+        KeyCode::Key4,
+        KeyCode::Key5,
+        KeyCode::Key6,
+        KeyCode::Key7,
+        KeyCode::Key8,
+        KeyCode::Key9,
+        KeyCode::Key0,
+        KeyCode::Up,
+        KeyCode::Down,
+        KeyCode::Left,
+        KeyCode::Right,
+        KeyCode::Space,
+        KeyCode::Return,
+        KeyCode::Tab,
+        KeyCode::Back,
+        KeyCode::ShiftLeft,
+        KeyCode::ShiftRight,
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::AltLeft,
+        KeyCode::AltRight,
+        KeyCode::Comma,
+        KeyCode::Period,
+        KeyCode::Slash,
+        KeyCode::Semicolon,
+    ];
+}
+
+impl Bindable for GamepadButtonType {
+    const ALL: &'static [GamepadButtonType] = &[
+        GamepadButtonType::South,
+        GamepadButtonType::East,
+        GamepadButtonType::North,
+        GamepadButtonType::West,
+        GamepadButtonType::C,
+        GamepadButtonType::Z,
+        GamepadButtonType::LeftTrigger,
+        GamepadButtonType::LeftTrigger2,
+        GamepadButtonType::RightTrigger,
+        GamepadButtonType::RightTrigger2,
+        GamepadButtonType::Select,
+        GamepadButtonType::Start,
+        GamepadButtonType::Mode,
+        GamepadButtonType::LeftThumb,
+        GamepadButtonType::RightThumb,
+        GamepadButtonType::DPadUp,
+        GamepadButtonType::DPadDown,
+        GamepadButtonType::DPadLeft,
+        GamepadButtonType::DPadRight,
+    ];
+}
+
+///A bound key or button. Saved by its name, so [Controls] can be stored without bevy's `serialize` feature.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Bound<T: Bindable>(pub T);
+
+impl<T: Bindable> Serialize for Bound<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:?}", self.0))
+    }
+}
+
+impl<'de, T: Bindable> Deserialize<'de> for Bound<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        T::ALL
+            .iter()
+            .find(|bindable| format!("{:?}", bindable) == name)
+            .map(|bindable| Bound(*bindable))
+            .ok_or_else(|| D::Error::custom(format!("unknown binding \"{}\"", name)))
+    }
+}
+
+///How the players control their unicorns. Stored in [PkvStore] under [CONTROLS_KEY], changed in the controls screen.
+/// # Fields
+/// * `device` - [InputDevice] of the first player.
+/// * `second_device` - [InputDevice] of the second player in [GameMode::Local].
+/// * `keys` - Two keys for every [Action], in the order of [Action::ALL]. If both players use the keyboard,
+/// the first player uses the first keys and the second player the second ones.
+/// * `gamepad_hide` - Gamepad button used to hide.
+/// * `keyboard_speed` - How far held movement keys move the unicorn per second, in the same units as mouse movement.
+/// * `gamepad_speed` - How far a fully tilted stick moves the unicorn per second, in the same units as mouse movement.
+/// * `sensitivity` - Multiplier of movement of every [InputDevice], changed in the options screen. Applied when input
+/// is collected, so replays keep the movement they were recorded with.
+#[derive(Resource, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Controls {
+    pub device: InputDevice,
+    pub second_device: InputDevice,
+    pub keys: [[Option<Bound<KeyCode>>; 2]; 5],
+    pub gamepad_hide: Bound<GamepadButtonType>,
+    pub keyboard_speed: f32,
+    pub gamepad_speed: f32,
+    pub sensitivity: f32,
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        let keys = |first, second| [Some(Bound(first)), Some(Bound(second))];
+        Controls {
+            device: InputDevice::Mouse,
+            second_device: InputDevice::Keyboard,
+            keys: [
+                keys(KeyCode::W, KeyCode::Up),
+                keys(KeyCode::S, KeyCode::Down),
+                keys(KeyCode::A, KeyCode::Left),
+                keys(KeyCode::D, KeyCode::Right),
+                keys(KeyCode::Space, KeyCode::Return),
+            ],
+            gamepad_hide: Bound(GamepadButtonType::South),
+            keyboard_speed: 750.,
+            gamepad_speed: 750.,
+            sensitivity: 1.,
+        }
+    }
+}
+
+impl Controls {
+    ///Loads [Controls] from [PkvStore], default ones if none are stored yet.
+    pub fn load(pkv: &PkvStore) -> Controls {
+        pkv.get::<Controls>(CONTROLS_KEY).unwrap_or_default()
+    }
+
+    ///Stores [Controls] in [PkvStore].
+    pub fn save(&self, pkv: &mut PkvStore) {
+        if let Err(err) = pkv.set(CONTROLS_KEY, self) {
+            warn!("Failed to store controls: {}", err);
+        }
+    }
+
+    ///[InputDevice] of the player with given index.
+    pub fn device_of(&self, index: usize) -> InputDevice {
+        if index == 0 {
+            self.device
+        } else {
+            self.second_device
+        }
+    }
+
+    ///Sets [InputDevice] of the player with given index.
+    pub fn set_device(&mut self, index: usize, device: InputDevice) {
+        if index == 0 {
+            self.device = device;
+        } else {
+            self.second_device = device;
+        }
+    }
+
+    ///Indexes of the players in `mode` who use `device`.
+    pub fn players_on(
+        &self,
+        device: InputDevice,
+        mode: GameMode,
+    ) -> impl Iterator<Item = usize> + '_ {
+        (0..mode.players()).filter(move |index| self.device_of(*index) == device)
+    }
+
+    ///Keys bound to `action`. If `column` is [Some], only the keys in that column, see [Controls] `keys`.
+    pub fn keys(
+        &self,
+        action: Action,
+        column: Option<usize>,
+    ) -> impl Iterator<Item = KeyCode> + '_ {
+        self.keys[action as usize]
+            .iter()
+            .enumerate()
+            .filter(move |(i, _)| column.is_none_or(|column| column == *i))
+            .filter_map(|(_, key)| key.map(|key| key.0))
+    }
+}
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Controls>().add_systems(
+            PreUpdate,
+            (
+                collect_mouse.run_if(device_in_use(InputDevice::Mouse)),
+                collect_keyboard.run_if(device_in_use(InputDevice::Keyboard)),
+                collect_gamepad.run_if(device_in_use(InputDevice::Gamepad)),
+            )
+                .after(InputSystem),
+        );
+    }
+}
+
+///Run condition which is true while any player of the current [GameMode] uses `device`.
+/// # Arguments
+/// * `device` - [InputDevice].
+fn device_in_use(device: InputDevice) -> impl Fn(Res<Controls>, Res<GameMode>) -> bool {
+    move |controls: Res<Controls>, mode: Res<GameMode>| {
+        controls.players_on(device, *mode).next().is_some()
+    }
+}
+
+///Collects mouse input every frame into [PendingInputs] of the players using the mouse.
+/// # Arguments
+/// * `pending` - [PendingInputs].
+/// * `motion` - Reads [MouseMotion] events.
+/// * `buttons` - Mouse input.
+/// * `controls` - [Controls].
+/// * `mode` - [GameMode].
+fn collect_mouse(
+    mut pending: ResMut<PendingInputs>,
+    mut motion: EventReader<MouseMotion>,
+    buttons: Res<Input<MouseButton>>,
+    controls: Res<Controls>,
+    mode: Res<GameMode>,
+) {
+    let delta: Vec2 = motion.iter().map(|event| event.delta).sum();
+    for index in controls.players_on(InputDevice::Mouse, *mode) {
+        let pending = &mut pending.0[index];
+        pending.add_motion(delta * controls.sensitivity);
+        pending.press(
+            buttons.pressed(MouseButton::Left),
+            buttons.just_pressed(MouseButton::Left),
+        );
+    }
+}
+
+///Collects keyboard input every frame into [PendingInputs] of the players using the keyboard.
+/// # Arguments
+/// * `pending` - [PendingInputs].
+/// * `keys` - Keyboard input.
+/// * `controls` - [Controls].
+/// * `mode` - [GameMode].
+/// * `time` - [Time].
+fn collect_keyboard(
+    mut pending: ResMut<PendingInputs>,
+    keys: Res<Input<KeyCode>>,
+    controls: Res<Controls>,
+    mode: Res<GameMode>,
+    time: Res<Time>,
+) {
+    let shared = controls.players_on(InputDevice::Keyboard, *mode).count() > 1;
+    for index in controls.players_on(InputDevice::Keyboard, *mode) {
+        let column = shared.then_some(index);
+        let held =
+            |action: Action| f32::from(u8::from(keys.any_pressed(controls.keys(action, column))));
+        //Mouse movement grows downwards
+        let direction = Vec2::new(
+            held(Action::Right) - held(Action::Left),
+            held(Action::Down) - held(Action::Up),
+        )
+        .normalize_or_zero();
+        let pending = &mut pending.0[index];
+        pending.add_motion(
+            direction * controls.keyboard_speed * controls.sensitivity * time.delta_seconds(),
+        );
+        pending.press(
+            keys.any_pressed(controls.keys(Action::Hide, column)),
+            keys.any_just_pressed(controls.keys(Action::Hide, column)),
+        );
+    }
+}
+
+///Collects gamepad input every frame into [PendingInputs] of the players using a gamepad.
+/// The first of them uses the first connected gamepad, the second one the second gamepad.
+/// # Arguments
+/// * `pending` - [PendingInputs].
+/// * `gamepads` - [Gamepads].
+/// * `axes` - Gamepad stick input.
+/// * `buttons` - Gamepad button input.
+/// * `controls` - [Controls].
+/// * `mode` - [GameMode].
+/// * `time` - [Time].
+fn collect_gamepad(
+    mut pending: ResMut<PendingInputs>,
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    buttons: Res<Input<GamepadButton>>,
+    controls: Res<Controls>,
+    mode: Res<GameMode>,
+    time: Res<Time>,
+) {
+    for (nth, index) in controls.players_on(InputDevice::Gamepad, *mode).enumerate() {
+        let pending = &mut pending.0[index];
+        let Some(gamepad) = gamepads.iter().nth(nth) else {
+            pending.press(false, false);
+            continue;
+        };
+        let axis = |axis_type| axes.get(GamepadAxis::new(gamepad, axis_type)).unwrap_or(0.);
+        let held = |button_type| {
+            f32::from(u8::from(
+                buttons.pressed(GamepadButton::new(gamepad, button_type)),
+            ))
+        };
+        let stick = Vec2::new(
+            axis(GamepadAxisType::LeftStickX) + held(GamepadButtonType::DPadRight)
+                - held(GamepadButtonType::DPadLeft),
+            axis(GamepadAxisType::LeftStickY) + held(GamepadButtonType::DPadUp)
+                - held(GamepadButtonType::DPadDown),
+        )
+        .clamp_length_max(1.);
+        //Mouse movement grows downwards, stick movement upwards
+        pending.add_motion(
+            Vec2::new(stick.x, -stick.y)
+                * controls.gamepad_speed
+                * controls.sensitivity
+                * time.delta_seconds(),
+        );
+        let hide = GamepadButton::new(gamepad, controls.gamepad_hide.0);
+        pending.press(buttons.pressed(hide), buttons.just_pressed(hide));
+    }
+}
@@ -0,0 +1,469 @@
+use crate::{
+    controls::{Action, Bindable, Bound, Controls, InputDevice},
+    playfield::PlayfieldPanel,
+    preferences::{Language, Preferences},
+    versus::MAX_PLAYERS,
+    GameState,
+};
+use bevy::prelude::*;
+use bevy_pkv::PkvStore;
+
+///[Plugin] taking care of the screen where [Controls] are picked and rebound.
+pub struct ControlsScreenPlugin;
+
+///Labels all [entities](Entity) which are spawned on enter to [GameState::Controls] and despawn on exit.
+#[derive(Component)]
+struct ControlsScreen;
+
+///Button in the top left corner that takes player back to the main menu. When clicked, [GameState] changes
+/// to [GameState::MainMenu]. When hovered, its background color changes until unhovered.
+#[derive(Component)]
+struct ControlsBackButton;
+
+///Button picking an [InputDevice] of the player with given index. The picked one is highlighted.
+#[derive(Component)]
+struct DeviceButton(usize, InputDevice);
+
+///A single binding which can be changed on the controls screen.
+/// * `Key` - One of the two keys of an [Action].
+/// * `GamepadHide` - Gamepad button used to hide.
+#[derive(Clone, Copy, PartialEq)]
+enum Binding {
+    Key(Action, usize),
+    GamepadHide,
+}
+
+///Button showing a [Binding]. When clicked, the next pressed key or button replaces it.
+#[derive(Component)]
+struct BindingButton(Binding);
+
+///Labels the [Text] of a [BindingButton].
+#[derive(Component)]
+struct BindingLabel(Binding);
+
+///The [Binding] waiting for a key or button press, if any.
+#[derive(Resource, Default)]
+struct Rebinding(Option<Binding>);
+
+///Background color of a button.
+const BUTTON_COLOR: Color = Color::rgba(0., 0., 0., 0.1);
+///Background color of a hovered button.
+const HOVERED_COLOR: Color = Color::rgba(0., 0., 0., 0.7);
+///Background color of the picked [DeviceButton].
+const PICKED_COLOR: Color = Color::rgba(1.0, 0.8, 0.9, 0.5);
+
+impl Plugin for ControlsScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rebinding>()
+            .add_systems(Startup, load_controls)
+            .add_systems(OnEnter(GameState::Controls), spawn_controls_screen)
+            .add_systems(
+                Update,
+                (
+                    back_button_interaction,
+                    device_button_interaction,
+                    binding_button_interaction,
+                    capture_binding,
+                    refresh_controls_screen,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Controls)),
+            )
+            .add_systems(
+                OnExit(GameState::Controls),
+                (save_controls, despawn_controls_screen),
+            );
+    }
+}
+
+///Loads [Controls] at startup.
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `pkv` - [PkvStore].
+fn load_controls(mut commands: Commands, pkv: Res<PkvStore>) {
+    commands.insert_resource(Controls::load(&pkv));
+}
+
+///Stores [Controls] and cancels [Rebinding] on exit from [GameState::Controls].
+/// # Arguments
+/// * `controls` - [Controls].
+/// * `rebinding` - [Rebinding].
+/// * `pkv` - [PkvStore].
+fn save_controls(
+    controls: Res<Controls>,
+    mut rebinding: ResMut<Rebinding>,
+    mut pkv: ResMut<PkvStore>,
+) {
+    rebinding.0 = None;
+    controls.save(&mut pkv);
+}
+
+///Text shown on a [BindingButton].
+/// # Arguments
+/// * `binding` - [Binding].
+/// * `controls` - [Controls].
+/// * `rebinding` - [Rebinding].
+/// * `language` - [Language] of the text.
+fn binding_text(
+    binding: Binding,
+    controls: &Controls,
+    rebinding: &Rebinding,
+    language: Language,
+) -> String {
+    if rebinding.0 == Some(binding) {
+        return language.tr("Press...").to_string();
+    }
+    match binding {
+        Binding::Key(action, slot) => controls.keys[action as usize][slot]
+            .map_or("-".to_string(), |key| format!("{:?}", key.0)),
+        Binding::GamepadHide => format!("{:?}", controls.gamepad_hide.0),
+    }
+}
+
+///Spawns [ControlsScreen] with [ControlsBackButton], a [DeviceButton] for every player and [InputDevice] and
+/// a [BindingButton] for every [Binding].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `assets` - [AssetServer]. Used to load font.
+/// * `controls` - [Controls].
+/// * `rebinding` - [Rebinding].
+/// * `preferences` - [Preferences], texts are in its `language`.
+fn spawn_controls_screen(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    controls: Res<Controls>,
+    rebinding: Res<Rebinding>,
+    preferences: Res<Preferences>,
+) {
+    let language = preferences.language;
+    let font = assets.load("fonts\\Love_Letters.ttf");
+    let text_style = |font_size| TextStyle {
+        font: font.clone(),
+        font_size,
+        color: Color::rgb(0.9, 0.9, 0.9),
+    };
+    let button = |width: f32, height: f32| ButtonBundle {
+        style: Style {
+            width: Val::Px(width),
+            height: Val::Px(height),
+            margin: UiRect::axes(Val::Px(4.), Val::Px(2.)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        background_color: BUTTON_COLOR.into(),
+        ..default()
+    };
+    let row = || NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        ..default()
+    };
+    let heading = |text: &'static str| {
+        TextBundle::from_section(language.tr(text), text_style(35.)).with_style(Style {
+            margin: UiRect::top(Val::Px(8.)),
+            ..default()
+        })
+    };
+    let label = |text: &str| {
+        TextBundle::from_section(text, text_style(26.)).with_style(Style {
+            width: Val::Px(120.),
+            ..default()
+        })
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                margin: UiRect::all(Val::Auto),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::rgba(0.25, 0.15, 0.15, 0.7).into(), //Grey
+            ..default()
+        })
+        .insert(PlayfieldPanel)
+        .with_children(|parent| {
+            parent
+                .spawn(ButtonBundle {
+                    style: Style {
+                        width: Val::Px(250.0),
+                        height: Val::Px(65.0),
+                        margin: UiRect {
+                            left: Val::Px(10.),
+                            top: Val::Px(10.),
+                            ..default()
+                        },
+                        align_self: AlignSelf::FlexStart,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: BUTTON_COLOR.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(language.tr("Return to menu"), text_style(35.)));
+                })
+                .insert(ControlsBackButton);
+            parent.spawn(TextBundle::from_section(language.tr("Controls"), text_style(55.)));
+
+            parent.spawn(heading("Play with"));
+            for index in 0..MAX_PLAYERS {
+                parent.spawn(row()).with_children(|parent| {
+                    parent.spawn(label(&format!("{} {}", language.tr("Player"), index + 1)));
+                    for device in InputDevice::ALL {
+                        let mut button = button(160., 40.);
+                        if controls.device_of(index) == device {
+                            button.background_color = PICKED_COLOR.into();
+                        }
+                        parent
+                            .spawn(button)
+                            .with_children(|parent| {
+                                parent.spawn(TextBundle::from_section(
+                                    language.tr(device.name()),
+                                    text_style(30.),
+                                ));
+                            })
+                            .insert(DeviceButton(index, device));
+                    }
+                });
+            }
+
+            parent.spawn(heading("Keyboard"));
+            for action in Action::ALL {
+                parent.spawn(row()).with_children(|parent| {
+                    parent.spawn(label(language.tr(action.name())));
+                    for slot in 0..2 {
+                        let binding = Binding::Key(action, slot);
+                        parent
+                            .spawn(button(150., 34.))
+                            .with_children(|parent| {
+                                parent
+                                    .spawn(TextBundle::from_section(
+                                        binding_text(binding, &controls, &rebinding, language),
+                                        text_style(26.),
+                                    ))
+                                    .insert(BindingLabel(binding));
+                            })
+                            .insert(BindingButton(binding));
+                    }
+                });
+            }
+
+            parent.spawn(heading("Gamepad"));
+            parent.spawn(row()).with_children(|parent| {
+                parent.spawn(label(language.tr(Action::Hide.name())));
+                let binding = Binding::GamepadHide;
+                parent
+                    .spawn(button(150., 34.))
+                    .with_children(|parent| {
+                        parent
+                            .spawn(TextBundle::from_section(
+                                binding_text(binding, &controls, &rebinding, language),
+                                text_style(26.),
+                            ))
+                            .insert(BindingLabel(binding));
+                    })
+                    .insert(BindingButton(binding));
+            });
+
+            parent.spawn(
+                TextBundle::from_section(
+                    language.tr(
+                        "Click a binding and press a key or button.\nEscape cancels, Delete clears.\n\
+                        If both players use the keyboard, player 1 uses the left keys and player 2 the right ones.",
+                    ),
+                    text_style(20.),
+                )
+                .with_text_alignment(TextAlignment::Center)
+                .with_style(Style {
+                    margin: UiRect::top(Val::Px(15.)),
+                    ..default()
+                }),
+            );
+        })
+        .insert(ControlsScreen);
+}
+
+///Handles interactions with [ControlsBackButton].
+/// # Arguments
+/// * `interaction_query` - [Query] for [ControlsBackButton] and its [Interaction] when changed.
+/// * `next` - [NextState] of [GameState].
+fn back_button_interaction(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ControlsBackButton>),
+    >,
+    mut next: ResMut<NextState<GameState>>,
+) {
+    for (interaction, mut color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                next.set(GameState::MainMenu);
+            }
+            Interaction::Hovered => {
+                *color = HOVERED_COLOR.into();
+            }
+            Interaction::None => {
+                *color = BUTTON_COLOR.into();
+            }
+        }
+    }
+}
+
+///Handles interactions with [DeviceButton]s.
+/// # Arguments
+/// * `interaction_query` - [Query] for [DeviceButton]s and their [Interaction] when changed.
+/// * `controls` - [Controls].
+fn device_button_interaction(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor, &DeviceButton),
+        Changed<Interaction>,
+    >,
+    mut controls: ResMut<Controls>,
+) {
+    for (interaction, mut color, device) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                controls.set_device(device.0, device.1);
+            }
+            Interaction::Hovered => {
+                *color = HOVERED_COLOR.into();
+            }
+            Interaction::None => {
+                *color = if controls.device_of(device.0) == device.1 {
+                    PICKED_COLOR.into()
+                } else {
+                    BUTTON_COLOR.into()
+                };
+            }
+        }
+    }
+}
+
+///Handles interactions with [BindingButton]s.
+/// # Arguments
+/// * `interaction_query` - [Query] for [BindingButton]s and their [Interaction] when changed.
+/// * `rebinding` - [Rebinding].
+fn binding_button_interaction(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor, &BindingButton),
+        Changed<Interaction>,
+    >,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    for (interaction, mut color, binding) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                rebinding.0 = Some(binding.0);
+            }
+            Interaction::Hovered => {
+                *color = HOVERED_COLOR.into();
+            }
+            Interaction::None => {
+                *color = BUTTON_COLOR.into();
+            }
+        }
+    }
+}
+
+///Replaces the [Binding] in [Rebinding] with the next pressed key or gamepad button. A key bound to another [Action]
+/// is unbound from it first.
+/// # Arguments
+/// * `rebinding` - [Rebinding].
+/// * `controls` - [Controls].
+/// * `keys` - Keyboard input.
+/// * `gamepad_buttons` - Gamepad button input.
+fn capture_binding(
+    mut rebinding: ResMut<Rebinding>,
+    mut controls: ResMut<Controls>,
+    keys: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+) {
+    let Some(binding) = rebinding.0 else {
+        return;
+    };
+    if keys.just_pressed(KeyCode::Escape) {
+        rebinding.0 = None;
+        return;
+    }
+    match binding {
+        Binding::Key(action, slot) => {
+            if keys.just_pressed(KeyCode::Delete) {
+                controls.keys[action as usize][slot] = None;
+                rebinding.0 = None;
+                return;
+            }
+            let Some(key) = keys
+                .get_just_pressed()
+                .find(|key| KeyCode::ALL.contains(key))
+                .copied()
+            else {
+                return;
+            };
+            for bound in controls.keys.iter_mut().flatten() {
+                if *bound == Some(Bound(key)) {
+                    *bound = None;
+                }
+            }
+            controls.keys[action as usize][slot] = Some(Bound(key));
+            rebinding.0 = None;
+        }
+        Binding::GamepadHide => {
+            let Some(button) = gamepad_buttons
+                .get_just_pressed()
+                .find(|button| GamepadButtonType::ALL.contains(&button.button_type))
+            else {
+                return;
+            };
+            controls.gamepad_hide = Bound(button.button_type);
+            rebinding.0 = None;
+        }
+    }
+}
+
+///Updates [BindingLabel]s and [DeviceButton] colors once [Controls] or [Rebinding] change.
+/// # Arguments
+/// * `controls` - [Controls].
+/// * `rebinding` - [Rebinding].
+/// * `preferences` - [Preferences], texts are in its `language`.
+/// * `label_q` - [Query] for [BindingLabel]s.
+/// * `device_q` - [Query] for [DeviceButton]s.
+fn refresh_controls_screen(
+    controls: Res<Controls>,
+    rebinding: Res<Rebinding>,
+    preferences: Res<Preferences>,
+    mut label_q: Query<(&mut Text, &BindingLabel)>,
+    mut device_q: Query<(&mut BackgroundColor, &Interaction, &DeviceButton)>,
+) {
+    if !controls.is_changed() && !rebinding.is_changed() {
+        return;
+    }
+    for (mut text, label) in &mut label_q {
+        text.sections[0].value = binding_text(label.0, &controls, &rebinding, preferences.language);
+    }
+    for (mut color, interaction, device) in &mut device_q {
+        if *interaction == Interaction::None {
+            *color = if controls.device_of(device.0) == device.1 {
+                PICKED_COLOR.into()
+            } else {
+                BUTTON_COLOR.into()
+            };
+        }
+    }
+}
+
+///Despawns all [ControlsScreen] [entities](Entity).
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `query` - [Query] for [ControlsScreen].
+fn despawn_controls_screen(mut commands: Commands, query: Query<Entity, With<ControlsScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1,4 +1,4 @@
-use crate::GameState;
+use crate::{pause::PauseState, GameState};
 use bevy::prelude::*;
 use bevy::window::CursorGrabMode;
 use bevy::window::PrimaryWindow;
@@ -11,10 +11,15 @@ pub struct CursorPlugin;
 impl Plugin for CursorPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(GameState::Game), hide_cursor)
-            .add_systems(OnExit(GameState::Game), unhide_cursor);
+            .add_systems(OnExit(GameState::Game), unhide_cursor)
+            .add_systems(OnEnter(PauseState::Paused), unhide_cursor)
+            .add_systems(
+                OnExit(PauseState::Paused),
+                hide_cursor.run_if(in_state(GameState::Game)),
+            );
     }
 }
-/// Hides the cursor and locks it inside the game window. It is run on enter in the [GameState::Game].
+/// Hides the cursor and locks it inside the game window. It is run on enter in the [GameState::Game] and when the game is unpaused.
 /// # Arguments
 /// * `window` - [Resource] containing [Windows].
 fn hide_cursor(mut window: Query<&mut Window, With<PrimaryWindow>>) {
@@ -24,7 +29,7 @@ fn hide_cursor(mut window: Query<&mut Window, With<PrimaryWindow>>) {
     }
 }
 
-/// Unhides the cursor and unlocks it from the game window. It is run on exit in the [GameState::Game].
+/// Unhides the cursor and unlocks it from the game window. It is run on exit in the [GameState::Game] and when the game is paused.
 /// # Arguments
 /// * `window` - [Resource] containing [Windows].
 fn unhide_cursor(mut window: Query<&mut Window, With<PrimaryWindow>>) {
@@ -1,6 +1,14 @@
-use crate::{mainmenu::Flickering, text::Score, GameState};
+use crate::{
+    highscore::{prepare_entry, Leaderboard, PendingEntry, MAX_NAME_LENGTH},
+    lan::{GhostState, LanConnection},
+    mainmenu::Flickering,
+    playfield::PlayfieldPanel,
+    preferences::{Language, Preferences},
+    score::Score,
+    versus::{GameMode, PlayerResults, MAX_PLAYERS},
+    GameState,
+};
 use bevy::prelude::*;
-use bevy_pkv::PkvStore;
 
 /// [Plugin] taking care of the endsceen appearance. This plugin contains
 pub struct EndScreenPlugin;
@@ -13,43 +21,130 @@ struct EndScreen;
 #[derive(Component)]
 struct ContinueButton;
 
+///Labels the [Text] showing the name being typed for [PendingEntry].
+#[derive(Component)]
+struct NameText;
+
+///Labels a line of the summary of the game, see [refresh_summary].
+/// # Fields
+/// * `0` - Index of the line.
+#[derive(Component)]
+struct SummaryLine(usize);
+
+///Number of [SummaryLine]s, as many as the longest summary has.
+const SUMMARY_LINES: usize = MAX_PLAYERS + 1;
+
 impl Plugin for EndScreenPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::EndScreen), spawn_endscreen)
-            .add_systems(
-                Update,
-                continue_interaction.run_if(in_state(GameState::EndScreen)),
+        app.add_systems(
+            OnEnter(GameState::EndScreen),
+            spawn_endscreen.after(prepare_entry),
+        )
+        .add_systems(
+            Update,
+            (continue_interaction, type_name, refresh_summary)
+                .run_if(in_state(GameState::EndScreen)),
+        )
+        .add_systems(OnExit(GameState::EndScreen), despawn_endscreen);
+    }
+}
+
+///Lines of the end screen summing up a [GameMode::Local] game, score of every player and who won.
+/// # Arguments
+/// * `results` - [PlayerResults].
+/// * `players` - Number of players.
+/// * `language` - [Language] of the lines.
+pub fn versus_summary(results: &PlayerResults, players: usize, language: Language) -> Vec<String> {
+    let mut lines = results
+        .0
+        .iter()
+        .take(players)
+        .enumerate()
+        .map(|(index, result)| {
+            format!(
+                "{} {}: {}",
+                language.tr("Player"),
+                index + 1,
+                result.map_or(0., |result| result.score.max(0.).floor())
             )
-            .add_systems(OnExit(GameState::EndScreen), despawn_endscreen);
+        })
+        .collect::<Vec<String>>();
+    lines.push(match results.winner() {
+        Some(winner) => language
+            .tr("Player {} wins!")
+            .replace("{}", &(winner + 1).to_string()),
+        None => language.tr("It's a draw!").to_owned(),
+    });
+    lines
+}
+
+///Lines of the end screen summing up a [GameMode::Lan] race, own score and how the other player is doing.
+/// The race is decided once the other player died, even if they left afterwards.
+/// # Arguments
+/// * `score` - Own score.
+/// * `ghost` - Newest [GhostState] of the other player.
+/// * `peer_left` - Whether the other player left.
+/// * `language` - [Language] of the lines.
+pub fn lan_summary(
+    score: f32,
+    ghost: Option<GhostState>,
+    peer_left: bool,
+    language: Language,
+) -> Vec<String> {
+    let score = score.max(0.).floor();
+    let mut lines = Vec::from([format!("{} {}", language.tr("Score:"), score)]);
+    match ghost {
+        None | Some(GhostState { hp: 0.., .. }) if peer_left => {
+            lines.push(language.tr("Opponent left").to_owned())
+        }
+        None => lines.push(language.tr("Opponent never showed up").to_owned()),
+        Some(ghost) if ghost.hp >= 0 => {
+            lines.push(language.tr("Opponent is still flying").to_owned())
+        }
+        Some(ghost) => {
+            let opponent = ghost.score.max(0.).floor();
+            lines.push(format!("{} {}", language.tr("Opponent:"), opponent));
+            lines.push(
+                language
+                    .tr(if score > opponent {
+                        "You win!"
+                    } else if score < opponent {
+                        "Opponent wins!"
+                    } else {
+                        "It's a draw!"
+                    })
+                    .to_owned(),
+            );
+        }
     }
+    lines
 }
 
-/// Takes care of all collisions with [entities](Entity) that have a unique special effect. It is run on update in the [GameState::Game].
+/// Spawns the end screen with empty [SummaryLine]s, filled by [refresh_summary]. If the run made it to [Leaderboard],
+/// the player is asked for a name.
 /// # Arguments
 /// * `commands` - [Commands].
 /// * `assets` - [AssetServer]. Used to load font.
-/// * `score` - [Score].
+/// * `pending` - [PendingEntry].
+/// * `preferences` - [Preferences], texts are in its `language`.
 fn spawn_endscreen(
     mut commands: Commands,
     assets: Res<AssetServer>,
-    score: Res<Score>,
-    pkv: ResMut<PkvStore>,
+    pending: Res<PendingEntry>,
+    preferences: Res<Preferences>,
 ) {
-    let highscore = pkv.get::<String>("highscore").unwrap();
+    let language = preferences.language;
     let font = assets.load("fonts\\Love_Letters.ttf");
     let score_text_style = TextStyle {
         font: font.clone(),
         font_size: 50.0,
         color: Color::WHITE,
     };
-    let score = score.score.floor().to_string();
 
     commands
         //Clickable background
         .spawn(ButtonBundle {
             style: Style {
-                height: Val::Px(1920.0 / 3. + 100.),
-                width: Val::Px(660.0),
                 // center button and children
                 margin: UiRect::all(Val::Auto),
                 justify_content: JustifyContent::Center,
@@ -59,13 +154,14 @@ fn spawn_endscreen(
             background_color: Color::rgba(0.25, 0.15, 0.15, 0.7).into(), //Grey
             ..default()
         })
+        .insert(PlayfieldPanel)
         .with_children(|parent| {
             //"Click to continue" text
             parent
                 .spawn(
                     TextBundle {
                         text: Text::from_section(
-                            "Left click to continue",
+                            language.tr("Left click to continue"),
                             score_text_style.clone(),
                         )
                         .with_alignment(TextAlignment::Center),
@@ -82,33 +178,53 @@ fn spawn_endscreen(
                 .insert(Flickering {
                     timer: Timer::from_seconds(0.6, TimerMode::Repeating),
                 });
-            //Achieved score
-            parent.spawn((TextBundle {
-                text: Text::from_section("Score: ".to_owned() + &score, score_text_style.clone())
-                    .with_alignment(TextAlignment::Center),
-                ..default()
+            //Achieved score and highscore
+            for i in 0..SUMMARY_LINES {
+                parent.spawn((
+                    TextBundle {
+                        text: Text::from_section("", score_text_style.clone())
+                            .with_alignment(TextAlignment::Center),
+                        ..default()
+                    }
+                    .with_style(Style {
+                        position_type: PositionType::Absolute,
+                        align_self: AlignSelf::Center,
+                        top: Val::Percent(20. + 10. * i as f32),
+                        ..default()
+                    }),
+                    SummaryLine(i),
+                ));
             }
-            .with_style(Style {
-                position_type: PositionType::Absolute,
-                align_self: AlignSelf::Center,
-                top: Val::Percent(20.),
-                ..default()
-            }),));
-            //highscore
-            parent.spawn((TextBundle {
-                text: Text::from_section(
-                    "Highscore: ".to_owned() + &highscore,
-                    score_text_style.clone(),
-                )
-                .with_alignment(TextAlignment::Center),
-                ..default()
+            if pending.position.is_some() {
+                //name entry
+                parent.spawn((TextBundle {
+                    text: Text::from_section(
+                        language.tr("New highscore! Type your name:"),
+                        score_text_style.clone(),
+                    )
+                    .with_alignment(TextAlignment::Center),
+                    ..default()
+                }
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    align_self: AlignSelf::Center,
+                    top: Val::Percent(45.),
+                    ..default()
+                }),));
+                parent
+                    .spawn((TextBundle {
+                        text: Text::from_section("_", score_text_style.clone())
+                            .with_alignment(TextAlignment::Center),
+                        ..default()
+                    }
+                    .with_style(Style {
+                        position_type: PositionType::Absolute,
+                        align_self: AlignSelf::Center,
+                        top: Val::Percent(55.),
+                        ..default()
+                    }),))
+                    .insert(NameText);
             }
-            .with_style(Style {
-                position_type: PositionType::Absolute,
-                align_self: AlignSelf::Center,
-                top: Val::Percent(30.),
-                ..default()
-            }),));
         })
         .insert(ContinueButton)
         .insert(EndScreen);
@@ -129,6 +245,90 @@ fn continue_interaction(
     }
 }
 
+///Fills the [SummaryLine]s with the score and highscore. In [GameMode::Local] the score of every player and the
+/// winner are shown instead, see [versus_summary], in [GameMode::Lan] the scores of both players, see [lan_summary].
+/// Runs every frame, so the [GameMode::Lan] race result follows the other player as they keep flying, die or leave.
+/// # Arguments
+/// * `score` - [Score].
+/// * `leaderboard` - [Leaderboard].
+/// * `mode` - [GameMode].
+/// * `results` - [PlayerResults].
+/// * `connection` - [LanConnection], exists in [GameMode::Lan].
+/// * `preferences` - [Preferences], texts are in its `language`.
+/// * `line_q` - [Query] for [SummaryLine]s.
+fn refresh_summary(
+    score: Res<Score>,
+    leaderboard: Res<Leaderboard>,
+    mode: Res<GameMode>,
+    results: Res<PlayerResults>,
+    connection: Option<Res<LanConnection>>,
+    preferences: Res<Preferences>,
+    mut line_q: Query<(&SummaryLine, &mut Text)>,
+) {
+    let language = preferences.language;
+    let lines = match *mode {
+        GameMode::Single => {
+            let highscore = leaderboard.best_score().max(score.score.max(0.) as u32);
+            Vec::from([
+                format!("{} {}", language.tr("Score:"), score.score.floor()),
+                format!("{} {}", language.tr("Highscore:"), highscore),
+            ])
+        }
+        GameMode::Local => versus_summary(&results, mode.players(), language),
+        GameMode::Lan => lan_summary(
+            score.score,
+            connection.as_ref().and_then(|connection| connection.ghost),
+            connection.is_none_or(|connection| connection.peer_left),
+            language,
+        ),
+    };
+    for (line, mut text) in &mut line_q {
+        let value = lines.get(line.0).map_or("", String::as_str);
+        if text.sections[0].value != value {
+            text.sections[0].value = value.to_owned();
+        }
+    }
+}
+
+///Edits the name of [PendingEntry] as the player types. Enter confirms it and goes to the main menu, like clicking [ContinueButton].
+/// # Arguments
+/// * `characters` - Reads [ReceivedCharacter] events.
+/// * `keys` - Keyboard input.
+/// * `pending` - [PendingEntry].
+/// * `name_q` - [Query] for [NameText].
+/// * `next` - [NextState] of [GameState].
+fn type_name(
+    mut characters: EventReader<ReceivedCharacter>,
+    keys: Res<Input<KeyCode>>,
+    mut pending: ResMut<PendingEntry>,
+    mut name_q: Query<&mut Text, With<NameText>>,
+    mut next: ResMut<NextState<GameState>>,
+) {
+    if pending.position.is_none() {
+        characters.clear();
+        return;
+    }
+    let name = &mut pending.name;
+    let mut changed = false;
+    for event in characters.iter() {
+        if !event.char.is_control() && name.chars().count() < MAX_NAME_LENGTH {
+            name.push(event.char);
+            changed = true;
+        }
+    }
+    if keys.just_pressed(KeyCode::Back) {
+        changed |= name.pop().is_some();
+    }
+    if keys.just_pressed(KeyCode::Return) {
+        next.set(GameState::MainMenu);
+    }
+    if changed {
+        for mut text in name_q.iter_mut() {
+            text.sections[0].value = name.clone() + "_";
+        }
+    }
+}
+
 /// Despawns all [entities](Entity) containing [EndScreen].
 /// # Arguments
 /// * `commands` - [Commands].
@@ -138,3 +338,48 @@ fn despawn_endscreen(mut commands: Commands, query: Query<Entity, With<EndScreen
         commands.entity(entity).despawn();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ghost(hp: i32, score: f32) -> Option<GhostState> {
+        Some(GhostState {
+            hp,
+            score,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn dead_opponent_is_ranked_even_after_leaving() {
+        assert_eq!(
+            lan_summary(120., ghost(-1, 80.), true, Language::English),
+            ["Score: 120", "Opponent: 80", "You win!"]
+        );
+        assert_eq!(
+            lan_summary(50., ghost(-1, 80.), false, Language::English),
+            ["Score: 50", "Opponent: 80", "Opponent wins!"]
+        );
+    }
+
+    #[test]
+    fn living_opponent() {
+        assert_eq!(
+            lan_summary(50., ghost(3, 80.), false, Language::English),
+            ["Score: 50", "Opponent is still flying"]
+        );
+        assert_eq!(
+            lan_summary(50., ghost(3, 80.), true, Language::English),
+            ["Score: 50", "Opponent left"]
+        );
+        assert_eq!(
+            lan_summary(50., None, true, Language::English),
+            ["Score: 50", "Opponent left"]
+        );
+        assert_eq!(
+            lan_summary(50., None, false, Language::English),
+            ["Score: 50", "Opponent never showed up"]
+        );
+    }
+}
@@ -1,6 +1,9 @@
-use crate::{falling::FallTimer, Object};
+use crate::{
+    falling::FallTimer, map_layout::ObjectParams, playfield::Playfield, walls::enemy_groups, Object,
+};
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
+use rand::{rngs::StdRng, Rng};
 
 ///Labels [entities](Entity) which when collided with give the player instantly some amount of energy.
 #[derive(Component)]
@@ -8,36 +11,45 @@ pub struct EnergyBar;
 
 /// Spawns an [EnergyBar].
 /// # Arguments
-/// * `x` - if [None], a random `x` within resolution is chosen.
-/// * `y` - if [None], it is set 100px above upper bound.
+/// * `x` - if [None], a random `x` within [Playfield] is chosen.
+/// * `y` - if [None], it is set to [Playfield::spawn_y], above the upper bound.
+/// * `params` - [ObjectParams], `rotation` and `fall_speed` are used.
 /// * `commands` - [Commands].
+/// * `rng` - Gameplay stream of [crate::rng::GameRng].
+/// * `playfield` - [Playfield].
 /// * `texture` - Handle for energybar [TextureAtlas]
 pub fn create_bar(
     x: Option<f32>,
     y: Option<f32>,
+    params: &ObjectParams,
     commands: &mut Commands,
+    rng: &mut StdRng,
+    playfield: &Playfield,
     texture: &Handle<TextureAtlas>,
 ) {
-    let random_num: usize = rand::random();
+    let random_num: usize = rng.gen();
     let sprite = TextureAtlasSprite::new(random_num % 3);
-    let x = x.unwrap_or((rand::random::<f32>() - 0.5) * (1920. / 3.));
-    let y = y.unwrap_or(600.);
+    let x = x.unwrap_or(playfield.random_x(rng));
+    let y = y.unwrap_or(playfield.spawn_y());
 
     let bar = commands
         .spawn(SpriteSheetBundle {
             sprite,
             texture_atlas: texture.clone(),
             transform: Transform {
-                translation: Vec3::new(x, y, 900.0 + rand::random::<f32>()),
+                translation: Vec3::new(x, y, 900.0 + rng.gen::<f32>()),
                 scale: Vec3::splat(0.6),
-                rotation: Quat::from_rotation_z((random_num % 360) as f32 / 180.),
+                rotation: params
+                    .rotation_or(Quat::from_rotation_z((random_num % 360) as f32 / 180.)),
             },
             ..Default::default()
         })
         .insert(EnergyBar)
         .insert(Collider::cuboid(121., 59.))
         .insert(FallTimer(Timer::from_seconds(9.85, TimerMode::Once)))
+        .insert(params.fall_speed())
         .insert(Sensor)
+        .insert(enemy_groups())
         .insert(Object)
         .id();
     commands.entity(bar);
@@ -1,5 +1,8 @@
-use crate::{speed::Speed, GameState, PrimaryWindow};
+use crate::{
+    pause::PauseState, playfield::Playfield, speed::Speed, GameState, GameplaySet, Settings,
+};
 use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
 
 /// [Plugin] taking care of all movement and despawning of falling [entities](Entity). Does *not* handle [Plane], because its
 /// despawn condition has to be different.
@@ -9,10 +12,20 @@ pub struct FallPlugin;
 #[derive(Component, Deref, DerefMut)]
 pub struct FallTimer(pub Timer);
 
+///Multiplies how fast an [Entity] with [FallTimer] falls. [entities](Entity) without it fall at normal speed.
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct FallSpeed(pub f32);
+
 impl Plugin for FallPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnExit(GameState::EndScreen), endscreen_despawn)
-            .add_systems(Update, (movement, ingame_despawn));
+            .add_systems(
+                FixedUpdate,
+                (movement, ingame_despawn)
+                    .after(GameplaySet::Logic)
+                    .before(PhysicsSet::SyncBackend)
+                    .run_if(in_state(PauseState::Running)),
+            );
     }
 }
 
@@ -20,16 +33,14 @@ impl Plugin for FallPlugin {
 /// # Arguments
 /// * `commands` - [Commands].
 /// * `query` - [Query] for [FallTimer].
-/// * `speed` - [Speed]. Despawns happen sooner with higher speed.
-/// * `time` - [Time].
+/// * `playfield` - [Playfield]. The size of the window does not matter, only [Playfield] is visible.
 fn ingame_despawn(
     mut commands: Commands,
     query: Query<(Entity, &Transform), With<FallTimer>>,
-    q_windows: Query<&Window, With<PrimaryWindow>>,
+    playfield: Res<Playfield>,
 ) {
-    let w_height = q_windows.single().height();
     for (entity, trans) in query.iter() {
-        if trans.translation.y < (w_height / -2.) - 200. {
+        if trans.translation.y < playfield.despawn_y() {
             commands.entity(entity).despawn();
         }
     }
@@ -44,13 +55,21 @@ fn endscreen_despawn(mut commands: Commands, query: Query<Entity, With<FallTimer
         commands.entity(entity).despawn();
     }
 }
-///Moves [entities](Entity) with [FallTimer] down.
+///Moves [entities](Entity) with [FallTimer] down every gameplay tick.
 /// # Arguments
-/// * `query` - [Query] for [FallTimer].
+/// * `query` - [Query] for [FallTimer] and optional [FallSpeed].
 /// * `speed` - [Speed].
-/// * `time` - [Time].
-fn movement(mut query: Query<&mut Transform, With<FallTimer>>, time: Res<Time>, speed: Res<Speed>) {
-    for mut transform in query.iter_mut() {
-        transform.translation.y -= 200. * speed.speed * time.delta_seconds();
+/// * `time` - [FixedTime].
+/// * `settings` - [Settings], used to access the `fall_speed` field.
+fn movement(
+    mut query: Query<(&mut Transform, Option<&FallSpeed>), With<FallTimer>>,
+    time: Res<FixedTime>,
+    speed: Res<Speed>,
+    settings: Res<Settings>,
+) {
+    for (mut transform, fall_speed) in query.iter_mut() {
+        let multiplier = fall_speed.map_or(1., |fall_speed| fall_speed.0);
+        transform.translation.y -=
+            settings.fall_speed * multiplier * speed.speed * time.period.as_secs_f32();
     }
 }
@@ -0,0 +1,58 @@
+use crate::{
+    falling::FallTimer, map_layout::ObjectParams, playfield::Playfield, walls::enemy_groups, Object,
+};
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::{rngs::StdRng, Rng};
+
+///Number of sprites in the fruit [TextureAtlas].
+pub const FRUIT_SPRITES: usize = 3;
+
+///Labels [entities](Entity) which when collided with heal the player by one hp.
+#[derive(Component)]
+pub struct Fruit;
+
+/// Spawns a [Fruit].
+/// # Arguments
+/// * `x` - if [None], a random `x` within [Playfield] is chosen.
+/// * `y` - if [None], it is set to [Playfield::spawn_y], above the upper bound.
+/// * `params` - [ObjectParams], `sprite`, `rotation` and `fall_speed` are used.
+/// * `commands` - [Commands].
+/// * `rng` - Gameplay stream of [crate::rng::GameRng].
+/// * `playfield` - [Playfield].
+/// * `texture` - Handle for fruit [TextureAtlas]
+pub fn create_fruit(
+    x: Option<f32>,
+    y: Option<f32>,
+    params: &ObjectParams,
+    commands: &mut Commands,
+    rng: &mut StdRng,
+    playfield: &Playfield,
+    texture: &Handle<TextureAtlas>,
+) {
+    let random_num: usize = rng.gen();
+    let sprite = TextureAtlasSprite::new(params.sprite.unwrap_or(random_num % FRUIT_SPRITES));
+    let x = x.unwrap_or(playfield.random_x(rng));
+    let y = y.unwrap_or(playfield.spawn_y());
+
+    commands
+        .spawn(SpriteSheetBundle {
+            sprite,
+            texture_atlas: texture.clone(),
+            transform: Transform {
+                translation: Vec3::new(x, y, 900.0 + rng.gen::<f32>()),
+                scale: Vec3::splat(0.6),
+                rotation: params.rotation_or(Quat::from_rotation_z(
+                    (random_num % 90) as f32 / 180. - 0.25,
+                )),
+            },
+            ..Default::default()
+        })
+        .insert(Fruit)
+        .insert(Collider::ball(32.))
+        .insert(FallTimer(Timer::from_seconds(9.85, TimerMode::Once)))
+        .insert(params.fall_speed())
+        .insert(Sensor)
+        .insert(enemy_groups())
+        .insert(Object);
+}
@@ -0,0 +1,395 @@
+use crate::{
+    catalogue::catalogue_from_args,
+    lan::LanConnection,
+    loading::Atlases,
+    player::Player,
+    preferences::Preferences,
+    replay::{Playback, Replay, SaveReplays},
+    rng::{seed_from_args, GameRng, SeedConfig},
+    score::Score,
+    settings::settings_from_args,
+    GameState, GameplayPlugin, Settings,
+};
+use bevy::app::AppExit;
+use bevy::core::FrameCount;
+use bevy::input::mouse::{MouseButtonInput, MouseMotion};
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use std::fs::read_to_string;
+use std::time::Duration;
+
+///How long a single headless frame lasts. Time is advanced by exactly this much every frame,
+/// no matter how fast the machine is.
+pub const FRAME_TIME: f32 = 1. / 60.;
+
+///One step of an [InputScript].
+/// # Fields
+/// * `frames` - For how many frames the step lasts.
+/// * `delta` - Mouse movement sent every frame of the step.
+/// * `hide` - Whether the left mouse button is held during the step.
+pub struct ScriptStep {
+    pub frames: u32,
+    pub delta: Vec2,
+    pub hide: bool,
+}
+
+///Scripted input source replacing the mouse in headless mode. Steps are played in order and loop once finished.
+/// # Fields
+/// * `steps` - [Vec] of [ScriptStep].
+/// * `step` - Index of the currently played step.
+/// * `frame` - Number of frames the current step has been played for.
+/// * `hiding` - Whether the left mouse button is currently pressed.
+#[derive(Resource)]
+pub struct InputScript {
+    steps: Vec<ScriptStep>,
+    step: usize,
+    frame: u32,
+    hiding: bool,
+}
+
+impl InputScript {
+    ///Creates a new [InputScript] from given steps. Empty scripts never move.
+    pub fn new(steps: Vec<ScriptStep>) -> InputScript {
+        InputScript {
+            steps,
+            step: 0,
+            frame: 0,
+            hiding: false,
+        }
+    }
+
+    ///Loads [InputScript] from a file. Every line has the format `frames dx dy hide`, where `hide` is either `0` or `1`.
+    /// Empty lines and lines starting with `#` are skipped.
+    pub fn from_file(path: &str) -> Result<InputScript, String> {
+        let content = read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+        let mut steps = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = || format!("{}:{}: expected \"frames dx dy hide\"", path, number + 1);
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            let [frames, dx, dy, hide] = fields[..] else {
+                return Err(error());
+            };
+            steps.push(ScriptStep {
+                frames: frames.parse().map_err(|_| error())?,
+                delta: Vec2::new(
+                    dx.parse().map_err(|_| error())?,
+                    dy.parse().map_err(|_| error())?,
+                ),
+                hide: match hide {
+                    "0" => false,
+                    "1" => true,
+                    _ => return Err(error()),
+                },
+            });
+        }
+        Ok(InputScript::new(steps))
+    }
+
+    ///Returns the input for the current frame and moves on to the next one.
+    fn advance(&mut self) -> Option<(Vec2, bool)> {
+        if self.steps.is_empty() {
+            return None;
+        }
+        while self.frame >= self.steps[self.step].frames {
+            self.frame = 0;
+            self.step = (self.step + 1) % self.steps.len();
+            if self.steps.iter().all(|step| step.frames == 0) {
+                return None;
+            }
+        }
+        self.frame += 1;
+        let step = &self.steps[self.step];
+        Some((step.delta, step.hide))
+    }
+}
+
+impl Default for InputScript {
+    ///Weaves left and right across the screen, hiding for a moment every now and then.
+    fn default() -> Self {
+        InputScript::new(Vec::from([
+            ScriptStep {
+                frames: 40,
+                delta: Vec2::new(6., 0.),
+                hide: false,
+            },
+            ScriptStep {
+                frames: 20,
+                delta: Vec2::new(0., -2.),
+                hide: true,
+            },
+            ScriptStep {
+                frames: 80,
+                delta: Vec2::new(-6., 1.),
+                hide: false,
+            },
+            ScriptStep {
+                frames: 40,
+                delta: Vec2::new(6., -1.),
+                hide: false,
+            },
+        ]))
+    }
+}
+
+///Configuration of a headless run.
+/// # Fields
+/// * `max_frames` - After how many frames the run ends even if [Player] is still alive.
+#[derive(Resource)]
+pub struct HeadlessConfig {
+    pub max_frames: u32,
+}
+
+///Builds an [App] running the game without a window, GPU or audio. Textures are replaced by empty handles,
+/// the mouse by [InputScript] and time advances by [FRAME_TIME] each frame. The app exits once [Player] dies
+/// or `max_frames` pass, printing the final [Score] and cause of death. [EnemyCatalogue](crate::catalogue::EnemyCatalogue) has to be inserted before it runs.
+/// # Arguments
+/// * `script` - [InputScript] controlling [Player].
+/// * `max_frames` - See [HeadlessConfig].
+/// * `seed` - [SeedConfig]. The same seed together with the same script always results in the same run.
+/// * `settings` - [Settings] of the run.
+/// * `replay` - If [Some], the [Replay] is played instead of `script`, `seed` and `settings`.
+pub fn headless_app(
+    script: InputScript,
+    max_frames: u32,
+    seed: SeedConfig,
+    settings: Settings,
+    replay: Option<Replay>,
+) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        HierarchyPlugin,
+        InputPlugin,
+    ))
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+        FRAME_TIME,
+    )))
+    .init_resource::<Atlases>()
+    .init_resource::<Preferences>()
+    .insert_resource(seed)
+    .insert_resource(settings)
+    .add_plugins(GameplayPlugin)
+    .insert_resource(SaveReplays(false))
+    .insert_resource(script)
+    .insert_resource(HeadlessConfig { max_frames })
+    .add_systems(
+        Update,
+        start_run.run_if(in_state(GameState::Loading).or_else(in_state(GameState::MainMenu))),
+    )
+    .add_systems(Update, finish_run)
+    .add_systems(Last, scripted_input);
+    if let Some(replay) = replay {
+        app.insert_resource(Playback::new(replay));
+    }
+    app
+}
+
+///Runs a headless game session based on command line arguments.
+/// # Arguments
+/// * `args` - Command line arguments. Recognized are `--frames N` (default 36000, 10 minutes), `--script PATH`, `--seed N`,
+///   `--settings PATH`, `--enemies PATH` and `--replay PATH`.
+pub fn run(args: &[String]) {
+    let seed = seed_from_args(args).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(2);
+    });
+    let settings = settings_from_args(args).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(2);
+    });
+    let catalogue = catalogue_from_args(args).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(2);
+    });
+    let mut max_frames = 36_000;
+    let mut script = InputScript::default();
+    let mut replay = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--frames" => match args.next().map(|frames| frames.parse::<u32>()) {
+                Some(Ok(frames)) => max_frames = frames,
+                _ => {
+                    eprintln!("--frames expects a number");
+                    std::process::exit(2);
+                }
+            },
+            "--script" => match args.next().map(|path| InputScript::from_file(path)) {
+                Some(Ok(loaded)) => script = loaded,
+                Some(Err(err)) => {
+                    eprintln!("{}", err);
+                    std::process::exit(2);
+                }
+                None => {
+                    eprintln!("--script expects a path");
+                    std::process::exit(2);
+                }
+            },
+            "--replay" => match args.next().map(|path| Replay::load(path)) {
+                Some(Ok(loaded)) => replay = Some(loaded),
+                Some(Err(err)) => {
+                    eprintln!("{}", err);
+                    std::process::exit(2);
+                }
+                None => {
+                    eprintln!("--replay expects a path");
+                    std::process::exit(2);
+                }
+            },
+            _ => {}
+        }
+    }
+    let mut app = headless_app(script, max_frames, seed, settings, replay);
+    app.insert_resource(catalogue);
+    app.run();
+}
+
+///Starts the game right away, there is no one to click through the main menu and no assets to wait for. With a [LanConnection]
+/// the game waits for the other one in [GameState::Lan] first.
+/// # Arguments
+/// * `next` - [NextState] of [GameState].
+/// * `connection` - [LanConnection].
+fn start_run(mut next: ResMut<NextState<GameState>>, connection: Option<Res<LanConnection>>) {
+    next.set(if connection.is_some() {
+        GameState::Lan
+    } else {
+        GameState::Game
+    });
+}
+
+///Sends mouse events generated by [InputScript], they are read the next frame the same way real input is.
+/// # Arguments
+/// * `script` - [InputScript].
+/// * `motion` - Sends [MouseMotion] events.
+/// * `buttons` - Sends [MouseButtonInput] events.
+fn scripted_input(
+    mut script: ResMut<InputScript>,
+    mut motion: EventWriter<MouseMotion>,
+    mut buttons: EventWriter<MouseButtonInput>,
+) {
+    let Some((delta, hide)) = script.advance() else {
+        return;
+    };
+    motion.send(MouseMotion { delta });
+    if hide != script.hiding {
+        script.hiding = hide;
+        buttons.send(MouseButtonInput {
+            button: MouseButton::Left,
+            state: if hide {
+                ButtonState::Pressed
+            } else {
+                ButtonState::Released
+            },
+            window: Entity::PLACEHOLDER,
+        });
+    }
+}
+
+///Ends the run once [Player] dies or [HeadlessConfig] `max_frames` pass, printing the final [Score] and cause of death.
+/// # Arguments
+/// * `state` - Current [GameState].
+/// * `frames` - [FrameCount].
+/// * `config` - [HeadlessConfig].
+/// * `score` - [Score].
+/// * `rng` - [GameRng], used to print the seed of the run.
+/// * `player_q` - [Query] for [Player].
+/// * `exit` - Sends [AppExit].
+fn finish_run(
+    state: Res<State<GameState>>,
+    frames: Res<FrameCount>,
+    config: Res<HeadlessConfig>,
+    score: Res<Score>,
+    rng: Res<GameRng>,
+    player_q: Query<&Player>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let died = *state.get() == GameState::EndScreen;
+    if !died && frames.0 < config.max_frames {
+        return;
+    }
+    println!("seed: {}", rng.seed);
+    println!("score: {}", score.score as usize);
+    println!("frames: {}", frames.0);
+    match (died, player_q.iter().find_map(|player| player.last_hit)) {
+        (true, Some(enemy)) => println!("cause of death: {}", enemy),
+        (true, None) => println!("cause of death: unknown"),
+        (false, _) => println!("cause of death: none, survived"),
+    }
+    exit.send(AppExit);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogue::{EnemyCatalogue, CATALOGUE_FILE};
+    use std::net::Ipv4Addr;
+
+    ///Runs two headless games in lockstep, one hosting on 127.0.0.1 and one joining it, the joining one playing
+    /// the script mirrored so the two unicorns differ. Both have to agree on the seed and receive each other's [Player].
+    #[test]
+    fn lan_loopback() {
+        let max_frames = 600;
+        let host = LanConnection::host((Ipv4Addr::LOCALHOST, 0)).expect("can't host");
+        let address = host.local_addr().expect("host has no address");
+        let joiner = LanConnection::join(address).expect("can't join");
+        let script = InputScript::default();
+        let mirrored = InputScript::new(
+            script
+                .steps
+                .iter()
+                .map(|step| ScriptStep {
+                    frames: step.frames,
+                    delta: Vec2::new(-step.delta.x, step.delta.y),
+                    hide: step.hide,
+                })
+                .collect(),
+        );
+
+        let mut apps = [(host, script), (joiner, mirrored)].map(|(connection, script)| {
+            let mut app = headless_app(
+                script,
+                max_frames,
+                SeedConfig(None),
+                Settings::default(),
+                None,
+            );
+            app.insert_resource(connection)
+                .insert_resource(EnemyCatalogue::load(CATALOGUE_FILE).expect("can't load enemies"));
+            app
+        });
+        let mut finished = [false; 2];
+        //Handshake and both runs together can not take more than this
+        for _ in 0..max_frames * 2 + 600 {
+            for (app, finished) in apps.iter_mut().zip(finished.iter_mut()) {
+                if !*finished {
+                    app.update();
+                    *finished = !app.world.resource::<Events<AppExit>>().is_empty();
+                }
+            }
+            if finished.iter().all(|finished| *finished) {
+                break;
+            }
+        }
+
+        assert_eq!(finished, [true; 2], "the games did not finish");
+        let [host, joiner] = &apps;
+        assert_eq!(
+            host.world.resource::<GameRng>().seed,
+            joiner.world.resource::<GameRng>().seed,
+            "the games play different seeds"
+        );
+        for app in [host, joiner] {
+            let received = app
+                .world
+                .get_resource::<LanConnection>()
+                .map_or(0, |connection| connection.received);
+            assert!(received > 0, "a game never received the other player");
+        }
+    }
+}
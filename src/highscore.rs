@@ -1,27 +1,359 @@
-use crate::{text::Score, GameState};
+use crate::{
+    player::Player, replay::Playback, rng::GameRng, score::Score, speed::Speed, versus::GameMode,
+    GameState, GameplaySet, TICK,
+};
 use bevy::prelude::*;
 use bevy_pkv::PkvStore;
-///[Plugin] taking care of audio related functionalities.
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+///[PkvStore] key the [Leaderboard] is stored under.
+pub const LEADERBOARD_KEY: &str = "leaderboard";
+///[PkvStore] key of the single highscore stored by older versions, moved into [Leaderboard] on first launch.
+const LEGACY_HIGHSCORE_KEY: &str = "highscore";
+///How many runs [Leaderboard] keeps.
+pub const LEADERBOARD_SIZE: usize = 10;
+///Longest name which can be entered for a [LeaderboardEntry].
+pub const MAX_NAME_LENGTH: usize = 12;
+///Name used when the player does not type any.
+const DEFAULT_NAME: &str = "Unicorn";
+
+///[Plugin] taking care of [Leaderboard] and the stats of the current run.
 pub struct HighScorePlugin;
 
+///A single run in [Leaderboard].
+/// # Fields
+/// * `name` - Name the player entered on the end screen.
+/// * `score` - Achieved [Score].
+/// * `date` - Day of the run, `YYYY-MM-DD` (UTC).
+/// * `duration` - Length of the run in seconds of gameplay.
+/// * `max_speed` - Highest [Speed] reached.
+/// * `hits` - How many times [Player] was hit.
+/// * `seed` - Seed of the run, it can be played again with `--seed N`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    pub score: u32,
+    pub date: String,
+    pub duration: f32,
+    pub max_speed: f32,
+    pub hits: u32,
+    pub seed: u64,
+}
+
+///Best [LEADERBOARD_SIZE] runs, sorted from the best one. Stored in [PkvStore] under [LEADERBOARD_KEY].
+/// # Fields
+/// * `entries` - [LeaderboardEntry]s.
+#[derive(Resource, Serialize, Deserialize, Default)]
+pub struct Leaderboard {
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+///Stats of the current run which [Score] does not track.
+/// # Fields
+/// * `ticks` - Gameplay ticks since the run started.
+/// * `max_speed` - Highest [Speed] reached.
+#[derive(Resource, Default)]
+pub struct RunStats {
+    pub ticks: u32,
+    pub max_speed: f32,
+}
+
+///The finished run waiting for the player to enter a name. The run is added to [Leaderboard] and stored on enter to
+/// [GameState::EndScreen] under [DEFAULT_NAME], so closing the game there does not lose it. The typed name replaces it
+/// on exit from [GameState::EndScreen].
+/// # Fields
+/// * `position` - Position of the run in [Leaderboard], only [Some] on the end screen of a run which made it there.
+/// * `name` - Name typed so far.
+#[derive(Resource, Default)]
+pub struct PendingEntry {
+    pub position: Option<usize>,
+    pub name: String,
+}
+
 impl Plugin for HighScorePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnExit(GameState::Game), update_highscore);
+        app.init_resource::<RunStats>()
+            .init_resource::<PendingEntry>()
+            .add_systems(Startup, load_leaderboard)
+            .add_systems(OnEnter(GameState::Game), reset_run_stats)
+            .add_systems(FixedUpdate, track_run_stats.in_set(GameplaySet::Logic))
+            .add_systems(OnEnter(GameState::EndScreen), prepare_entry)
+            .add_systems(OnExit(GameState::EndScreen), save_name);
     }
 }
-///Turns on background music on repeat.
-/// # Arguments
-/// * `asset_server` - [AssetServer], used to load the music in.
-/// * `audio` - [Audio].
-fn update_highscore(mut pkv: ResMut<PkvStore>, score: Res<Score>) {
-    let score = score.score as i32;
-    if let Ok(highscore) = pkv.get::<String>("highscore") {
-        if highscore.parse::<i32>().unwrap() < score {
-            pkv.set("highscore", &score.to_string())
-                .expect("failed to store score");
+
+impl Leaderboard {
+    ///Loads [Leaderboard] from [PkvStore]. If there is none yet, the highscore stored by older versions becomes its only entry.
+    pub fn load(pkv: &PkvStore) -> Leaderboard {
+        if let Ok(leaderboard) = pkv.get::<Leaderboard>(LEADERBOARD_KEY) {
+            return leaderboard;
+        }
+        let mut leaderboard = Leaderboard::default();
+        if let Some(score) = pkv
+            .get::<String>(LEGACY_HIGHSCORE_KEY)
+            .ok()
+            .and_then(|score| score.parse::<u32>().ok())
+        {
+            leaderboard.entries.push(LeaderboardEntry {
+                name: DEFAULT_NAME.to_string(),
+                score,
+                date: String::new(),
+                duration: 0.,
+                max_speed: 0.,
+                hits: 0,
+                seed: 0,
+            });
         }
+        leaderboard
+    }
+
+    ///Stores [Leaderboard] in [PkvStore].
+    pub fn save(&self, pkv: &mut PkvStore) {
+        if let Err(err) = pkv.set(LEADERBOARD_KEY, self) {
+            warn!("Failed to store leaderboard: {}", err);
+        }
+    }
+
+    ///Whether a run with given score makes it to [Leaderboard].
+    pub fn qualifies(&self, score: u32) -> bool {
+        self.entries.len() < LEADERBOARD_SIZE
+            || self.entries.last().is_none_or(|last| score > last.score)
+    }
+
+    ///Adds a run, keeping [Leaderboard] sorted and at most [LEADERBOARD_SIZE] long.
+    /// Returns its position, or [None] if the run did not make it.
+    pub fn insert(&mut self, entry: LeaderboardEntry) -> Option<usize> {
+        let position = self
+            .entries
+            .iter()
+            .position(|other| entry.score > other.score)
+            .unwrap_or(self.entries.len());
+        if position >= LEADERBOARD_SIZE {
+            return None;
+        }
+        self.entries.insert(position, entry);
+        self.entries.truncate(LEADERBOARD_SIZE);
+        Some(position)
+    }
+
+    ///The best run's score, 0 if there is none.
+    pub fn best_score(&self) -> u32 {
+        self.entries.first().map_or(0, |best| best.score)
+    }
+}
+
+///Today's date as `YYYY-MM-DD` (UTC).
+fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs() / 86400) as i64;
+    civil_date(days)
+}
+
+///Converts days since 1970-01-01 to a civil date as `YYYY-MM-DD`, see http://howardhinnant.github.io/date_algorithms.html
+/// # Arguments
+/// * `days` - Days since 1970-01-01, negative before it.
+fn civil_date(days: i64) -> String {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
     } else {
-        pkv.set("highscore", &score.to_string())
-            .expect("failed to store score");
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+///Loads [Leaderboard] at startup.
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `pkv` - [PkvStore].
+fn load_leaderboard(mut commands: Commands, pkv: Res<PkvStore>) {
+    commands.insert_resource(Leaderboard::load(&pkv));
+}
+
+///Resets [RunStats] on enter to [GameState::Game].
+/// # Arguments
+/// * `stats` - [RunStats].
+fn reset_run_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
+
+///Updates [RunStats] every gameplay tick.
+/// # Arguments
+/// * `stats` - [RunStats].
+/// * `speed` - [Speed].
+fn track_run_stats(mut stats: ResMut<RunStats>, speed: Res<Speed>) {
+    stats.ticks += 1;
+    stats.max_speed = stats.max_speed.max(speed.speed);
+}
+
+///Adds the run to [Leaderboard] and stores it on enter to [GameState::EndScreen] if it made it there, filling
+/// [PendingEntry]. Replays are never added.
+/// # Arguments
+/// * `pending` - [PendingEntry].
+/// * `leaderboard` - [Leaderboard].
+/// * `pkv` - [PkvStore].
+/// * `score` - [Score].
+/// * `stats` - [RunStats].
+/// * `rng` - [GameRng], used to access the seed of the run.
+/// * `player_q` - [Query] for [Player], used to count hits.
+/// * `playback` - [Playback], exists when the run was a replay.
+/// * `mode` - [GameMode], only single player runs make it to [Leaderboard].
+pub fn prepare_entry(
+    mut pending: ResMut<PendingEntry>,
+    mut leaderboard: ResMut<Leaderboard>,
+    mut pkv: ResMut<PkvStore>,
+    score: Res<Score>,
+    stats: Res<RunStats>,
+    rng: Res<GameRng>,
+    player_q: Query<&Player>,
+    playback: Option<Res<Playback>>,
+    mode: Res<GameMode>,
+) {
+    *pending = PendingEntry::default();
+    let score = score.score.max(0.) as u32;
+    if playback.is_some() || *mode != GameMode::Single || !leaderboard.qualifies(score) {
+        return;
+    }
+    let hits = player_q.get_single().map_or(0, |player| player.hits);
+    pending.position = leaderboard.insert(LeaderboardEntry {
+        name: DEFAULT_NAME.to_string(),
+        score,
+        date: today(),
+        duration: stats.ticks as f32 * TICK,
+        max_speed: stats.max_speed,
+        hits,
+        seed: rng.seed,
+    });
+    leaderboard.save(&mut pkv);
+}
+
+///Names the run of [PendingEntry] in [Leaderboard] by the typed name and stores it on exit from
+/// [GameState::EndScreen], once the name is confirmed. Without a typed name the run keeps [DEFAULT_NAME].
+/// # Arguments
+/// * `pending` - [PendingEntry].
+/// * `leaderboard` - [Leaderboard].
+/// * `pkv` - [PkvStore].
+fn save_name(
+    mut pending: ResMut<PendingEntry>,
+    mut leaderboard: ResMut<Leaderboard>,
+    mut pkv: ResMut<PkvStore>,
+) {
+    let PendingEntry { position, name } = std::mem::take(&mut *pending);
+    let Some(entry) = position.and_then(|position| leaderboard.entries.get_mut(position)) else {
+        return;
+    };
+    if name.trim().is_empty() {
+        return;
+    }
+    entry.name = name;
+    leaderboard.save(&mut pkv);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ///A [LeaderboardEntry] named `name` with given score.
+    fn entry(name: &str, score: u32) -> LeaderboardEntry {
+        LeaderboardEntry {
+            name: name.to_string(),
+            score,
+            date: String::new(),
+            duration: 0.,
+            max_speed: 0.,
+            hits: 0,
+            seed: 0,
+        }
+    }
+
+    ///Names of the entries of `leaderboard`, from the best one.
+    fn names(leaderboard: &Leaderboard) -> Vec<&str> {
+        leaderboard
+            .entries
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn insert_keeps_order() {
+        let mut leaderboard = Leaderboard::default();
+        assert_eq!(leaderboard.insert(entry("b", 200)), Some(0));
+        assert_eq!(leaderboard.insert(entry("a", 300)), Some(0));
+        assert_eq!(leaderboard.insert(entry("d", 100)), Some(2));
+        //A tie goes below the run which got there first
+        assert_eq!(leaderboard.insert(entry("c", 200)), Some(2));
+        assert_eq!(names(&leaderboard), ["a", "b", "c", "d"]);
+        assert_eq!(leaderboard.best_score(), 300);
+    }
+
+    #[test]
+    fn insert_truncates_and_rejects() {
+        let mut leaderboard = Leaderboard::default();
+        for score in 1..=LEADERBOARD_SIZE as u32 {
+            assert!(leaderboard.qualifies(score));
+            leaderboard.insert(entry(&score.to_string(), score * 10));
+        }
+        assert_eq!(leaderboard.entries.len(), LEADERBOARD_SIZE);
+        //Not better than the last entry
+        assert!(!leaderboard.qualifies(10));
+        assert_eq!(leaderboard.insert(entry("low", 10)), None);
+        assert_eq!(leaderboard.insert(entry("lower", 5)), None);
+        assert_eq!(leaderboard.entries.len(), LEADERBOARD_SIZE);
+        //Better than the last entry, which drops out
+        assert!(leaderboard.qualifies(11));
+        assert_eq!(leaderboard.insert(entry("high", 1000)), Some(0));
+        assert_eq!(leaderboard.entries.len(), LEADERBOARD_SIZE);
+        assert_eq!(leaderboard.entries[0].name, "high");
+        assert_eq!(leaderboard.entries.last().unwrap().score, 20);
+    }
+
+    #[test]
+    fn empty_leaderboard_takes_anything() {
+        let leaderboard = Leaderboard::default();
+        assert!(leaderboard.qualifies(0));
+        assert_eq!(leaderboard.best_score(), 0);
+    }
+
+    #[test]
+    fn legacy_highscore_is_migrated() {
+        let dir = std::env::temp_dir().join("pupik_legacy_highscore");
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut pkv = PkvStore::new_in_dir(&dir);
+        assert!(Leaderboard::load(&pkv).entries.is_empty());
+
+        pkv.set(LEGACY_HIGHSCORE_KEY, &"250".to_string()).unwrap();
+        let leaderboard = Leaderboard::load(&pkv);
+        assert_eq!(names(&leaderboard), [DEFAULT_NAME]);
+        assert_eq!(leaderboard.best_score(), 250);
+
+        //Once stored, the leaderboard wins over the legacy highscore
+        let mut leaderboard = Leaderboard::default();
+        leaderboard.insert(entry("a", 100));
+        leaderboard.save(&mut pkv);
+        assert_eq!(names(&Leaderboard::load(&pkv)), ["a"]);
+        drop(pkv);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn civil_dates() {
+        assert_eq!(civil_date(0), "1970-01-01");
+        assert_eq!(civil_date(-1), "1969-12-31");
+        assert_eq!(civil_date(59), "1970-03-01");
+        assert_eq!(civil_date(11016), "2000-02-29");
+        assert_eq!(civil_date(11017), "2000-03-01");
+        assert_eq!(civil_date(19782), "2024-02-29");
+        assert_eq!(civil_date(20743), "2026-10-17");
     }
 }
@@ -1,61 +1,758 @@
-use crate::map_layout::{Enemy, SpawnEvent, Structure};
+use crate::fruit::FRUIT_SPRITES;
+use crate::map_layout::{Enemy, ObjectParams, SpawnEvent, Structure};
+use crate::pixie::MAX_SWARM;
+use crate::plane::PlaneDir;
+use crate::planet::PLANET_SPRITES;
+use bevy::log::warn;
+use std::fmt;
 use std::fs::*;
 
-///Turns on background music on repeat.
+///Folder structure files are loaded from.
+pub const STRUCTURE_DIR: &str = "./structures";
+///Newest structure file format version, see `code_docs.md` for its description.
+pub const FORMAT_VERSION: u32 = 2;
+
+///What went wrong while parsing a structure file.
+#[derive(Debug, PartialEq)]
+pub enum StructureErrorKind {
+    ///The file could not be read.
+    Io(String),
+    ///The file contains no header and no objects.
+    Empty,
+    ///The first line is neither `version N` nor a version 1 weight.
+    MissingVersion,
+    ///The version line names a version this game does not know.
+    UnsupportedVersion(String),
+    ///A header line starts with an unknown key.
+    UnknownKey(String),
+    ///A header key appears twice.
+    DuplicateKey(String),
+    ///A header key has no value.
+    MissingValue(&'static str),
+    ///A value that should be a number is not one.
+    InvalidNumber { field: &'static str, value: String },
+    ///An object line names an unknown enemy.
+    UnknownEnemy(String),
+    ///An object line does not have the `x y enemy [parameter=value ...]` format.
+    MalformedObject,
+    ///An object line sets an unknown parameter.
+    UnknownParameter(String),
+    ///An object line sets a parameter its enemy does not use.
+    ParameterNotAllowed { param: &'static str, enemy: Enemy },
+    ///`dir` is neither `left` nor `right`.
+    InvalidDirection(String),
+    ///`sprite` is not a sprite variant of its enemy.
+    InvalidSprite { sprite: usize, count: usize },
+    ///A value which must not be negative is.
+    NegativeParameter(&'static str),
+    ///A header line appears after the first object.
+    HeaderAfterObjects(String),
+    ///The header does not contain `weight`.
+    MissingWeight,
+    ///The file contains no objects.
+    NoObjects,
+    ///`weight` is not greater than 0.
+    InvalidWeight,
+    ///`min_speed` is greater than `max_speed`, or `min_score` is greater than `max_score`.
+    InvalidRange(&'static str),
+    ///`max_repeats` is not a whole number greater than 0.
+    InvalidRepeats,
+    ///`count` is not a whole number from 1 to [MAX_SWARM].
+    InvalidCount(String),
+}
+
+///Keys which can appear in the header.
+const HEADER_KEYS: [&str; 8] = [
+    "name",
+    "weight",
+    "min_speed",
+    "max_speed",
+    "min_score",
+    "max_score",
+    "cooldown",
+    "max_repeats",
+];
+
+///Parameters which can follow the enemy on an object line, see [ObjectParams].
+const PARAMETERS: [&str; 10] = [
+    "dir",
+    "sprite",
+    "rotation",
+    "gravity",
+    "fall",
+    "count",
+    "separation",
+    "alignment",
+    "cohesion",
+    "chase",
+];
+
+///Error returned by [parse_structure], pointing to the line it happened on.
+/// # Fields
+/// * `file` - Name of the file.
+/// * `line` - Line number, starting at 1. 0 if the error concerns the whole file.
+/// * `kind` - [StructureErrorKind].
+#[derive(Debug, PartialEq)]
+pub struct StructureError {
+    pub file: String,
+    pub line: usize,
+    pub kind: StructureErrorKind,
+}
+
+impl fmt::Display for StructureErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StructureErrorKind::Io(error) => write!(f, "could not read file: {}", error),
+            StructureErrorKind::Empty => write!(f, "file is empty"),
+            StructureErrorKind::MissingVersion => {
+                write!(f, "file must start with \"version {}\"", FORMAT_VERSION)
+            }
+            StructureErrorKind::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported version \"{}\", newest is {}",
+                version, FORMAT_VERSION
+            ),
+            StructureErrorKind::UnknownKey(key) => write!(
+                f,
+                "unknown header key \"{}\", expected {}",
+                key,
+                HEADER_KEYS.join(", ")
+            ),
+            StructureErrorKind::DuplicateKey(key) => write!(f, "\"{}\" is set twice", key),
+            StructureErrorKind::MissingValue(key) => write!(f, "\"{}\" has no value", key),
+            StructureErrorKind::InvalidNumber { field, value } => {
+                write!(f, "{} must be a number, found \"{}\"", field, value)
+            }
+            StructureErrorKind::UnknownEnemy(keyword) => write!(
+                f,
+                "unknown enemy \"{}\", expected one of {}",
+                keyword,
+                Enemy::ALL.map(|enemy| enemy.to_string()).join(", ")
+            ),
+            StructureErrorKind::MalformedObject => {
+                write!(f, "expected \"x y enemy [parameter=value ...]\"")
+            }
+            StructureErrorKind::UnknownParameter(param) => write!(
+                f,
+                "unknown parameter \"{}\", expected {}",
+                param,
+                PARAMETERS.join(", ")
+            ),
+            StructureErrorKind::ParameterNotAllowed { param, enemy } => {
+                write!(f, "\"{}\" cannot be set for {}", param, enemy)
+            }
+            StructureErrorKind::InvalidDirection(dir) => {
+                write!(f, "dir must be left or right, found \"{}\"", dir)
+            }
+            StructureErrorKind::InvalidSprite { sprite, count } => write!(
+                f,
+                "sprite {} does not exist, expected 0 to {}",
+                sprite,
+                count - 1
+            ),
+            StructureErrorKind::NegativeParameter(param) => {
+                write!(f, "{} must not be negative", param)
+            }
+            StructureErrorKind::HeaderAfterObjects(key) => {
+                write!(f, "\"{}\" must come before the first object", key)
+            }
+            StructureErrorKind::MissingWeight => write!(f, "header is missing \"weight\""),
+            StructureErrorKind::NoObjects => write!(f, "structure contains no objects"),
+            StructureErrorKind::InvalidWeight => write!(f, "weight must be greater than 0"),
+            StructureErrorKind::InvalidRange(value) => {
+                write!(f, "min_{0} is greater than max_{0}", value)
+            }
+            StructureErrorKind::InvalidRepeats => {
+                write!(f, "max_repeats must be a whole number greater than 0")
+            }
+            StructureErrorKind::InvalidCount(value) => write!(
+                f,
+                "count must be a whole number from 1 to {}, not \"{}\"",
+                MAX_SWARM, value
+            ),
+        }
+    }
+}
+
+impl fmt::Display for StructureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}: {}", self.file, self.kind)
+        } else {
+            write!(f, "{}:{}: {}", self.file, self.line, self.kind)
+        }
+    }
+}
+
+impl std::error::Error for StructureError {}
+
+///Loads all structure files from [STRUCTURE_DIR]. Files that fail to parse are skipped with a logged warning.
 /// # Arguments
-/// * `asvec_server` - [AsvecServer], used to load the music in.
-/// * `audio` - [Audio].
-pub fn import_structures() -> Option<Vec<Structure>> {
-    if let Err(_) = create_dir_all("./structures") {
+/// * `basic_sprites` - Number of `regular` object sprite variants, see [crate::catalogue::EnemyCatalogue::sprites].
+pub fn import_structures(basic_sprites: usize) -> Option<Vec<Structure>> {
+    if create_dir_all(STRUCTURE_DIR).is_err() {
         return None;
     };
 
-    let Ok(paths) = read_dir("./structures") else {return None;};
+    let Ok(paths) = read_dir(STRUCTURE_DIR) else {
+        return None;
+    };
     let mut result = Vec::from([]);
-    for path in paths {
-        let mut vec = Vec::from([]);
-        let mut weight = 1.;
-        let mut first = true;
-        let mut min_y = i32::MAX;
-        for line in read_to_string(path.unwrap().path())
-            .expect("Error reading file \"structures\"")
-            .lines()
-        {
-            if first {
-                first = false;
-                let line_split = line.split(' ').collect::<Vec<&str>>();
-                weight = line_split[0].parse::<f64>().unwrap();
-                continue;
-            }
-            let line_split = line.split(' ').collect::<Vec<&str>>();
-            let error_message = "Incorrect formatting of the \"structures\" file";
-            let x = Some(line_split[0].parse::<i32>().expect(error_message));
-            let y = Some(line_split[1].parse::<i32>().expect(error_message));
-            if y.unwrap() < min_y {
-                min_y = y.unwrap();
-            }
-            let enemy = match line_split[2] {
-                "blackhole" => Enemy::HoleE,
-                "rainbow" => Enemy::RainbowE,
-                "energybar" => Enemy::BarE,
-                "regular" => Enemy::BasicE,
-                "plane" => Enemy::PlaneE,
-                "planet" => Enemy::PlanetE,
-                _ => Enemy::PlanetE,
+    for path in paths.flatten() {
+        let path = path.path();
+        if !path.is_file() {
+            continue;
+        }
+        let file = path.display().to_string();
+        let parsed = read_to_string(&path)
+            .map_err(|error| StructureError {
+                file: file.clone(),
+                line: 0,
+                kind: StructureErrorKind::Io(error.to_string()),
+            })
+            .and_then(|content| parse_structure(&file, &content, basic_sprites));
+        match parsed {
+            Ok(structure) => result.push(structure),
+            Err(error) => warn!("Skipping structure file: {}", error),
+        }
+    }
+    Some(result)
+}
+
+///Parses a single structure file. Both the current format and the original headerless one (version 1, where the first line
+/// is only the weight) are accepted.
+/// # Arguments
+/// * `file` - Name of the file, used in errors.
+/// * `content` - Content of the file.
+/// * `basic_sprites` - Number of `regular` object sprite variants.
+pub fn parse_structure(
+    file: &str,
+    content: &str,
+    basic_sprites: usize,
+) -> Result<Structure, StructureError> {
+    let error = |line: usize, kind: StructureErrorKind| StructureError {
+        file: file.to_string(),
+        line,
+        kind,
+    };
+    let parse_number = |line: usize, field: &'static str, value: &str| match value.parse::<f64>() {
+        Ok(number) if number.is_finite() => Ok(number),
+        _ => Err(error(
+            line,
+            StructureErrorKind::InvalidNumber {
+                field,
+                value: value.to_string(),
+            },
+        )),
+    };
+    //Values which are wrong on their own are reported on their line, ranges concern the whole file
+    let check_value = |line: usize, key: &'static str, value: f64| {
+        let kind = match key {
+            "weight" if value <= 0. => StructureErrorKind::InvalidWeight,
+            "cooldown" if value < 0. => StructureErrorKind::NegativeParameter("cooldown"),
+            "max_repeats" if value < 1. || value.fract() != 0. => {
+                StructureErrorKind::InvalidRepeats
+            }
+            _ => return Ok(value),
+        };
+        Err(error(line, kind))
+    };
+
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .map(|(number, line)| (number + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .peekable();
+
+    let mut name = None;
+    let mut weight = None;
+    let mut min_speed = None;
+    let mut max_speed = None;
+    let mut min_score = None;
+    let mut max_score = None;
+    let mut cooldown = None;
+    let mut max_repeats = None;
+
+    let Some(&(number, first)) = lines.peek() else {
+        return Err(error(0, StructureErrorKind::Empty));
+    };
+    match first.split_whitespace().collect::<Vec<&str>>()[..] {
+        ["version", version] => {
+            if version != FORMAT_VERSION.to_string() {
+                return Err(error(
+                    number,
+                    StructureErrorKind::UnsupportedVersion(version.to_string()),
+                ));
+            }
+            lines.next();
+        }
+        //Version 1 files start with the weight alone
+        [version_1_weight] if !version_1_weight.starts_with(char::is_alphabetic) => {
+            let value = parse_number(number, "weight", version_1_weight)?;
+            weight = Some(check_value(number, "weight", value)?);
+            lines.next();
+        }
+        _ => return Err(error(number, StructureErrorKind::MissingVersion)),
+    }
+
+    let mut objects = Vec::new();
+    let mut min_y = i32::MAX;
+    for (number, line) in lines {
+        let (key, value) = line
+            .split_once(char::is_whitespace)
+            .map_or((line, ""), |(key, value)| (key, value.trim()));
+        let is_header = key.chars().next().is_some_and(char::is_alphabetic);
+        if is_header {
+            if !objects.is_empty() {
+                return Err(error(
+                    number,
+                    StructureErrorKind::HeaderAfterObjects(key.to_string()),
+                ));
+            }
+            let (field, key): (&mut Option<f64>, &'static str) = match key {
+                "name" => {
+                    if name.is_some() {
+                        return Err(error(
+                            number,
+                            StructureErrorKind::DuplicateKey(key.to_string()),
+                        ));
+                    }
+                    if value.is_empty() {
+                        return Err(error(number, StructureErrorKind::MissingValue("name")));
+                    }
+                    name = Some(value.to_string());
+                    continue;
+                }
+                "weight" => (&mut weight, "weight"),
+                "min_speed" => (&mut min_speed, "min_speed"),
+                "max_speed" => (&mut max_speed, "max_speed"),
+                "min_score" => (&mut min_score, "min_score"),
+                "max_score" => (&mut max_score, "max_score"),
+                "cooldown" => (&mut cooldown, "cooldown"),
+                "max_repeats" => (&mut max_repeats, "max_repeats"),
+                _ => {
+                    return Err(error(
+                        number,
+                        StructureErrorKind::UnknownKey(key.to_string()),
+                    ))
+                }
             };
-            vec.push(SpawnEvent {
-                x,
-                y: y.map(|y| y + 600),
+            if field.is_some() {
+                return Err(error(
+                    number,
+                    StructureErrorKind::DuplicateKey(key.to_string()),
+                ));
+            }
+            if value.is_empty() {
+                return Err(error(number, StructureErrorKind::MissingValue(key)));
+            }
+            let value = parse_number(number, key, value)?;
+            *field = Some(check_value(number, key, value)?);
+            continue;
+        }
+
+        let mut words = line.split_whitespace();
+        let (Some(x), Some(y), Some(enemy)) = (words.next(), words.next(), words.next()) else {
+            return Err(error(number, StructureErrorKind::MalformedObject));
+        };
+        let parse_coordinate = |field: &'static str, value: &str| {
+            value.parse::<i32>().map_err(|_| {
+                error(
+                    number,
+                    StructureErrorKind::InvalidNumber {
+                        field,
+                        value: value.to_string(),
+                    },
+                )
+            })
+        };
+        let x = parse_coordinate("x", x)?;
+        let y = parse_coordinate("y", y)?;
+        let enemy = enemy
+            .parse::<Enemy>()
+            .map_err(|_| error(number, StructureErrorKind::UnknownEnemy(enemy.to_string())))?;
+        let params =
+            parse_params(enemy, words, basic_sprites).map_err(|kind| error(number, kind))?;
+        min_y = min_y.min(y);
+        objects.push(SpawnEvent {
+            x: Some(x),
+            y: Some(y),
+            enemy,
+            params,
+        });
+    }
+
+    let Some(weight) = weight else {
+        return Err(error(0, StructureErrorKind::MissingWeight));
+    };
+    if objects.is_empty() {
+        return Err(error(0, StructureErrorKind::NoObjects));
+    }
+    for (value, min, max) in [
+        ("speed", min_speed, max_speed),
+        ("score", min_score, max_score),
+    ] {
+        if let (Some(min), Some(max)) = (min, max) {
+            if min > max {
+                return Err(error(0, StructureErrorKind::InvalidRange(value)));
+            }
+        }
+    }
+    //The lowest object sits at 0, spawning moves the structure above the playfield
+    for object in objects.iter_mut() {
+        object.y = object.y.map(|y| y - min_y);
+    }
+    Ok(Structure {
+        name: name.unwrap_or_else(|| file.to_string()),
+        spawn_chance: weight,
+        min_speed: min_speed.map(|speed| speed as f32),
+        max_speed: max_speed.map(|speed| speed as f32),
+        min_score: min_score.map(|score| score as f32),
+        max_score: max_score.map(|score| score as f32),
+        cooldown: cooldown.map(|cooldown| cooldown as f32),
+        max_repeats: max_repeats.map(|repeats| repeats as u32),
+        structure: objects,
+    })
+}
+
+///Parses `parameter=value` pairs following the enemy on an object line into [ObjectParams].
+/// # Arguments
+/// * `enemy` - [Enemy] of the object, parameters it does not use are rejected.
+/// * `words` - The pairs.
+/// * `basic_sprites` - Number of `regular` object sprite variants.
+fn parse_params<'a>(
+    enemy: Enemy,
+    words: impl Iterator<Item = &'a str>,
+    basic_sprites: usize,
+) -> Result<ObjectParams, StructureErrorKind> {
+    let mut params = ObjectParams::default();
+    for word in words {
+        let Some((param, value)) = word.split_once('=') else {
+            return Err(StructureErrorKind::MalformedObject);
+        };
+        let Some(param) = PARAMETERS.into_iter().find(|known| *known == param) else {
+            return Err(StructureErrorKind::UnknownParameter(param.to_string()));
+        };
+        let allowed = match param {
+            "dir" => enemy == Enemy::PlaneE,
+            "sprite" => matches!(enemy, Enemy::BasicE | Enemy::PlanetE | Enemy::FruitE),
+            "rotation" => !matches!(enemy, Enemy::PlaneE | Enemy::RainbowE | Enemy::PixieE),
+            "gravity" => matches!(
                 enemy,
-            });
+                Enemy::HoleE | Enemy::PlanetE | Enemy::WhiteHoleE | Enemy::BasicE
+            ),
+            "fall" => enemy != Enemy::RainbowE,
+            _ => enemy == Enemy::PixieE,
+        };
+        if !allowed {
+            return Err(StructureErrorKind::ParameterNotAllowed { param, enemy });
+        }
+        if value.is_empty() {
+            return Err(StructureErrorKind::MissingValue(param));
+        }
+        let already_set = match param {
+            "dir" => params.dir.is_some(),
+            "sprite" => params.sprite.is_some(),
+            "rotation" => params.rotation.is_some(),
+            "gravity" => params.gravity.is_some(),
+            "fall" => params.fall_speed.is_some(),
+            "count" => params.count.is_some(),
+            "separation" => params.separation.is_some(),
+            "alignment" => params.alignment.is_some(),
+            "cohesion" => params.cohesion.is_some(),
+            _ => params.chase.is_some(),
+        };
+        if already_set {
+            return Err(StructureErrorKind::DuplicateKey(param.to_string()));
+        }
+        let invalid_number = || StructureErrorKind::InvalidNumber {
+            field: param,
+            value: value.to_string(),
+        };
+        match param {
+            "dir" => {
+                params.dir = Some(match value {
+                    "left" => PlaneDir::Left,
+                    "right" => PlaneDir::Right,
+                    _ => return Err(StructureErrorKind::InvalidDirection(value.to_string())),
+                })
+            }
+            "sprite" => {
+                let sprite = value.parse::<usize>().map_err(|_| invalid_number())?;
+                let count = match enemy {
+                    Enemy::PlanetE => PLANET_SPRITES,
+                    Enemy::FruitE => FRUIT_SPRITES,
+                    _ => basic_sprites,
+                };
+                if sprite >= count {
+                    return Err(StructureErrorKind::InvalidSprite { sprite, count });
+                }
+                params.sprite = Some(sprite);
+            }
+            "count" => {
+                let count = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|count| (1..=MAX_SWARM).contains(count))
+                    .ok_or_else(|| StructureErrorKind::InvalidCount(value.to_string()))?;
+                params.count = Some(count);
+            }
+            _ => {
+                let number = value
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|number| number.is_finite())
+                    .ok_or_else(invalid_number)?;
+                match param {
+                    "rotation" => params.rotation = Some(number),
+                    //Negative chase makes pixies flee
+                    "chase" => params.chase = Some(number),
+                    _ if number < 0. => return Err(StructureErrorKind::NegativeParameter(param)),
+                    "gravity" => params.gravity = Some(number),
+                    "fall" => params.fall_speed = Some(number),
+                    "separation" => params.separation = Some(number),
+                    "alignment" => params.alignment = Some(number),
+                    _ => params.cohesion = Some(number),
+                }
+            }
+        }
+    }
+    Ok(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ///Number of `regular` object sprite variants used by the tests.
+    const BASIC_SPRITES: usize = 6;
+
+    ///Parses `content` as a file named `test`.
+    fn parse(content: &str) -> Result<Structure, StructureError> {
+        parse_structure("test", content, BASIC_SPRITES)
+    }
+
+    ///Parses `content` and returns the [StructureErrorKind] and line of its error.
+    fn parse_error(content: &str) -> (usize, StructureErrorKind) {
+        match parse(content) {
+            Ok(_) => panic!("{:?} should not parse", content),
+            Err(error) => (error.line, error.kind),
         }
-        for object in vec.iter_mut() {
-            object.y = Some(object.y.unwrap() - min_y);
+    }
+
+    #[test]
+    fn every_enemy_keyword_round_trips() {
+        for enemy in Enemy::ALL {
+            let keyword = enemy.to_string();
+            assert_eq!(keyword.parse::<Enemy>(), Ok(enemy));
+            let structure = parse(&format!("version 2\nweight 1\n10 -20 {}", keyword))
+                .unwrap_or_else(|error| panic!("{}: {}", keyword, error));
+            assert_eq!(structure.structure.len(), 1);
+            let object = &structure.structure[0];
+            assert_eq!(object.enemy, enemy);
+            assert_eq!(object.x, Some(10));
+            assert_eq!(object.y, Some(0));
+            assert_eq!(object.params, ObjectParams::default());
         }
-        result.push(Structure {
-            spawn_chance: weight,
-            structure: vec,
-        })
     }
-    return Some(result);
+
+    #[test]
+    fn version_2_header() {
+        let structure = parse(
+            "# comment\nversion 2\nname Wall\nweight 2.5\nmin_speed 1\nmax_speed 3\nmin_score 10\n\
+             max_score 20\ncooldown 4\nmax_repeats 2\n0 0 regular sprite=1 rotation=-1 gravity=2 fall=0.5\n\
+             0 100 plane dir=left\n50 50 pixie count=3 separation=1 alignment=2 cohesion=0 chase=-1",
+        )
+        .unwrap();
+        assert_eq!(structure.name, "Wall");
+        assert_eq!(structure.spawn_chance, 2.5);
+        assert_eq!(structure.min_speed, Some(1.));
+        assert_eq!(structure.max_speed, Some(3.));
+        assert_eq!(structure.min_score, Some(10.));
+        assert_eq!(structure.max_score, Some(20.));
+        assert_eq!(structure.cooldown, Some(4.));
+        assert_eq!(structure.max_repeats, Some(2));
+        assert_eq!(structure.structure.len(), 3);
+        let regular = structure.structure[0].params;
+        assert_eq!(regular.sprite, Some(1));
+        assert_eq!(regular.rotation, Some(-1.));
+        assert_eq!(regular.gravity, Some(2.));
+        assert_eq!(regular.fall_speed, Some(0.5));
+        assert_eq!(structure.structure[1].params.dir, Some(PlaneDir::Left));
+        let pixie = structure.structure[2].params;
+        assert_eq!(pixie.count, Some(3));
+        assert_eq!(pixie.separation, Some(1.));
+        assert_eq!(pixie.alignment, Some(2.));
+        assert_eq!(pixie.cohesion, Some(0.));
+        assert_eq!(pixie.chase, Some(-1.));
+    }
+
+    #[test]
+    fn version_1_fallback() {
+        let structure = parse("3\n0 0 blackhole\n100 200 energybar").unwrap();
+        assert_eq!(structure.name, "test");
+        assert_eq!(structure.spawn_chance, 3.);
+        assert_eq!(structure.min_speed, None);
+        assert_eq!(structure.max_repeats, None);
+        assert_eq!(structure.structure.len(), 2);
+        assert_eq!(structure.structure[0].y, Some(0));
+        assert_eq!(structure.structure[1].y, Some(200));
+    }
+
+    #[test]
+    fn every_error_kind() {
+        use StructureErrorKind::*;
+        let cases: Vec<(&str, usize, StructureErrorKind)> = vec![
+            ("", 0, Empty),
+            ("# only a comment", 0, Empty),
+            ("weight 1\n0 0 blackhole", 1, MissingVersion),
+            (
+                "version 3\nweight 1\n0 0 blackhole",
+                1,
+                UnsupportedVersion("3".to_string()),
+            ),
+            (
+                "version 2\nspeed 1\n0 0 blackhole",
+                2,
+                UnknownKey("speed".to_string()),
+            ),
+            (
+                "version 2\nweight 1\nweight 2\n0 0 blackhole",
+                3,
+                DuplicateKey("weight".to_string()),
+            ),
+            (
+                "version 2\nname A\nname B\n0 0 blackhole",
+                3,
+                DuplicateKey("name".to_string()),
+            ),
+            ("version 2\nname\n0 0 blackhole", 2, MissingValue("name")),
+            (
+                "version 2\nweight\n0 0 blackhole",
+                2,
+                MissingValue("weight"),
+            ),
+            (
+                "version 2\nweight lots\n0 0 blackhole",
+                2,
+                InvalidNumber {
+                    field: "weight",
+                    value: "lots".to_string(),
+                },
+            ),
+            (
+                "version 2\nweight 1\n0.5 0 blackhole",
+                3,
+                InvalidNumber {
+                    field: "x",
+                    value: "0.5".to_string(),
+                },
+            ),
+            (
+                "version 2\nweight 1\n0 0 dragon",
+                3,
+                UnknownEnemy("dragon".to_string()),
+            ),
+            ("version 2\nweight 1\n0 0", 3, MalformedObject),
+            (
+                "version 2\nweight 1\n0 0 blackhole gravity",
+                3,
+                MalformedObject,
+            ),
+            (
+                "version 2\nweight 1\n0 0 blackhole size=2",
+                3,
+                UnknownParameter("size".to_string()),
+            ),
+            (
+                "version 2\nweight 1\n0 0 blackhole dir=left",
+                3,
+                ParameterNotAllowed {
+                    param: "dir",
+                    enemy: Enemy::HoleE,
+                },
+            ),
+            (
+                "version 2\nweight 1\n0 0 blackhole gravity=",
+                3,
+                MissingValue("gravity"),
+            ),
+            (
+                "version 2\nweight 1\n0 0 blackhole gravity=1 gravity=2",
+                3,
+                DuplicateKey("gravity".to_string()),
+            ),
+            (
+                "version 2\nweight 1\n0 0 plane dir=up",
+                3,
+                InvalidDirection("up".to_string()),
+            ),
+            (
+                "version 2\nweight 1\n0 0 regular sprite=6",
+                3,
+                InvalidSprite {
+                    sprite: 6,
+                    count: BASIC_SPRITES,
+                },
+            ),
+            (
+                "version 2\nweight 1\n0 0 blackhole gravity=-1",
+                3,
+                NegativeParameter("gravity"),
+            ),
+            (
+                "version 2\nweight 1\ncooldown -1\n0 0 blackhole",
+                3,
+                NegativeParameter("cooldown"),
+            ),
+            (
+                "version 2\nweight 1\n0 0 blackhole\nname Late",
+                4,
+                HeaderAfterObjects("name".to_string()),
+            ),
+            ("version 2\n0 0 blackhole", 0, MissingWeight),
+            ("version 2\nweight 1", 0, NoObjects),
+            ("version 2\nweight 0\n0 0 blackhole", 2, InvalidWeight),
+            ("0\n0 0 blackhole", 1, InvalidWeight),
+            (
+                "version 2\nweight 1\nmin_speed 3\nmax_speed 2\n0 0 blackhole",
+                0,
+                InvalidRange("speed"),
+            ),
+            (
+                "version 2\nweight 1\nmin_score 3\nmax_score 2\n0 0 blackhole",
+                0,
+                InvalidRange("score"),
+            ),
+            (
+                "version 2\nweight 1\nmax_repeats 0\n0 0 blackhole",
+                3,
+                InvalidRepeats,
+            ),
+            (
+                "version 2\nweight 1\nmax_repeats 1.5\n0 0 blackhole",
+                3,
+                InvalidRepeats,
+            ),
+            (
+                "version 2\nweight 1\n0 0 pixie count=0",
+                3,
+                InvalidCount("0".to_string()),
+            ),
+        ];
+        for (content, line, kind) in cases {
+            assert_eq!(parse_error(content), (line, kind), "{:?}", content);
+        }
+    }
+
+    #[test]
+    fn io_error_names_the_file() {
+        let error = StructureError {
+            file: "test".to_string(),
+            line: 0,
+            kind: StructureErrorKind::Io("denied".to_string()),
+        };
+        assert_eq!(error.to_string(), "test: could not read file: denied");
+    }
 }
@@ -0,0 +1,455 @@
+use crate::{
+    catalogue::EnemyCatalogue,
+    loading::Atlases,
+    player::{damage_sprite, Hidden, Player},
+    rng::{reseed, GameRng},
+    score::Score,
+    versus::GameMode,
+    GameState, GameplaySet, Settings,
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::io::{self, ErrorKind};
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs, UdpSocket};
+
+///Port the host listens on.
+pub const DEFAULT_PORT: u16 = 7777;
+///Biggest datagram read, enough for [LanMessage::Welcome] carrying [Settings].
+const MAX_DATAGRAM: usize = 4096;
+///Seconds between two [LanMessage::Hello]s sent while joining.
+const HELLO_INTERVAL: f32 = 0.5;
+
+///[Plugin] taking care of racing another game over LAN. Both games play the same seed, so they spawn the same obstacles,
+/// and send each other the state of their [Player] every gameplay tick. The other player is shown as a [Ghost].
+pub struct LanPlugin;
+
+///Which side of the connection this game is.
+/// * `Host` - Waits for a player to join and picks the seed.
+/// * `Join` - Connects to a host.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LanRole {
+    Host,
+    Join,
+}
+
+///State of a [Player] sent to the other game every gameplay tick.
+/// # Fields
+/// * `tick` - Gameplay tick of the sender's run, older states than the last received one are dropped.
+/// * `x` - x coordinate of the unicorn.
+/// * `y` - y coordinate of the unicorn.
+/// * `hidden` - Whether the unicorn is hidden.
+/// * `hp` - Hp of the unicorn, negative once he is dead.
+/// * `score` - [Score] of the sender.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+pub struct GhostState {
+    pub tick: u32,
+    pub x: f32,
+    pub y: f32,
+    pub hidden: bool,
+    pub hp: i32,
+    pub score: f32,
+}
+
+///Messages sent over LAN, one per datagram, encoded as RON.
+/// * `Hello` - Sent by the joining game until `Welcome` arrives.
+/// * `Welcome` - Answer of the host with the seed of the run, his [Settings] and [EnemyCatalogue::fingerprint].
+///   Both games start once it arrives.
+/// * `State` - [GhostState] of the sender's [Player].
+/// * `Bye` - The sender left.
+#[derive(Serialize, Deserialize)]
+pub enum LanMessage {
+    Hello,
+    Welcome {
+        seed: u64,
+        settings: Settings,
+        catalogue: u64,
+    },
+    State(GhostState),
+    Bye,
+}
+
+impl LanMessage {
+    ///Encodes [LanMessage] into a datagram.
+    pub fn encode(&self) -> Vec<u8> {
+        ron::to_string(self)
+            .expect("LAN messages are always serializable.")
+            .into_bytes()
+    }
+
+    ///Decodes [LanMessage] from a datagram, [None] if it is not one.
+    pub fn decode(bytes: &[u8]) -> Option<LanMessage> {
+        ron::from_str(std::str::from_utf8(bytes).ok()?).ok()
+    }
+}
+
+///Connection to the other game. Exists from hosting or joining until the game returns to the main menu.
+/// # Fields
+/// * `socket` - Non-blocking [UdpSocket].
+/// * `role` - [LanRole].
+/// * `peer` - Address of the other game, [None] while the host waits.
+/// * `seed` - Seed of the run, [Some] once the games agreed on it.
+/// * `ghost` - Newest [GhostState] of the other player.
+/// * `received` - How many [GhostState]s arrived this run.
+/// * `peer_left` - Whether the other game sent [LanMessage::Bye].
+/// * `error` - Why the games could not connect, if they could not. English, translated where it is shown.
+/// * `tick` - Gameplay ticks of this run, see [GhostState] `tick`.
+/// * `hello_timer` - [Timer] of [LanMessage::Hello]s.
+#[derive(Resource)]
+pub struct LanConnection {
+    socket: UdpSocket,
+    pub role: LanRole,
+    pub peer: Option<SocketAddr>,
+    pub seed: Option<u64>,
+    pub ghost: Option<GhostState>,
+    pub received: u32,
+    pub peer_left: bool,
+    pub error: Option<&'static str>,
+    tick: u32,
+    hello_timer: Timer,
+}
+
+impl LanConnection {
+    ///Creates a [LanConnection] waiting for another game on `address`.
+    pub fn host(address: impl ToSocketAddrs) -> io::Result<LanConnection> {
+        LanConnection::new(UdpSocket::bind(address)?, LanRole::Host, None)
+    }
+
+    ///Creates a [LanConnection] joining the host on `host`.
+    pub fn join(host: SocketAddr) -> io::Result<LanConnection> {
+        let any: IpAddr = if host.is_ipv4() {
+            [0, 0, 0, 0].into()
+        } else {
+            [0u16; 8].into()
+        };
+        LanConnection::new(UdpSocket::bind((any, 0))?, LanRole::Join, Some(host))
+    }
+
+    fn new(
+        socket: UdpSocket,
+        role: LanRole,
+        peer: Option<SocketAddr>,
+    ) -> io::Result<LanConnection> {
+        socket.set_nonblocking(true)?;
+        Ok(LanConnection {
+            socket,
+            role,
+            peer,
+            seed: None,
+            ghost: None,
+            received: 0,
+            peer_left: false,
+            error: None,
+            tick: 0,
+            hello_timer: Timer::from_seconds(HELLO_INTERVAL, TimerMode::Repeating),
+        })
+    }
+
+    ///Address the socket is bound to. Only the loopback test needs it, the game shows [local_ip] instead.
+    #[cfg(test)]
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.socket.local_addr().ok()
+    }
+
+    ///Sends `message` to the other game, if there is one.
+    fn send(&self, message: &LanMessage) {
+        let Some(peer) = self.peer else {
+            return;
+        };
+        if let Err(err) = self.socket.send_to(&message.encode(), peer) {
+            warn!("Failed to send to {}: {}", peer, err);
+        }
+    }
+
+    ///Reads all datagrams which arrived since the last call. Datagrams which are not [LanMessage]s are skipped.
+    fn receive(&self) -> Vec<(SocketAddr, LanMessage)> {
+        let mut messages = Vec::new();
+        let mut buffer = [0; MAX_DATAGRAM];
+        loop {
+            match self.socket.recv_from(&mut buffer) {
+                Ok((length, from)) => {
+                    if let Some(message) = LanMessage::decode(&buffer[..length]) {
+                        messages.push((from, message));
+                    }
+                }
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                //Windows reports a closed port of the other side as an error of the next read
+                Err(err) if err.kind() == ErrorKind::ConnectionReset => {}
+                Err(err) => {
+                    warn!("Failed to receive: {}", err);
+                    break;
+                }
+            }
+        }
+        messages
+    }
+
+    ///Answers [LanMessage::Hello] of the other game with [LanMessage::Welcome]. The first game to say hello becomes
+    /// the peer, the others are ignored.
+    fn welcome(&mut self, from: SocketAddr, settings: &Settings, catalogue: &EnemyCatalogue) {
+        if self.peer.is_some_and(|peer| peer != from) {
+            return;
+        }
+        self.peer = Some(from);
+        let seed = *self.seed.get_or_insert_with(rand::random);
+        self.send(&LanMessage::Welcome {
+            seed,
+            settings: settings.clone(),
+            catalogue: catalogue.fingerprint(),
+        });
+    }
+}
+
+///Parses the address of a host typed by the player. The port may be left out, [DEFAULT_PORT] is used then.
+/// The error is English, translated where it is shown.
+/// # Arguments
+/// * `text` - Typed address, like `192.168.0.5` or `192.168.0.5:7777`.
+pub fn parse_address(text: &str) -> Result<SocketAddr, &'static str> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("Type the address of the host");
+    }
+    if let Ok(address) = text.parse::<SocketAddr>() {
+        return Ok(address);
+    }
+    (text, DEFAULT_PORT)
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addresses| addresses.next())
+        .ok_or("This is not an address")
+}
+
+///Address of this computer in the local network, shown to the host so he can tell it to the other player.
+/// [None] if it can not be found out. No datagram is sent, connecting a UDP socket only picks the route.
+pub fn local_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0)).ok()?;
+    socket.connect(("8.8.8.8", 80)).ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+///Translucent unicorn showing where the other player is.
+#[derive(Component)]
+struct Ghost;
+
+impl Plugin for LanPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            handshake
+                .run_if(in_state(GameState::Lan))
+                .run_if(resource_exists::<LanConnection>()),
+        )
+        .add_systems(
+            OnEnter(GameState::Game),
+            (start_lan_run.after(reseed), spawn_ghost).run_if(resource_exists::<LanConnection>()),
+        )
+        .add_systems(
+            FixedUpdate,
+            send_state
+                .in_set(GameplaySet::Logic)
+                .run_if(resource_exists::<LanConnection>()),
+        )
+        .add_systems(
+            OnEnter(GameState::EndScreen),
+            send_state.run_if(resource_exists::<LanConnection>()),
+        )
+        .add_systems(
+            Update,
+            (receive_states, update_ghost)
+                .chain()
+                .run_if(in_state(GameState::Game).or_else(in_state(GameState::EndScreen)))
+                .run_if(resource_exists::<LanConnection>()),
+        )
+        .add_systems(
+            OnExit(GameState::EndScreen),
+            leave_lan.run_if(resource_exists::<LanConnection>()),
+        );
+    }
+}
+
+///Agrees on the seed with the other game while in [GameState::Lan]. The joining game says hello until the host
+/// welcomes it, then both switch to [GameMode::Lan] and start the game. Games with different [Settings] or
+/// [EnemyCatalogue] would not spawn the same obstacles, so the joining game refuses to start with them.
+/// # Arguments
+/// * `connection` - [LanConnection].
+/// * `mode` - [GameMode].
+/// * `settings` - [Settings].
+/// * `catalogue` - [EnemyCatalogue].
+/// * `next` - [NextState] of [GameState].
+/// * `time` - [Time].
+fn handshake(
+    mut connection: ResMut<LanConnection>,
+    mut mode: ResMut<GameMode>,
+    settings: Res<Settings>,
+    catalogue: Res<EnemyCatalogue>,
+    mut next: ResMut<NextState<GameState>>,
+    time: Res<Time>,
+) {
+    if connection.error.is_some() {
+        return;
+    }
+    if connection.role == LanRole::Join && connection.hello_timer.tick(time.delta()).just_finished()
+    {
+        connection.send(&LanMessage::Hello);
+    }
+    for (from, message) in connection.receive() {
+        match (connection.role, message) {
+            (LanRole::Host, LanMessage::Hello) => connection.welcome(from, &settings, &catalogue),
+            (
+                LanRole::Join,
+                LanMessage::Welcome {
+                    seed,
+                    settings: host,
+                    catalogue: host_catalogue,
+                },
+            ) => {
+                if connection.peer != Some(from) {
+                    continue;
+                }
+                if ron::to_string(&host).ok() != ron::to_string(&*settings).ok() {
+                    connection.error = Some("Your settings differ from the host's");
+                    return;
+                }
+                if host_catalogue != catalogue.fingerprint() {
+                    connection.error = Some("Your enemies differ from the host's");
+                    return;
+                }
+                connection.seed = Some(seed);
+            }
+            _ => {}
+        }
+    }
+    if connection.seed.is_some() && connection.peer.is_some() {
+        *mode = GameMode::Lan;
+        next.set(GameState::Game);
+    }
+}
+
+///Reseeds [GameRng] with the seed both games agreed on and forgets the last run on enter to [GameState::Game].
+/// # Arguments
+/// * `connection` - [LanConnection].
+/// * `rng` - [GameRng].
+fn start_lan_run(mut connection: ResMut<LanConnection>, mut rng: ResMut<GameRng>) {
+    if let Some(seed) = connection.seed {
+        *rng = GameRng::new(seed);
+    }
+    connection.tick = 0;
+    connection.ghost = None;
+    connection.received = 0;
+}
+
+///Sends [GhostState] of the first [Player] every gameplay tick and once more on enter to [GameState::EndScreen],
+/// since no tick runs after the death which ends the run.
+/// # Arguments
+/// * `connection` - [LanConnection].
+/// * `player_q` - [Query] for [Player].
+/// * `score` - [Score].
+fn send_state(
+    mut connection: ResMut<LanConnection>,
+    player_q: Query<(&Player, &Hidden, &Transform)>,
+    score: Res<Score>,
+) {
+    connection.tick += 1;
+    let Some((player, hidden, transform)) =
+        player_q.iter().find(|(player, _, _)| player.index == 0)
+    else {
+        return;
+    };
+    connection.send(&LanMessage::State(GhostState {
+        tick: connection.tick,
+        x: transform.translation.x,
+        y: transform.translation.y,
+        hidden: hidden.hidden,
+        hp: player.hp,
+        score: score.score,
+    }));
+}
+
+///Reads [LanMessage]s of the other game during the run. The host welcomes the other game again if his welcome got lost.
+/// # Arguments
+/// * `connection` - [LanConnection].
+/// * `settings` - [Settings].
+/// * `catalogue` - [EnemyCatalogue].
+fn receive_states(
+    mut connection: ResMut<LanConnection>,
+    settings: Res<Settings>,
+    catalogue: Res<EnemyCatalogue>,
+) {
+    for (from, message) in connection.receive() {
+        if connection.peer != Some(from) {
+            continue;
+        }
+        match message {
+            LanMessage::Hello if connection.role == LanRole::Host => {
+                connection.welcome(from, &settings, &catalogue);
+            }
+            LanMessage::State(state) => {
+                if connection.ghost.is_none_or(|ghost| ghost.tick < state.tick) {
+                    connection.ghost = Some(state);
+                }
+                connection.received += 1;
+            }
+            LanMessage::Bye => connection.peer_left = true,
+            _ => {}
+        }
+    }
+}
+
+///Spawns the [Ghost] on enter to [GameState::Game]. It stays invisible until the first [GhostState] arrives.
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `atlases` - [Atlases].
+fn spawn_ghost(mut commands: Commands, atlases: Res<Atlases>) {
+    let mut sprite = TextureAtlasSprite::new(0);
+    sprite.custom_size = Some(Vec2::new(100., 120.));
+    sprite.color = Color::rgba(1., 1., 1., 0.4);
+    commands
+        .spawn(SpriteSheetBundle {
+            sprite,
+            texture_atlas: atlases.get("unicorn"),
+            transform: Transform::from_xyz(0., -250., 899.),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        })
+        .insert(Ghost);
+}
+
+///Moves the [Ghost] to the newest [GhostState]. It disappears once the other player dies or leaves.
+/// # Arguments
+/// * `connection` - [LanConnection].
+/// * `ghost_q` - [Query] for [Ghost].
+fn update_ghost(
+    connection: Res<LanConnection>,
+    mut ghost_q: Query<(&mut Transform, &mut TextureAtlasSprite, &mut Visibility), With<Ghost>>,
+) {
+    for (mut transform, mut sprite, mut visibility) in &mut ghost_q {
+        match connection.ghost {
+            Some(state) if state.hp >= 0 && !connection.peer_left => {
+                transform.translation.x = state.x;
+                transform.translation.y = state.y;
+                sprite.index = damage_sprite(state.hp, state.hidden);
+                *visibility = Visibility::Visible;
+            }
+            _ => *visibility = Visibility::Hidden,
+        }
+    }
+}
+
+///Says bye to the other game, closes [LanConnection] and despawns the [Ghost] on exit from [GameState::EndScreen].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `connection` - [LanConnection].
+/// * `mode` - [GameMode], back to [GameMode::Single].
+/// * `ghost_q` - [Query] for [Ghost].
+fn leave_lan(
+    mut commands: Commands,
+    connection: Res<LanConnection>,
+    mut mode: ResMut<GameMode>,
+    ghost_q: Query<Entity, With<Ghost>>,
+) {
+    connection.send(&LanMessage::Bye);
+    commands.remove_resource::<LanConnection>();
+    *mode = GameMode::Single;
+    for entity in &ghost_q {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,399 @@
+use crate::{
+    lan::{local_ip, parse_address, LanConnection, LanRole, DEFAULT_PORT},
+    playfield::PlayfieldPanel,
+    preferences::{Language, Preferences},
+    GameState,
+};
+use bevy::prelude::*;
+
+///Longest address which can be typed.
+const MAX_ADDRESS_LENGTH: usize = 40;
+
+///[Plugin] taking care of the screen where a LAN game is hosted or joined. The game starts once the other game
+/// connects, see [crate::lan].
+pub struct LanScreenPlugin;
+
+///Labels all [entities](Entity) which are spawned on enter to [GameState::Lan] and despawn on exit.
+#[derive(Component)]
+struct LanScreen;
+
+///Button in the top left corner that takes player back to the main menu, closing [LanConnection]. When hovered,
+/// its background color changes until unhovered.
+#[derive(Component)]
+struct LanBackButton;
+
+///Button which starts waiting for another game. When hovered, its background color changes until unhovered.
+#[derive(Component)]
+struct HostButton;
+
+///Button which joins the host typed in [AddressText]. Enter does the same. When hovered, its background color
+/// changes until unhovered.
+#[derive(Component)]
+struct JoinButton;
+
+///Labels the [Text] showing the typed address of the host.
+#[derive(Component)]
+struct AddressText;
+
+///Labels the [Text] showing [LanStatus].
+#[derive(Component)]
+struct StatusText;
+
+///Address of the host typed by the player. Kept between visits of the screen.
+#[derive(Resource, Default)]
+struct TypedAddress(String);
+
+///What is going on with the connection, shown in [StatusText].
+#[derive(Resource, Default)]
+struct LanStatus(String);
+
+///Background color of a button.
+const BUTTON_COLOR: Color = Color::rgba(0., 0., 0., 0.1);
+///Background color of a hovered button.
+const HOVERED_COLOR: Color = Color::rgba(0., 0., 0., 0.7);
+
+impl Plugin for LanScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TypedAddress>()
+            .init_resource::<LanStatus>()
+            .add_systems(OnEnter(GameState::Lan), spawn_lan_screen)
+            .add_systems(
+                Update,
+                (
+                    back_button_interaction,
+                    host_button_interaction,
+                    join_button_interaction,
+                    type_address,
+                    refresh_lan_screen,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Lan)),
+            )
+            .add_systems(OnExit(GameState::Lan), despawn_lan_screen);
+    }
+}
+
+///Spawns [LanScreen] with [LanBackButton], [HostButton], [AddressText], [JoinButton] and [StatusText].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `assets` - [AssetServer]. Used to load font.
+/// * `address` - [TypedAddress].
+/// * `status` - [LanStatus].
+/// * `preferences` - [Preferences], texts are in its `language`.
+fn spawn_lan_screen(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    address: Res<TypedAddress>,
+    mut status: ResMut<LanStatus>,
+    preferences: Res<Preferences>,
+) {
+    let language = preferences.language;
+    status.0 = language.tr("Host a game or join one").to_string();
+    let font = assets.load("fonts\\Love_Letters.ttf");
+    let text_style = |font_size| TextStyle {
+        font: font.clone(),
+        font_size,
+        color: Color::rgb(0.9, 0.9, 0.9),
+    };
+    let button = |width: f32| ButtonBundle {
+        style: Style {
+            width: Val::Px(width),
+            height: Val::Px(55.),
+            margin: UiRect::all(Val::Px(8.)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        background_color: BUTTON_COLOR.into(),
+        ..default()
+    };
+    let spaced = |text: TextBundle| {
+        text.with_text_alignment(TextAlignment::Center)
+            .with_style(Style {
+                margin: UiRect::top(Val::Px(25.)),
+                ..default()
+            })
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                margin: UiRect::all(Val::Auto),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::rgba(0.25, 0.15, 0.15, 0.7).into(), //Grey
+            ..default()
+        })
+        .insert(PlayfieldPanel)
+        .with_children(|parent| {
+            parent
+                .spawn(ButtonBundle {
+                    style: Style {
+                        width: Val::Px(250.0),
+                        height: Val::Px(65.0),
+                        margin: UiRect {
+                            left: Val::Px(10.),
+                            top: Val::Px(10.),
+                            ..default()
+                        },
+                        align_self: AlignSelf::FlexStart,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: BUTTON_COLOR.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(language.tr("Return to menu"), text_style(35.)));
+                })
+                .insert(LanBackButton);
+            parent.spawn(TextBundle::from_section(language.tr("LAN race"), text_style(55.)));
+
+            parent
+                .spawn(button(250.))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(language.tr("Host"), text_style(35.)));
+                })
+                .insert(HostButton);
+
+            parent.spawn(spaced(TextBundle::from_section(
+                language.tr("Host address:"),
+                text_style(30.),
+            )));
+            parent
+                .spawn(TextBundle::from_section(
+                    address.0.clone() + "_",
+                    text_style(35.),
+                ))
+                .insert(AddressText);
+            parent
+                .spawn(button(250.))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(language.tr("Join"), text_style(35.)));
+                })
+                .insert(JoinButton);
+
+            parent
+                .spawn(spaced(TextBundle::from_section(
+                    status.0.clone(),
+                    text_style(30.),
+                )))
+                .insert(StatusText);
+            parent.spawn(spaced(TextBundle::from_section(
+                language.tr(
+                    "Both games need the same settings.ron and enemies.ron.\nThe run starts as soon as the other game connects.",
+                ),
+                text_style(20.),
+            )));
+        })
+        .insert(LanScreen);
+}
+
+///Colors a button by its [Interaction].
+fn hover_color(interaction: Interaction) -> BackgroundColor {
+    if interaction == Interaction::None {
+        BUTTON_COLOR.into()
+    } else {
+        HOVERED_COLOR.into()
+    }
+}
+
+///Handles interactions with [LanBackButton].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `interaction_query` - [Query] for [LanBackButton] and its [Interaction] when changed.
+/// * `next` - [NextState] of [GameState].
+fn back_button_interaction(
+    mut commands: Commands,
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<LanBackButton>),
+    >,
+    mut next: ResMut<NextState<GameState>>,
+) {
+    for (interaction, mut color) in &mut interaction_query {
+        *color = hover_color(*interaction);
+        if *interaction == Interaction::Pressed {
+            commands.remove_resource::<LanConnection>();
+            next.set(GameState::MainMenu);
+        }
+    }
+}
+
+///Handles interactions with [HostButton]. When clicked, a [LanConnection] starts waiting on [DEFAULT_PORT].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `interaction_query` - [Query] for [HostButton] and its [Interaction] when changed.
+/// * `connection` - [LanConnection], the port is already taken if hosting.
+/// * `status` - [LanStatus].
+/// * `preferences` - [Preferences], texts are in its `language`.
+fn host_button_interaction(
+    mut commands: Commands,
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<HostButton>),
+    >,
+    connection: Option<Res<LanConnection>>,
+    mut status: ResMut<LanStatus>,
+    preferences: Res<Preferences>,
+) {
+    let language = preferences.language;
+    for (interaction, mut color) in &mut interaction_query {
+        *color = hover_color(*interaction);
+        if *interaction != Interaction::Pressed
+            || connection
+                .as_ref()
+                .is_some_and(|connection| connection.role == LanRole::Host)
+        {
+            continue;
+        }
+        match LanConnection::host(("0.0.0.0", DEFAULT_PORT)) {
+            Ok(connection) => {
+                status.0 = match local_ip() {
+                    Some(ip) => format!("{} {}", language.tr("Waiting for a player, join"), ip),
+                    None => format!(
+                        "{} {}",
+                        language.tr("Waiting for a player on port"),
+                        DEFAULT_PORT
+                    ),
+                };
+                commands.insert_resource(connection);
+            }
+            Err(err) => status.0 = format!("{} {}", language.tr("Can't host:"), err),
+        }
+    }
+}
+
+///Joins the host typed in [TypedAddress].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `address` - [TypedAddress].
+/// * `status` - [LanStatus].
+/// * `language` - [Language] of [LanStatus].
+fn join(
+    commands: &mut Commands,
+    address: &TypedAddress,
+    status: &mut LanStatus,
+    language: Language,
+) {
+    let connection = parse_address(&address.0)
+        .map_err(|err| language.tr(err).to_string())
+        .and_then(|host| {
+            LanConnection::join(host)
+                .map_err(|err| format!("{} {}", language.tr("Can't join:"), err))
+        });
+    match connection {
+        Ok(connection) => {
+            if let Some(host) = connection.peer {
+                status.0 = format!("{} {}...", language.tr("Connecting to"), host);
+            }
+            commands.insert_resource(connection);
+        }
+        Err(err) => status.0 = err,
+    }
+}
+
+///Handles interactions with [JoinButton].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `interaction_query` - [Query] for [JoinButton] and its [Interaction] when changed.
+/// * `address` - [TypedAddress].
+/// * `status` - [LanStatus].
+/// * `preferences` - [Preferences], texts are in its `language`.
+fn join_button_interaction(
+    mut commands: Commands,
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<JoinButton>),
+    >,
+    address: Res<TypedAddress>,
+    mut status: ResMut<LanStatus>,
+    preferences: Res<Preferences>,
+) {
+    for (interaction, mut color) in &mut interaction_query {
+        *color = hover_color(*interaction);
+        if *interaction == Interaction::Pressed {
+            join(&mut commands, &address, &mut status, preferences.language);
+        }
+    }
+}
+
+///Edits [TypedAddress] as the player types. Enter joins, like clicking [JoinButton].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `characters` - Reads [ReceivedCharacter] events.
+/// * `keys` - Keyboard input.
+/// * `address` - [TypedAddress].
+/// * `status` - [LanStatus].
+/// * `preferences` - [Preferences], texts are in its `language`.
+fn type_address(
+    mut commands: Commands,
+    mut characters: EventReader<ReceivedCharacter>,
+    keys: Res<Input<KeyCode>>,
+    mut address: ResMut<TypedAddress>,
+    mut status: ResMut<LanStatus>,
+    preferences: Res<Preferences>,
+) {
+    for event in characters.iter() {
+        if !event.char.is_control()
+            && !event.char.is_whitespace()
+            && address.0.chars().count() < MAX_ADDRESS_LENGTH
+        {
+            address.0.push(event.char);
+        }
+    }
+    if keys.just_pressed(KeyCode::Back) {
+        address.0.pop();
+    }
+    if keys.just_pressed(KeyCode::Return) {
+        join(&mut commands, &address, &mut status, preferences.language);
+    }
+}
+
+///Updates [AddressText] and [StatusText]. An error of [LanConnection] replaces [LanStatus] and closes the connection,
+/// so the player can try again.
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `connection` - [LanConnection], if hosting or joining.
+/// * `address` - [TypedAddress].
+/// * `status` - [LanStatus].
+/// * `preferences` - [Preferences], texts are in its `language`.
+/// * `address_q` - [Query] for [AddressText].
+/// * `status_q` - [Query] for [StatusText].
+fn refresh_lan_screen(
+    mut commands: Commands,
+    connection: Option<Res<LanConnection>>,
+    address: Res<TypedAddress>,
+    mut status: ResMut<LanStatus>,
+    preferences: Res<Preferences>,
+    mut address_q: Query<&mut Text, (With<AddressText>, Without<StatusText>)>,
+    mut status_q: Query<&mut Text, (With<StatusText>, Without<AddressText>)>,
+) {
+    if let Some(error) = connection.and_then(|connection| connection.error) {
+        status.0 = preferences.language.tr(error).to_string();
+        commands.remove_resource::<LanConnection>();
+    }
+    if address.is_changed() {
+        for mut text in &mut address_q {
+            text.sections[0].value = address.0.clone() + "_";
+        }
+    }
+    if status.is_changed() {
+        for mut text in &mut status_q {
+            text.sections[0].value = status.0.clone();
+        }
+    }
+}
+
+///Despawns all [LanScreen] [entities](Entity).
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `query` - [Query] for [LanScreen].
+fn despawn_lan_screen(mut commands: Commands, query: Query<Entity, With<LanScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{
+    highscore::Leaderboard, playfield::PlayfieldPanel, preferences::Preferences, GameState,
+};
+use bevy::prelude::*;
+
+///[Plugin] taking care of the screen listing [Leaderboard].
+pub struct LeaderboardScreenPlugin;
+
+///Labels all [entities](Entity) which are spawned on enter to [GameState::Leaderboard] and despawn on exit.
+#[derive(Component)]
+struct LeaderboardScreen;
+
+///Button in the top left corner that takes player back to the main menu. When clicked, [GameState] changes
+/// to [GameState::MainMenu]. When hovered, its background color changes until unhovered.
+#[derive(Component)]
+struct LeaderboardBackButton;
+
+impl Plugin for LeaderboardScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Leaderboard), spawn_leaderboard)
+            .add_systems(
+                Update,
+                back_button_interaction.run_if(in_state(GameState::Leaderboard)),
+            )
+            .add_systems(OnExit(GameState::Leaderboard), despawn_leaderboard);
+    }
+}
+
+///Spawns [LeaderboardScreen] with one row for every run in [Leaderboard] and [LeaderboardBackButton].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `assets` - [AssetServer]. Used to load font.
+/// * `leaderboard` - [Leaderboard].
+/// * `preferences` - [Preferences], texts are in its `language`.
+fn spawn_leaderboard(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    leaderboard: Res<Leaderboard>,
+    preferences: Res<Preferences>,
+) {
+    let language = preferences.language;
+    let font = assets.load("fonts\\Love_Letters.ttf");
+    let title_style = TextStyle {
+        font: font.clone(),
+        font_size: 55.0,
+        color: Color::rgb(0.9, 0.9, 0.9),
+    };
+    let row_style = TextStyle {
+        font: font.clone(),
+        font_size: 24.0,
+        color: Color::rgb(0.9, 0.9, 0.9),
+    };
+    let seed_style = TextStyle {
+        font: font.clone(),
+        font_size: 16.0,
+        color: Color::rgb(0.6, 0.6, 0.6),
+    };
+    let button_style = TextStyle {
+        font,
+        font_size: 35.0,
+        color: Color::rgb(0.9, 0.9, 0.9),
+    };
+    //Column widths in px: rank, name, score, date, duration, max speed, hits
+    let columns = [30., 150., 80., 120., 70., 60., 50.];
+    let row = |parent: &mut ChildBuilder, cells: [String; 7], style: &TextStyle| {
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Row,
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                for (cell, width) in cells.into_iter().zip(columns) {
+                    parent.spawn(
+                        TextBundle::from_section(cell, style.clone()).with_style(Style {
+                            width: Val::Px(width),
+                            ..default()
+                        }),
+                    );
+                }
+            });
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                margin: UiRect::all(Val::Auto),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::rgba(0.25, 0.15, 0.15, 0.7).into(), //Grey
+            ..default()
+        })
+        .insert(PlayfieldPanel)
+        .with_children(|parent| {
+            parent
+                .spawn(ButtonBundle {
+                    style: Style {
+                        width: Val::Px(250.0),
+                        height: Val::Px(65.0),
+                        margin: UiRect {
+                            left: Val::Px(10.),
+                            top: Val::Px(10.),
+                            ..default()
+                        },
+                        align_self: AlignSelf::FlexStart,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::rgba(0., 0., 0., 0.1).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        language.tr("Return to menu"),
+                        button_style,
+                    ));
+                })
+                .insert(LeaderboardBackButton);
+            parent.spawn(
+                TextBundle::from_section(language.tr("Leaderboard"), title_style).with_style(
+                    Style {
+                        margin: UiRect::vertical(Val::Px(15.)),
+                        ..default()
+                    },
+                ),
+            );
+            row(
+                parent,
+                ["#", "Name", "Score", "Date", "Time", "Speed", "Hits"]
+                    .map(|heading| language.tr(heading).to_string()),
+                &row_style,
+            );
+            if leaderboard.entries.is_empty() {
+                parent.spawn(
+                    TextBundle::from_section(language.tr("No runs yet"), row_style.clone())
+                        .with_style(Style {
+                            margin: UiRect::top(Val::Px(20.)),
+                            ..default()
+                        }),
+                );
+            }
+            for (rank, entry) in leaderboard.entries.iter().enumerate() {
+                row(
+                    parent,
+                    [
+                        (rank + 1).to_string(),
+                        entry.name.clone(),
+                        entry.score.to_string(),
+                        entry.date.clone(),
+                        format!(
+                            "{}:{:02}",
+                            entry.duration as u32 / 60,
+                            entry.duration as u32 % 60
+                        ),
+                        format!("{:.1}", entry.max_speed),
+                        entry.hits.to_string(),
+                    ],
+                    &row_style,
+                );
+                parent.spawn(TextBundle::from_section(
+                    format!("{} {}", language.tr("seed"), entry.seed),
+                    seed_style.clone(),
+                ));
+            }
+        })
+        .insert(LeaderboardScreen);
+}
+
+///Handles interactions with [LeaderboardBackButton].
+/// # Arguments
+/// * `interaction_query` - [Query] for [LeaderboardBackButton] and its [Interaction] when changed.
+/// * `next` - [NextState] of [GameState].
+fn back_button_interaction(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<LeaderboardBackButton>),
+    >,
+    mut next: ResMut<NextState<GameState>>,
+) {
+    for (interaction, mut color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                next.set(GameState::MainMenu);
+            }
+            Interaction::Hovered => {
+                *color = Color::rgba(0., 0., 0., 0.7).into();
+            }
+            Interaction::None => {
+                *color = Color::rgba(0., 0., 0., 0.1).into();
+            }
+        }
+    }
+}
+
+///Despawns all [LeaderboardScreen] [entities](Entity).
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `query` - [Query] for [LeaderboardScreen].
+fn despawn_leaderboard(mut commands: Commands, query: Query<Entity, With<LeaderboardScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,436 @@
+use crate::{
+    catalogue::EnemyCatalogue, playfield::PlayfieldPanel, preferences::Preferences, GameState,
+};
+use bevy::app::AppExit;
+use bevy::asset::{HandleId, LoadState};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::Deserialize;
+use std::fs::read_to_string;
+
+///File describing every [TextureAtlas] and every other file the game waits for before the main menu.
+pub const MANIFEST_FILE: &str = "./assets/manifest.ron";
+
+///Keys of [Atlases] the code asks for, the manifest must contain all of them.
+pub const REQUIRED_ATLASES: [&str; 11] = [
+    "clouds",
+    "energy",
+    "fruit",
+    "holes",
+    "pixies",
+    "planes",
+    "planets",
+    "rainbow",
+    "stars",
+    "unicorn",
+    "white_holes",
+];
+
+///[Plugin] loading all assets listed in [MANIFEST_FILE] during [GameState::Loading], showing what went wrong in
+/// [GameState::AssetError] if anything fails.
+pub struct LoadingPlugin;
+
+///A single sprite sheet in [AssetManifest], cut into a grid of sprites.
+/// # Fields
+/// * `key` - Key the sheet is found under in [Atlases].
+/// * `file` - Image in the assets folder.
+/// * `tile` - Size of a single sprite.
+/// * `columns` - Number of columns of the grid.
+/// * `rows` - Number of rows of the grid.
+/// * `padding` - Space between sprites.
+/// * `offset` - Space before the first sprite.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AtlasEntry {
+    pub key: String,
+    pub file: String,
+    pub tile: (f32, f32),
+    #[serde(default = "default_grid")]
+    pub columns: usize,
+    #[serde(default = "default_grid")]
+    pub rows: usize,
+    #[serde(default)]
+    pub padding: Option<(f32, f32)>,
+    #[serde(default)]
+    pub offset: Option<(f32, f32)>,
+}
+
+///Default of [AtlasEntry] `columns` and `rows`.
+fn default_grid() -> usize {
+    1
+}
+
+///Content of [MANIFEST_FILE].
+/// # Fields
+/// * `atlases` - [AtlasEntry] of every sprite sheet.
+/// * `files` - Other files, like fonts and sounds, which have to load before the main menu.
+/// * `optional` - Files the game can do without, like music. They are loaded the same way, but failing to load them
+///   is only logged.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AssetManifest {
+    pub atlases: Vec<AtlasEntry>,
+    #[serde(default)]
+    pub files: Vec<String>,
+    #[serde(default)]
+    pub optional: Vec<String>,
+}
+
+impl AssetManifest {
+    ///Loads [AssetManifest] from a RON file, checking that it contains every key of [REQUIRED_ATLASES] once
+    /// and that every grid is valid.
+    pub fn load(path: &str) -> Result<AssetManifest, String> {
+        let content = read_to_string(path).map_err(|error| format!("{}: {}", path, error))?;
+        let manifest: AssetManifest = ron::from_str(&content).map_err(|error| {
+            format!(
+                "{}:{}:{}: {}",
+                path, error.position.line, error.position.col, error.code
+            )
+        })?;
+        manifest
+            .validate()
+            .map_err(|reason| format!("{}: {}", path, reason))?;
+        Ok(manifest)
+    }
+
+    ///Checks the manifest, returning what is wrong with it.
+    fn validate(&self) -> Result<(), String> {
+        for (i, entry) in self.atlases.iter().enumerate() {
+            if self.atlases[..i].iter().any(|other| other.key == entry.key) {
+                return Err(format!("atlas `{}` is listed twice", entry.key));
+            }
+            if entry.columns == 0 || entry.rows == 0 || entry.tile.0 <= 0. || entry.tile.1 <= 0. {
+                return Err(format!("atlas `{}` has an empty grid", entry.key));
+            }
+        }
+        match REQUIRED_ATLASES
+            .iter()
+            .find(|key| self.atlases.iter().all(|entry| entry.key != **key))
+        {
+            Some(key) => Err(format!("atlas `{}` is missing", key)),
+            None => Ok(()),
+        }
+    }
+}
+
+///Every [TextureAtlas] from [AssetManifest], by its key.
+#[derive(Resource, Default)]
+pub struct Atlases(pub HashMap<String, Handle<TextureAtlas>>);
+
+impl Atlases {
+    ///[Handle] of the [TextureAtlas] under `key`. An empty handle if there is none, so nothing is drawn.
+    pub fn get(&self, key: &str) -> Handle<TextureAtlas> {
+        self.0.get(key).cloned().unwrap_or_default()
+    }
+}
+
+///Handles of [AssetManifest] `files` and `optional` files, kept so they stay loaded.
+/// # Fields
+/// * `files` - Handles of `files`.
+/// * `optional` - Handles of `optional` files.
+#[derive(Resource, Default)]
+struct Preloaded {
+    files: Vec<HandleUntyped>,
+    optional: Vec<HandleUntyped>,
+}
+
+///Everything that went wrong while loading, shown in [GameState::AssetError].
+#[derive(Resource, Default)]
+struct LoadingErrors(Vec<String>);
+
+///Labels all [entities](Entity) of the loading screen.
+#[derive(Component)]
+struct LoadingScreen;
+
+///Labels all [entities](Entity) of the error screen.
+#[derive(Component)]
+struct ErrorScreen;
+
+///Button on the error screen which goes on to the main menu without the missing files. When hovered, its background
+/// color changes until unhovered.
+#[derive(Component)]
+struct ContinueButton;
+
+///Button on the error screen which closes the game. When hovered, its background color changes until unhovered.
+#[derive(Component)]
+struct QuitButton;
+
+///Background color of a button.
+const BUTTON_COLOR: Color = Color::rgba(0., 0., 0., 0.1);
+///Background color of a hovered button.
+const HOVERED_COLOR: Color = Color::rgba(0., 0., 0., 0.7);
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LoadingErrors>()
+            .add_systems(PreStartup, load_assets)
+            .add_systems(OnEnter(GameState::Loading), spawn_loading_screen)
+            .add_systems(Update, check_loading.run_if(in_state(GameState::Loading)))
+            .add_systems(OnExit(GameState::Loading), despawn::<LoadingScreen>)
+            .add_systems(OnEnter(GameState::AssetError), spawn_error_screen)
+            .add_systems(
+                Update,
+                error_buttons.run_if(in_state(GameState::AssetError)),
+            )
+            .add_systems(OnExit(GameState::AssetError), despawn::<ErrorScreen>);
+    }
+}
+
+///Reads [MANIFEST_FILE] and starts loading everything in it, inserting [Atlases].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `assets` - [AssetServer].
+/// * `texture_atlases` - [Assets] of type [TextureAtlas].
+/// * `errors` - [LoadingErrors], a broken manifest is one.
+fn load_assets(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut errors: ResMut<LoadingErrors>,
+) {
+    let manifest = AssetManifest::load(MANIFEST_FILE).unwrap_or_else(|error| {
+        errors.0.push(error);
+        AssetManifest {
+            atlases: Vec::new(),
+            files: Vec::new(),
+            optional: Vec::new(),
+        }
+    });
+    let atlases = manifest
+        .atlases
+        .iter()
+        .map(|entry| {
+            let atlas = TextureAtlas::from_grid(
+                assets.load(entry.file.as_str()),
+                Vec2::new(entry.tile.0, entry.tile.1),
+                entry.columns,
+                entry.rows,
+                entry.padding.map(|(x, y)| Vec2::new(x, y)),
+                entry.offset.map(|(x, y)| Vec2::new(x, y)),
+            );
+            (entry.key.clone(), texture_atlases.add(atlas))
+        })
+        .collect();
+    let load = |files: &[String]| {
+        files
+            .iter()
+            .map(|file| assets.load_untyped(file.as_str()))
+            .collect()
+    };
+    commands.insert_resource(Atlases(atlases));
+    commands.insert_resource(Preloaded {
+        files: load(&manifest.files),
+        optional: load(&manifest.optional),
+    });
+}
+
+///Waits until every image of [Atlases] and [EnemyCatalogue] and every preloaded file is loaded or failed, then goes on
+/// to [GameState::MainMenu], or to [GameState::AssetError] if anything but an optional file went wrong.
+/// # Arguments
+/// * `assets` - [AssetServer].
+/// * `texture_atlases` - [Assets] of type [TextureAtlas].
+/// * `atlases` - [Atlases].
+/// * `catalogue` - [EnemyCatalogue].
+/// * `preloaded` - [Preloaded].
+/// * `errors` - [LoadingErrors].
+/// * `next` - [NextState] of [GameState].
+fn check_loading(
+    assets: Res<AssetServer>,
+    texture_atlases: Res<Assets<TextureAtlas>>,
+    atlases: Res<Atlases>,
+    catalogue: Res<EnemyCatalogue>,
+    preloaded: Res<Preloaded>,
+    mut errors: ResMut<LoadingErrors>,
+    mut next: ResMut<NextState<GameState>>,
+) {
+    let ids: Vec<(HandleId, bool)> = atlases
+        .0
+        .values()
+        .chain(&catalogue.atlases)
+        .filter_map(|atlas| texture_atlases.get(atlas))
+        .map(|atlas| atlas.texture.id())
+        .chain(preloaded.files.iter().map(|handle| handle.id()))
+        .map(|id| (id, false))
+        .chain(preloaded.optional.iter().map(|handle| (handle.id(), true)))
+        .collect();
+    let mut failed = Vec::new();
+    for (id, optional) in ids {
+        match assets.get_load_state(id) {
+            LoadState::Loaded => {}
+            LoadState::Failed => failed.push((id, optional)),
+            _ => return,
+        }
+    }
+    for (id, optional) in failed {
+        let file = assets
+            .get_handle_path(id)
+            .map_or("unknown file".to_string(), |path| {
+                path.path().display().to_string()
+            });
+        if optional {
+            warn!("Can't load {}, going on without it", file);
+        } else {
+            error!("Can't load {}", file);
+            errors.0.push(format!("Can't load {}", file));
+        }
+    }
+    if errors.0.is_empty() {
+        next.set(GameState::MainMenu);
+    } else {
+        next.set(GameState::AssetError);
+    }
+}
+
+///Spawns [LoadingScreen]. Uses the built-in font, the game's own one may not be loaded yet.
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `preferences` - [Preferences], texts are in its `language`.
+fn spawn_loading_screen(mut commands: Commands, preferences: Res<Preferences>) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                margin: UiRect::all(Val::Auto),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                preferences.language.tr("Loading..."),
+                TextStyle {
+                    font_size: 40.,
+                    color: Color::rgb(0.3, 0.2, 0.4),
+                    ..default()
+                },
+            ));
+        })
+        .insert(LoadingScreen);
+}
+
+///Spawns [ErrorScreen] listing [LoadingErrors], with [ContinueButton] and [QuitButton]. Uses the built-in font,
+/// the game's own one may be what failed to load. The errors themselves name files and their contents, so they
+/// stay English, the rest is in [Preferences] `language`.
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `errors` - [LoadingErrors].
+/// * `preferences` - [Preferences].
+fn spawn_error_screen(
+    mut commands: Commands,
+    errors: Res<LoadingErrors>,
+    preferences: Res<Preferences>,
+) {
+    let language = preferences.language;
+    let text_style = |font_size| TextStyle {
+        font_size,
+        color: Color::rgb(0.9, 0.9, 0.9),
+        ..default()
+    };
+    let button = ButtonBundle {
+        style: Style {
+            width: Val::Px(250.),
+            height: Val::Px(55.),
+            margin: UiRect::all(Val::Px(8.)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        background_color: BUTTON_COLOR.into(),
+        ..default()
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                margin: UiRect::all(Val::Auto),
+                padding: UiRect::all(Val::Px(20.)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::rgba(0.25, 0.15, 0.15, 0.7).into(), //Grey
+            ..default()
+        })
+        .insert(PlayfieldPanel)
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                language.tr("Some files could not be loaded"),
+                text_style(30.),
+            ));
+            for error in &errors.0 {
+                parent.spawn(
+                    TextBundle::from_section(error.clone(), text_style(16.)).with_style(Style {
+                        margin: UiRect::top(Val::Px(10.)),
+                        max_width: Val::Px(600.),
+                        ..default()
+                    }),
+                );
+            }
+            parent.spawn(
+                TextBundle::from_section(
+                    language.tr(
+                        "Check that the assets folder is next to the game.\nThe game can go on, but things may be invisible or silent.",
+                    ),
+                    text_style(16.),
+                )
+                .with_text_alignment(TextAlignment::Center)
+                .with_style(Style {
+                    margin: UiRect::vertical(Val::Px(25.)),
+                    ..default()
+                }),
+            );
+            parent
+                .spawn(button.clone())
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(language.tr("Continue anyway"), text_style(24.)));
+                })
+                .insert(ContinueButton);
+            parent
+                .spawn(button)
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(language.tr("Quit"), text_style(24.)));
+                })
+                .insert(QuitButton);
+        })
+        .insert(ErrorScreen);
+}
+
+///Handles interactions with [ContinueButton] and [QuitButton].
+/// # Arguments
+/// * `interaction_query` - [Query] for both buttons and their [Interaction] when changed.
+/// * `next` - [NextState] of [GameState].
+/// * `exit` - Sends [AppExit] when quitting.
+fn error_buttons(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor, Option<&QuitButton>),
+        (
+            Changed<Interaction>,
+            Or<(With<ContinueButton>, With<QuitButton>)>,
+        ),
+    >,
+    mut next: ResMut<NextState<GameState>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    for (interaction, mut color, quit) in &mut interaction_query {
+        *color = match interaction {
+            Interaction::None => BUTTON_COLOR.into(),
+            _ => HOVERED_COLOR.into(),
+        };
+        if *interaction == Interaction::Pressed {
+            if quit.is_some() {
+                exit.send(AppExit);
+            } else {
+                next.set(GameState::MainMenu);
+            }
+        }
+    }
+}
+
+///Despawns all [entities](Entity) labeled by `T`.
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `query` - [Query] for `T`.
+fn despawn<T: Component>(mut commands: Commands, query: Query<Entity, With<T>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
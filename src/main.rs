@@ -1,38 +1,62 @@
 #![windows_subsystem = "windows"]
 
+use bevy::ecs::schedule::ExecutorKind;
 use bevy::prelude::*;
+use bevy::render::camera::ScalingMode;
 use bevy::window::*;
 use bevy::winit::WinitWindows;
 use bevy_kira_audio::prelude::*;
 use bevy_pkv::PkvStore;
 use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
 use winit::window::Icon;
 pub const CLEAR: Color = Color::rgb(0.75, 0.70, 1.);
-pub const RESOLUTION: f32 = 1920. / 1080.;
+///Length of a single gameplay tick. Gameplay and physics advance by exactly this much per tick, no matter the framerate.
+pub const TICK: f32 = 1. / 60.;
 
 //MODULE DECLARATION
-///Audio related functionalities.
+///Outlines of [Damaging](collisions::Damaging) entities and the tint of energy bars
+/// # Contains
+/// * [AccessibilityPlugin]
+/// * [Outline]
+/// * [OUTLINE_COLOR]
+/// * [OUTLINE_SCALE]
+/// * [outline_transform]
+/// * [spawn_outlines]
+/// * [follow_outlines]
+/// * [tint_energy_bars]
+mod accessibility;
+///Sound effects.
 /// # Contains
 /// * [GameAudioPlugin]
-/// * [play_background_music]
+/// * [SfxChannel]
+/// * [SfxEvent]
+/// * [SoundDef]
+/// * [LastPlayed]
+/// * [play_sfx]
 mod audio;
 ///Functionalities corelating with [BasicObject].
 /// # Contains
 /// * [BasicObject]
 /// * [BasicBundle]
 /// * [create_basic]
-/// * [create_full_choc]
-/// * [create_part_choc]
-/// * [create_egg]
-/// * [create_lolly]
-/// * [create_love]
-/// * [create_drink]
 mod basic;
 ///Functionalities corelating with [Hole]
 /// # Contains
 /// * [Hole]
 /// * [create_hole]
 mod blackhole;
+///Data driven definitions of [BasicObject] kinds
+/// # Contains
+/// * [CATALOGUE_FILE]
+/// * [Shape]
+/// * [ColliderPart]
+/// * [EnemyDef]
+/// * [CatalogueError]
+/// * [EnemyCatalogue]
+/// * [catalogue_from_args]
+/// * [load_catalogue_sheets]
+mod catalogue;
 ///Functionalities corelating with [Cloud]
 /// # Contains
 /// * [CloudPlugin]
@@ -46,6 +70,39 @@ mod clouds;
 /// * [manage_special_collisions]
 /// * [deal_damage]
 mod collisions;
+///Input devices and their bindings
+/// # Contains
+/// * [ControlsPlugin]
+/// * [InputDevice]
+/// * [Action]
+/// * [Bindable]
+/// * [Bound]
+/// * [Controls]
+/// * [device_is]
+/// * [collect_mouse]
+/// * [collect_keyboard]
+/// * [collect_gamepad]
+mod controls;
+///Functionalities corelating with [ControlsScreen]
+/// # Contains
+/// * [ControlsScreenPlugin]
+/// * [ControlsScreen]
+/// * [ControlsBackButton]
+/// * [DeviceButton]
+/// * [Binding]
+/// * [BindingButton]
+/// * [BindingLabel]
+/// * [Rebinding]
+/// * [load_controls]
+/// * [save_controls]
+/// * [spawn_controls_screen]
+/// * [back_button_interaction]
+/// * [device_button_interaction]
+/// * [binding_button_interaction]
+/// * [capture_binding]
+/// * [refresh_controls_screen]
+/// * [despawn_controls_screen]
+mod controls_screen;
 ///Cursor functionalities
 /// # Contains
 /// * [CursorPlugin]
@@ -57,8 +114,12 @@ mod cursor;
 /// * [EndScreenPlugin]
 /// * [EndScreen]
 /// * [ContinueButton]
+/// * [NameText]
+/// * [SummaryLine]
 /// * [spawn_endscreen]
 /// * [continue_interaction]
+/// * [refresh_summary]
+/// * [type_name]
 /// * [despawn_endscreen]
 mod endscreen;
 ///Functionalities corelating with [EnergyBar]
@@ -70,10 +131,17 @@ mod energybars;
 /// # Contains
 /// * [FallPlugin]
 /// * [FallTimer]
+/// * [FallSpeed]
 /// * [ingame_despawn]
 /// * [endscreen_despawn]
 /// * [movement]
 mod falling;
+///Functionalities corelating with [Fruit]
+/// # Contains
+/// * [FRUIT_SPRITES]
+/// * [Fruit]
+/// * [create_fruit]
+mod fruit;
 ///Functionalities corelating with [Rainbow]
 /// # Contains
 /// * [RainbowPlugin]
@@ -86,11 +154,78 @@ mod falling;
 /// * [despawn_trails]
 /// * [despawn_rainbow]
 mod homing;
+///Racing another game over LAN
+/// # Contains
+/// * [LanPlugin]
+/// * [LanRole]
+/// * [GhostState]
+/// * [LanMessage]
+/// * [LanConnection]
+/// * [Ghost]
+/// * [parse_address]
+/// * [local_ip]
+/// * [handshake]
+/// * [start_lan_run]
+/// * [send_state]
+/// * [receive_states]
+/// * [spawn_ghost]
+/// * [update_ghost]
+/// * [leave_lan]
+mod lan;
+///Functionalities corelating with [LanScreen]
+/// # Contains
+/// * [LanScreenPlugin]
+/// * [LanScreen]
+/// * [LanBackButton]
+/// * [HostButton]
+/// * [JoinButton]
+/// * [AddressText]
+/// * [StatusText]
+/// * [TypedAddress]
+/// * [LanStatus]
+/// * [spawn_lan_screen]
+/// * [back_button_interaction]
+/// * [host_button_interaction]
+/// * [join_button_interaction]
+/// * [type_address]
+/// * [refresh_lan_screen]
+/// * [despawn_lan_screen]
+mod lan_screen;
+///Functionalities corelating with [LeaderboardScreen]
+/// # Contains
+/// * [LeaderboardScreenPlugin]
+/// * [LeaderboardScreen]
+/// * [LeaderboardBackButton]
+/// * [spawn_leaderboard]
+/// * [back_button_interaction]
+/// * [despawn_leaderboard]
+mod leaderboard_screen;
+///Loading assets from the manifest and the loading and error screens
+/// # Contains
+/// * [LoadingPlugin]
+/// * [MANIFEST_FILE]
+/// * [REQUIRED_ATLASES]
+/// * [AtlasEntry]
+/// * [AssetManifest]
+/// * [Atlases]
+/// * [Preloaded]
+/// * [LoadingErrors]
+/// * [load_assets]
+/// * [check_loading]
+/// * [spawn_loading_screen]
+/// * [spawn_error_screen]
+/// * [error_buttons]
+mod loading;
 ///Functionalities corelating with [MainMenu]
 /// # Contains
 /// * [MenuPlugin]
 /// * [MainMenu]
 /// * [TutorialButton]
+/// * [ReplayButton]
+/// * [LeaderboardButton]
+/// * [ControlsButton]
+/// * [PlayersButton]
+/// * [LanButton]
 /// * [PlayButton]
 /// * [Flickering]
 /// * [LoadTimer]
@@ -98,18 +233,93 @@ mod homing;
 /// * [despawn_menu]
 /// * [click2play]
 /// * [tutorial_button_interaction]
+/// * [replay_button_interaction]
+/// * [leaderboard_button_interaction]
+/// * [controls_button_interaction]
+/// * [players_button_interaction]
+/// * [lan_button_interaction]
+/// * [HIGHLIGHT_COLOR]
 /// * [change_flick]
 /// * [load_game]
 mod mainmenu;
 /// # Contains
 /// * [StructurePlugin]
 /// * [Enemy]
+/// * [ObjectParams]
 /// * [SpawnEvent]
 /// * [Structure]
+/// * [SpawnHistory]
+/// * [Map]
 /// * [spawn_structure]
 /// * [despawn_structure]
 /// * [spawning]
 mod map_layout;
+///Music following [GameState] and [Speed], configured in a file
+/// # Contains
+/// * [MusicPlugin]
+/// * [MUSIC_FILE]
+/// * [MusicLayer]
+/// * [Tempo]
+/// * [MusicConfig]
+/// * [MusicError]
+/// * [music_from_args]
+/// * [MusicMix]
+/// * [start_music]
+/// * [mix_music]
+mod music;
+///The options screen
+/// # Contains
+/// * [OptionsScreenPlugin]
+/// * [OptionsScreen]
+/// * [OptionsBackButton]
+/// * [Section]
+/// * [OpenSection]
+/// * [Setting]
+/// * [Slider]
+/// * [SliderFill]
+/// * [SliderLabel]
+/// * [Choice]
+/// * [ChoiceButton]
+/// * [spawn_options_screen]
+/// * [back_button_interaction]
+/// * [choice_button_interaction]
+/// * [drag_sliders]
+/// * [refresh_options_screen]
+/// * [save_options]
+/// * [despawn_options_screen]
+mod options_screen;
+///Spacing between structures and weighted structure picking
+/// # Contains
+/// * [PacingPlugin]
+/// * [Pacing]
+/// * [pick_weighted]
+/// * [reset_pacing]
+mod pacing;
+///Pausing the game
+/// # Contains
+/// * [PausePlugin]
+/// * [PauseState]
+/// * [PauseMenu]
+/// * [ResumeButton]
+/// * [QuitButton]
+/// * [toggle_pause]
+/// * [pause_on_focus_loss]
+/// * [unpause]
+/// * [reset_tick_clock]
+/// * [spawn_pause_menu]
+/// * [pause_menu_interaction]
+/// * [despawn_pause_menu]
+mod pause;
+///Functionalities corelating with [Pixie]
+/// # Contains
+/// * [PixiePlugin]
+/// * [MAX_SWARM]
+/// * [Flocking]
+/// * [Pixie]
+/// * [create_pixies]
+/// * [steer]
+/// * [flocking]
+mod pixie;
 ///Functionalities corelating with [Plane]
 /// # Contains
 /// * [PlanePlugin]
@@ -131,11 +341,17 @@ mod planet;
 /// # Contains
 /// * [PlayerPlugin]
 /// * [Player]
+/// * [MAX_PLAYER_HP]
+/// * [damage_sprite]
 /// * [ScreenShaker]
 /// * [Hidden]
+/// * [Captured]
 /// * [StarTimer]
 /// * [Star]
 /// * [gravity_interaction]
+/// * [GRAVITY]
+/// * [gravity_acceleration]
+/// * [detect_capture]
 /// * [point_distance]
 /// * [movement]
 /// * [movement]
@@ -148,6 +364,30 @@ mod planet;
 /// * [despawn_stars]
 /// * [star_movement]
 mod player;
+///Size of the playfield and letterboxing
+/// # Contains
+/// * [PlayfieldPlugin]
+/// * [Playfield]
+/// * [LETTERBOX]
+/// * [letterbox]
+/// * [PlayfieldPanel]
+/// * [spawn_background]
+/// * [fit_to_window]
+/// * [fit_panels]
+mod playfield;
+///Preferences which do not affect the game itself, stored with [PkvStore]
+/// # Contains
+/// * [PreferencesPlugin]
+/// * [PREFERENCES_KEY]
+/// * [Language]
+/// * [CZECH]
+/// * [Palette]
+/// * [COLORBLIND_STARS]
+/// * [Preferences]
+/// * [load_preferences]
+/// * [toggle_fullscreen]
+/// * [apply_window_mode]
+mod preferences;
 ///Functionalities corelating with [Speed]
 /// # Contains
 /// * [SpeedPlugin]
@@ -159,20 +399,23 @@ mod speed;
 ///Functionalities corelating with ingame [Text]
 /// # Contains
 /// * [TextPlugin]
+/// * [LARGE_HUD_SCALE]
+/// * [hud_scale]
+/// * [Hud]
 /// * [ScoreText]
-/// * [Score]
 /// * [EnergyText]
+/// * [spawn_hud]
 /// * [score_counter]
 /// * [energy_counter]
-/// * [score_update]
+/// * [score_text_update]
 /// * [energy_update]
-/// * [despawn_text]
-/// * [reset_score]
+/// * [despawn_hud]
 mod text;
 ///Functionalities corelating with [Tutorial]
 /// # Contains
 /// * [TutorialPlugin]
 /// * [Tutorial]
+/// * [TUTORIAL]
 /// * [BackButton]
 /// * [spawn_background]
 /// * [despawn_tutorial]
@@ -180,36 +423,172 @@ mod text;
 /// * [explain_game]
 /// * [backbutton_system]
 mod tutorial_screen;
+///Games with more than one player
+/// # Contains
+/// * [VersusPlugin]
+/// * [GameMode]
+/// * [PlayerResult]
+/// * [PlayerResults]
+/// * [reset_results]
+/// * [finish_results]
+/// * [spawn_other_players]
+mod versus;
+///Master, music and sound effect volume and muting, stored with [PkvStore]
+/// # Contains
+/// * [VolumePlugin]
+/// * [VOLUME_KEY]
+/// * [MUTE_KEY]
+/// * [Volume]
+/// * [MuteButton]
+/// * [mute_text]
+/// * [load_volume]
+/// * [mute_hotkey]
+/// * [mute_button_interaction]
+/// * [refresh_mute_buttons]
+mod volume;
+///Solid walls around the playfield and collision groups
+/// # Contains
+/// * [WallPlugin]
+/// * [Wall]
+/// * [PLAYERS]
+/// * [WALLS]
+/// * [ENEMIES]
+/// * [player_groups]
+/// * [enemy_groups]
+/// * [wall_rects]
+/// * [build_walls]
+mod walls;
+///Functionalities corelating with [WhiteHole]
+/// # Contains
+/// * [WhiteHole]
+/// * [create_white_hole]
+mod whitehole;
 
+///Running the game without a window or GPU.
+/// # Contains
+/// * [InputScript]
+/// * [ScriptStep]
+/// * [HeadlessConfig]
+/// * [headless_app]
+/// * [run]
+/// * [start_run]
+/// * [scripted_input]
+/// * [finish_run]
+mod headless;
+///Local leaderboard and stats of the current run
+/// # Contains
+/// * [HighScorePlugin]
+/// * [LeaderboardEntry]
+/// * [Leaderboard]
+/// * [RunStats]
+/// * [PendingEntry]
+/// * [load_leaderboard]
+/// * [reset_run_stats]
+/// * [track_run_stats]
+/// * [prepare_entry]
+/// * [save_name]
 mod highscore;
 mod import;
+///Recording and playing back gameplay input
+/// # Contains
+/// * [ReplayPlugin]
+/// * [SaveReplays]
+/// * [PendingInput]
+/// * [PendingInputs]
+/// * [TickInput]
+/// * [TickInputs]
+/// * [RecordedInput]
+/// * [Replay]
+/// * [Recording]
+/// * [Playback]
+/// * [start_recording]
+/// * [start_playback]
+/// * [sample_live_input]
+/// * [sample_replay_input]
+/// * [save_replay]
+/// * [stop_playback]
+mod replay;
+///Seedable random number generation
+/// # Contains
+/// * [RngPlugin]
+/// * [SeedConfig]
+/// * [GameRng]
+/// * [seed_from_args]
+/// * [pick_seed]
+/// * [spawn_rng]
+/// * [reseed]
+mod rng;
+///Functionalities corelating with [Score]
+/// # Contains
+/// * [ScorePlugin]
+/// * [Score]
+/// * [spawn_score]
+/// * [score_update]
+/// * [reset_score]
+mod score;
+///Loading [Settings] from a file
+/// # Contains
+/// * [SETTINGS_FILE]
+/// * [SettingsError]
+/// * [settings_from_args]
+mod settings;
 
+use accessibility::AccessibilityPlugin;
 use audio::GameAudioPlugin;
 use clouds::CloudPlugin;
 use collisions::CollPlugin;
+use controls::ControlsPlugin;
+use controls_screen::ControlsScreenPlugin;
 use cursor::CursorPlugin;
 use endscreen::EndScreenPlugin;
 use falling::FallPlugin;
 use highscore::HighScorePlugin;
 use homing::RainbowPlugin;
+use lan::LanPlugin;
+use lan_screen::LanScreenPlugin;
+use leaderboard_screen::LeaderboardScreenPlugin;
+use loading::LoadingPlugin;
 use mainmenu::MenuPlugin;
 use map_layout::MapPlugin;
+use music::MusicPlugin;
+use options_screen::OptionsScreenPlugin;
+use pacing::PacingPlugin;
+use pause::{PausePlugin, PauseState};
+use pixie::PixiePlugin;
 use plane::PlanePlugin;
 use player::PlayerPlugin;
+use playfield::{Playfield, PlayfieldPlugin};
+use preferences::PreferencesPlugin;
+use replay::ReplayPlugin;
+use rng::RngPlugin;
+use score::ScorePlugin;
 use speed::SpeedPlugin;
 use text::TextPlugin;
 use tutorial_screen::TutorialPlugin;
+use versus::VersusPlugin;
+use volume::VolumePlugin;
+use walls::WallPlugin;
 
 ///Enum containing possible [State]s
 /// # Fields
+/// * `Loading` - When state is set, the game waits for all assets to load
+/// * `AssetError` - When state is set, the screen listing assets which failed to load shows up
 /// * `MainMenu` - When state is set, the main menu loads
 /// * `Tutorial` - When state is set, the "How to play" section loads
 /// * `Game` - When state is set, the game itself loads
 /// * `EndScreen` - When state is set, the end screen loads
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, Default, States)]
+/// * `Leaderboard` - When state is set, the leaderboard loads
+/// * `Controls` - When state is set, the controls screen loads
+/// * `Lan` - When state is set, the screen for hosting and joining a LAN race loads
+/// * `Options` - When state is set, the options screen loads
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, Default, States, Deserialize)]
 pub enum GameState {
-    ///When state is set, the main menu loads
+    ///When state is set, the game waits for all assets to load
     #[default]
+    Loading,
+    ///When state is set, the screen listing assets which failed to load shows up
+    AssetError,
+    ///When state is set, the main menu loads
     MainMenu,
     ///When state is set, the "How to play" section loads
     Tutorial,
@@ -217,6 +596,26 @@ pub enum GameState {
     Game,
     ///When state is set, the end screen loads
     EndScreen,
+    ///When state is set, the leaderboard loads
+    Leaderboard,
+    ///When state is set, the controls screen loads
+    Controls,
+    ///When state is set, the screen for hosting and joining a LAN race loads
+    Lan,
+    ///When state is set, the options screen loads
+    Options,
+}
+
+///[SystemSet]s of the [FixedUpdate] schedule, which runs once per gameplay [TICK]. They only run in [GameState::Game]
+/// while the game is not paused.
+/// * `Input` - Input for the tick is sampled, see [replay::TickInputs].
+/// * `Logic` - Everything moving, spawning and despawning. Runs before physics.
+/// * `Collisions` - Reacting to collisions found by physics this tick.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GameplaySet {
+    Input,
+    Logic,
+    Collisions,
 }
 
 ///A [Timer] used for animations.
@@ -224,65 +623,47 @@ pub enum GameState {
 struct AnimationTimer(Timer);
 
 ///Contains constant values that are used in the code, mainly for better readability, but also debugging and easier changes.
+/// Loaded from [settings::SETTINGS_FILE] at startup, so balance can be tuned without recompiling.
 /// # Fields
 /// * `startup_speed` - A value at which speed is initialized at the start of the [GameState::Game].
 /// * `speed_scaling` - How fast does [Speed] scale with time.
 /// * `startup_score` - A value at which score is initialized at the start of the [GameState::Game].
 /// * `hit_resistence` - For how long is [Player] hidden after being hit.
 /// * `shakes` - How many shakes happen after being hit.
-#[derive(Resource)]
+/// * `player_hp` - How many hits [Player] survives, at most [player::MAX_PLAYER_HP].
+/// * `max_hp` - Most hp a [Fruit](fruit::Fruit) heals [Player] up to.
+/// * `energy_drain` - How much energy hiding uses per second.
+/// * `energy_regen` - How much energy regenerates per second while not hiding.
+/// * `energy_pickup` - How much energy an [EnergyBar](energybars::EnergyBar) refills.
+/// * `fall_speed` - How fast [FallTimer](falling::FallTimer) [entities](Entity) fall at [Speed] 1.
+/// * `gravity_radius` - Distance from which [Gravitating] [entities](Entity) affect [Player].
+/// * `gravity_softening` - Distance from a [Gravitating] [Entity] at which its pull peaks, closer it weakens.
+/// * `max_gravity` - Cap of the acceleration all [Gravitating] [entities](Entity) give [Player] together, in px/s².
+/// * `min_gap` - Smallest free space between two structures, see [Pacing](pacing::Pacing).
+/// * `max_gap` - Largest free space between two structures.
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default, deny_unknown_fields)]
 struct Settings {
     startup_speed: f32,
     speed_scaling: f32,
     startup_score: f32,
     hit_resistence: f32,
     shakes: usize,
+    player_hp: i32,
+    max_hp: i32,
+    energy_drain: f32,
+    energy_regen: f32,
+    energy_pickup: f32,
+    fall_speed: f32,
+    gravity_radius: f32,
+    gravity_softening: f32,
+    max_gravity: f32,
+    min_gap: f32,
+    max_gap: f32,
 }
 
-///[Handle] for unicorn [TextureAtlas].
-#[derive(Resource)]
-pub struct UnicornSheet(pub Handle<TextureAtlas>);
-///[Handle] for black hole [TextureAtlas].
-#[derive(Resource)]
-pub struct HolesSheet(pub Handle<TextureAtlas>);
-///[Handle] for plane [TextureAtlas].
-#[derive(Resource)]
-pub struct PlanesSheet(pub Handle<TextureAtlas>);
-///[Handle] for star [TextureAtlas].
-#[derive(Resource)]
-pub struct StarsSheet(pub Handle<TextureAtlas>);
-///[Handle] for planet [TextureAtlas].
-#[derive(Resource)]
-pub struct PlanetSheet(pub Handle<TextureAtlas>);
-///[Handle] for energybar [TextureAtlas].
-#[derive(Resource)]
-pub struct EnergySheet(pub Handle<TextureAtlas>);
-///[Handle] for rainbow [TextureAtlas].
-#[derive(Resource)]
-pub struct RainbowSheet(pub Handle<TextureAtlas>);
-///[Handle] for cloud [TextureAtlas].
-#[derive(Resource)]
-pub struct CloudSheet(pub Handle<TextureAtlas>);
-///[Handle] for full chocolate bar [TextureAtlas].
-#[derive(Resource)]
-pub struct FullChocSheet(pub Handle<TextureAtlas>);
-///[Handle] for partial chocolate bar [TextureAtlas].
-#[derive(Resource)]
-pub struct PartChocSheet(pub Handle<TextureAtlas>);
-///[Handle] for chocolate egg [TextureAtlas].
-#[derive(Resource)]
-pub struct EggSheet(pub Handle<TextureAtlas>);
-///[Handle] for lollipop [TextureAtlas].
-#[derive(Resource)]
-pub struct LollySheet(pub Handle<TextureAtlas>);
-///[Handle] for round gingerbread [TextureAtlas].
-#[derive(Resource)]
-pub struct LoveSheet(pub Handle<TextureAtlas>);
-///[Handle] for drink [TextureAtlas].
-#[derive(Resource)]
-pub struct KofolaSheet(pub Handle<TextureAtlas>);
-
-///Labels [entities](Entity) which suck [Player] closer.
+///Labels [entities](Entity) which suck [Player] closer, or push him away if `strength` is negative.
+/// See [player::gravity_acceleration].
 #[derive(Component)]
 pub struct Gravitating {
     strength: f32,
@@ -293,17 +674,42 @@ pub struct Gravitating {
 pub struct Object;
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|arg| arg == "--headless") {
+        headless::run(&args);
+        return;
+    }
+    let seed = rng::seed_from_args(&args).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(2);
+    });
+    let settings = settings::settings_from_args(&args).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(2);
+    });
+    let catalogue = catalogue::catalogue_from_args(&args).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(2);
+    });
+    let music = music::music_from_args(&args).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(2);
+    });
+
     App::new()
-        .add_state::<GameState>()
-        .insert_resource(ClearColor(CLEAR))
+        .insert_resource(seed)
+        .insert_resource(settings)
+        .insert_resource(catalogue)
+        .insert_resource(music)
         .add_plugins(
             DefaultPlugins
                 .set(ImagePlugin::default_linear())
                 .set(WindowPlugin {
                     primary_window: Some(Window {
                         title: "pupik".to_string(),
-                        resolution: (1920. / 3., 700.).into(),
-                        resizable: false,
+                        resolution: (Playfield::default().width, Playfield::default().height)
+                            .into(),
+                        resizable: true,
                         position: WindowPosition::At(IVec2::new(100, 100)),
                         present_mode: PresentMode::Fifo,
                         /* cursor: Cursor {
@@ -318,146 +724,143 @@ fn main() {
         )
         .insert_resource(PkvStore::new("idk", "lol")) //im sorry i dont have time
         .add_systems(Startup, (set_window_icon, spawn_camera))
-        .add_systems(PreStartup, load_all)
+        .add_systems(PreStartup, catalogue::load_catalogue_sheets)
         .add_systems(Update, animate_objects)
-        .add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
         //.add_plugin(RapierDebugRenderPlugin::default())
+        .add_plugins(GameplayPlugin)
         .add_plugins((
             AudioPlugin,
             CloudPlugin,
-            CollPlugin,
+            ControlsScreenPlugin,
             CursorPlugin,
             EndScreenPlugin,
-            FallPlugin,
             GameAudioPlugin,
             HighScorePlugin,
-            MapPlugin,
-            (
+            LanScreenPlugin,
+            LeaderboardScreenPlugin,
+            LoadingPlugin,
+            PausePlugin,
+            PlayfieldPlugin,
+            TextPlugin,
+            TutorialPlugin,
+            MenuPlugin,
+        ))
+        .add_plugins((
+            AccessibilityPlugin,
+            MusicPlugin,
+            OptionsScreenPlugin,
+            PreferencesPlugin,
+            VolumePlugin,
+        ))
+        .run();
+}
+
+///[Plugin] containing everything the game itself needs to run, without any window, menu or audio related functionalities.
+/// Shared by the windowed game and the [headless] mode.
+pub struct GameplayPlugin;
+
+impl Plugin for GameplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<GameState>()
+            .add_state::<PauseState>()
+            .insert_resource(FixedTime::new_from_secs(TICK))
+            .add_plugins(
+                RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0)
+                    .with_default_system_setup(false),
+            )
+            .configure_sets(
+                FixedUpdate,
+                (
+                    GameplaySet::Input,
+                    GameplaySet::Logic,
+                    PhysicsSet::SyncBackend,
+                    PhysicsSet::StepSimulation,
+                    PhysicsSet::Writeback,
+                    GameplaySet::Collisions,
+                )
+                    .chain(),
+            )
+            .configure_set(FixedUpdate, GameplaySet::Input.run_if(in_game))
+            .configure_set(FixedUpdate, GameplaySet::Logic.run_if(in_game))
+            .configure_set(FixedUpdate, GameplaySet::Collisions.run_if(in_game))
+            .configure_set(
+                FixedUpdate,
+                PhysicsSet::SyncBackend.run_if(in_state(PauseState::Running)),
+            )
+            .configure_set(
+                FixedUpdate,
+                PhysicsSet::StepSimulation.run_if(in_state(PauseState::Running)),
+            )
+            .configure_set(
+                FixedUpdate,
+                PhysicsSet::Writeback.run_if(in_state(PauseState::Running)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::SyncBackend)
+                        .in_set(PhysicsSet::SyncBackend),
+                    RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::StepSimulation)
+                        .in_set(PhysicsSet::StepSimulation),
+                    RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::Writeback)
+                        .in_set(PhysicsSet::Writeback),
+                ),
+            )
+            //Systems running in the same tick must always run in the same order, otherwise replays could diverge
+            .edit_schedule(FixedUpdate, |schedule| {
+                schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+            })
+            .add_plugins((
+                CollPlugin,
+                ControlsPlugin,
+                FallPlugin,
+                LanPlugin,
+                MapPlugin,
+                PacingPlugin,
+                PixiePlugin,
                 PlanePlugin,
                 PlayerPlugin,
                 RainbowPlugin,
+                ReplayPlugin,
+                RngPlugin,
+                ScorePlugin,
                 SpeedPlugin,
-                TextPlugin,
-                TutorialPlugin,
-                MenuPlugin,
-            ),
-        ))
-        .insert_resource(RapierConfiguration {
-            gravity: Vec2::splat(0.),
-            ..Default::default()
-        })
-        .insert_resource(Settings {
-            startup_score: 120.,
-            startup_speed: 1.6,
-            speed_scaling: 0.01,
-            hit_resistence: 100.,
-            shakes: 4,
-        })
-        .run();
+                VersusPlugin,
+            ))
+            .add_plugins(WallPlugin)
+            .insert_resource(RapierConfiguration {
+                gravity: Vec2::splat(0.),
+                timestep_mode: TimestepMode::Fixed {
+                    dt: TICK,
+                    substeps: 1,
+                },
+                ..Default::default()
+            })
+            .init_resource::<Settings>()
+            .init_resource::<Playfield>()
+            .add_event::<audio::SfxEvent>();
+    }
 }
 
-///Loads all spritesheets from the assets folder into the [AssetServer]
+///Run condition of [GameplaySet]s. True in [GameState::Game] while not paused, unless the game is already about to leave it,
+/// so no ticks run between [Player](player::Player)'s death and the actual state change.
 /// # Arguments
-/// * `commands` -[Commands].
-/// * `assets` - [AssetServer].
-/// * `texture_atlases` - [Assets] of type [TextureAtlas].
-fn load_all(
-    mut commands: Commands,
-    assets: Res<AssetServer>,
-    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-) {
-    struct SheetInfo {
-        name: &'static str,
-        x: f32,
-        y: f32,
-        columns: usize,
-        rows: usize,
-        padding: Option<Vec2>,
-        offset: Option<Vec2>,
-    }
-    impl SheetInfo {
-        pub fn new(
-            name: &'static str,
-            x: f32,
-            y: f32,
-            columns: usize,
-            rows: usize,
-            padding: Option<Vec2>,
-            offset: Option<Vec2>,
-        ) -> SheetInfo {
-            SheetInfo {
-                name,
-                x,
-                y,
-                columns,
-                rows,
-                padding,
-                offset,
-            }
-        }
-    }
-    let init_arr = [
-        SheetInfo::new("mraky_full2.png", 1150., 850., 4, 2, None, None),
-        SheetInfo::new("duha.png", 21., 1., 1, 1, None, None),
-        SheetInfo::new("planet_sheet.png", 100., 100., 15, 1, None, None),
-        SheetInfo::new("star_sheet.png", 21., 21., 1, 1, None, None),
-        SheetInfo::new("plane_sheet1.png", 322., 108., 2, 1, None, None),
-        SheetInfo::new("energy_sheet.png", 243., 117., 3, 1, None, None),
-        SheetInfo::new("blackhole_sheet.png", 223., 223., 4, 1, None, None),
-        SheetInfo::new("full_choc.png", 96., 44., 2, 1, None, None),
-        SheetInfo::new("part_choc.png", 92., 44., 2, 1, None, None),
-        SheetInfo::new("lollysheet.png", 49., 98., 4, 1, None, None),
-        SheetInfo::new("lovesheet.png", 100., 100., 2, 1, None, None),
-        SheetInfo::new("eggsheet.png", 71., 100., 2, 1, None, None),
-        SheetInfo::new("kofolasheet.png", 24., 88., 1, 1, None, None),
-        SheetInfo::new(
-            "unicorn_sheet.png",
-            200.,
-            250.,
-            8,
-            1,
-            Some(Vec2::splat(10.0)),
-            Some(Vec2::splat(10.0)),
-        ),
-    ];
-    for sheet in init_arr {
-        let image = assets.load(sheet.name);
-        let atlas = TextureAtlas::from_grid(
-            image,
-            Vec2::new(sheet.x, sheet.y),
-            sheet.columns,
-            sheet.rows,
-            sheet.padding,
-            sheet.offset,
-        );
-
-        let atlas_handle = texture_atlases.add(atlas);
-        match sheet.name {
-            "mraky_full2.png" => commands.insert_resource(CloudSheet(atlas_handle)),
-            "duha.png" => commands.insert_resource(RainbowSheet(atlas_handle)),
-            "star_sheet.png" => commands.insert_resource(StarsSheet(atlas_handle)),
-            "plane_sheet1.png" => commands.insert_resource(PlanesSheet(atlas_handle)),
-            "energy_sheet.png" => commands.insert_resource(EnergySheet(atlas_handle)),
-            "blackhole_sheet.png" => commands.insert_resource(HolesSheet(atlas_handle)),
-            "unicorn_sheet.png" => commands.insert_resource(UnicornSheet(atlas_handle)),
-            "planet_sheet.png" => commands.insert_resource(PlanetSheet(atlas_handle)),
-            "full_choc.png" => commands.insert_resource(FullChocSheet(atlas_handle)),
-            "part_choc.png" => commands.insert_resource(PartChocSheet(atlas_handle)),
-            "lollysheet.png" => commands.insert_resource(LollySheet(atlas_handle)),
-            "lovesheet.png" => commands.insert_resource(LoveSheet(atlas_handle)),
-            "eggsheet.png" => commands.insert_resource(EggSheet(atlas_handle)),
-            "kofolasheet.png" => commands.insert_resource(KofolaSheet(atlas_handle)),
-            _ => {
-                panic!("=============FILE NAME MISSING IN MAIN.RS MATCH EXPRESSION!=============");
-            }
-        };
-    }
+/// * `state` - Current [GameState].
+/// * `next` - [NextState] of [GameState].
+/// * `pause` - Current [PauseState].
+fn in_game(
+    state: Res<State<GameState>>,
+    next: Res<NextState<GameState>>,
+    pause: Res<State<PauseState>>,
+) -> bool {
+    *state.get() == GameState::Game && next.0.is_none() && *pause.get() == PauseState::Running
 }
 
-/// Spawns the camera.
+/// Spawns the camera. It always shows the whole [Playfield], see [PlayfieldPlugin].
 /// # Arguments
 /// * `commands` - [Commands]
-fn spawn_camera(mut commands: Commands) {
+/// * `playfield` - [Playfield].
+fn spawn_camera(mut commands: Commands, playfield: Res<Playfield>) {
     let mut camera = Camera2dBundle {
         transform: Transform {
             translation: Vec3::new(0., 0., 1000.),
@@ -467,11 +870,10 @@ fn spawn_camera(mut commands: Commands) {
     };
 
     camera.projection = OrthographicProjection {
-        area: Rect::new(-1.0 * RESOLUTION, -1.0, 1.0 * RESOLUTION, 1.0), /*
-                                                                         scaling_mode: ScalingMode::Fixed {
-                                                                             width: 1. * RESOLUTION,
-                                                                             height: 0.,
-                                                                         }, */
+        scaling_mode: ScalingMode::Fixed {
+            width: playfield.width,
+            height: playfield.height,
+        },
         scale: 1.,
         ..Default::default()
     };
@@ -1,5 +1,13 @@
-use crate::GameState;
+use crate::{
+    playfield::PlayfieldPanel,
+    preferences::{Language, Preferences},
+    replay::{Playback, Replay, BEST_REPLAY},
+    versus::GameMode,
+    volume::{mute_text, MuteButton, Volume},
+    GameState,
+};
 use bevy::prelude::*;
+use std::path::Path;
 
 ///[Plugin] taking care of functionalities corelating with [MainMenu]
 pub struct MenuPlugin;
@@ -13,11 +21,50 @@ struct MainMenu;
 #[derive(Component)]
 struct TutorialButton;
 
+///The "Options" button, next to [TutorialButton]. When clicked, [GameState] changes to [GameState::Options].
+/// When hovered, its background color changes until unhovered.
+#[derive(Component)]
+struct OptionsButton;
+
+///The "Watch best run" button, only spawned if a replay of the best run exists. When clicked, the replay is loaded
+/// and [GameState] changes to [GameState::Game]. When hovered, its background color changes until unhovered.
+#[derive(Component)]
+struct ReplayButton;
+
+///The "Leaderboard" button. When clicked, [GameState] changes to [GameState::Leaderboard].
+/// When hovered, its background color changes until unhovered.
+#[derive(Component)]
+struct LeaderboardButton;
+
+///The "Controls" button. When clicked, [GameState] changes to [GameState::Controls].
+/// When hovered, its background color changes until unhovered.
+#[derive(Component)]
+struct ControlsButton;
+
+///The "Players" button switching [GameMode] between one and two players. Its [Text] shows the current count.
+/// When hovered, its background color changes until unhovered.
+#[derive(Component)]
+struct PlayersButton;
+
+///The "LAN" button. When clicked, [GameState] changes to [GameState::Lan].
+/// When hovered, its background color changes until unhovered.
+#[derive(Component)]
+struct LanButton;
+
+///Text of [GameMode] `players` for the "Players" button.
+/// # Arguments
+/// * `mode` - [GameMode].
+/// * `language` - [Language] of the text.
+fn players_text(mode: GameMode, language: Language) -> String {
+    format!("{}: {}", language.tr("Players"), mode.players())
+}
+
 ///A clickable "Left click to play" background. When clicked, spawns [LoadTimer].
 #[derive(Component)]
 struct PlayButton;
 
-///[Timer] indicating that [Entity] oscilates between visible and invisible each 0.3s
+///[Timer] indicating that [Entity] oscilates between visible and invisible each 0.3s. With [Preferences]
+/// `steady_text`, its [Text] stays visible and is highlighted by [HIGHLIGHT_COLOR] instead.
 #[derive(Component)]
 pub struct Flickering {
     pub timer: Timer,
@@ -36,7 +83,18 @@ impl Plugin for MenuPlugin {
         app.add_systems(OnEnter(GameState::MainMenu), spawn_start_text)
             .add_systems(
                 Update,
-                (click2play, load_game, tutorial_button_interaction)
+                (
+                    click2play,
+                    load_game,
+                    tutorial_button_interaction,
+                    options_button_interaction,
+                    mute_button_interaction,
+                    replay_button_interaction,
+                    leaderboard_button_interaction,
+                    controls_button_interaction,
+                    players_button_interaction,
+                    lan_button_interaction,
+                )
                     .run_if(in_state(GameState::MainMenu)),
             )
             .add_systems(OnExit(GameState::MainMenu), despawn_menu)
@@ -48,7 +106,17 @@ impl Plugin for MenuPlugin {
 /// # Arguments
 /// * `commands` - [Commands].
 /// * `assets` - [AssetServer]. Used to load font.
-fn spawn_start_text(mut commands: Commands, assets: Res<AssetServer>) {
+/// * `mode` - [GameMode], shown on [PlayersButton].
+/// * `volume` - [Volume], shown on [MuteButton].
+/// * `preferences` - [Preferences], texts are in its `language`.
+fn spawn_start_text(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    mode: Res<GameMode>,
+    volume: Res<Volume>,
+    preferences: Res<Preferences>,
+) {
+    let language = preferences.language;
     let font = assets.load("fonts\\Love_Letters.ttf");
     let text_style = TextStyle {
         font: font.clone(),
@@ -63,8 +131,6 @@ fn spawn_start_text(mut commands: Commands, assets: Res<AssetServer>) {
     commands
         .spawn(ButtonBundle {
             style: Style {
-                width: Val::Px(1920.0 / 3.),
-                height: Val::Px(700.),
                 // center button and children
                 margin: UiRect::all(Val::Auto),
                 justify_content: JustifyContent::Center,
@@ -74,11 +140,15 @@ fn spawn_start_text(mut commands: Commands, assets: Res<AssetServer>) {
             background_color: Color::rgba(1.0, 0.8, 0.9, 0.7).into(), //pink
             ..default()
         })
+        .insert(PlayfieldPanel)
         .with_children(|parent| {
             parent.spawn(
                 TextBundle {
-                    text: Text::from_section("Music by Vojtech Klhufek", credits_text_style)
-                        .with_alignment(TextAlignment::Center),
+                    text: Text::from_section(
+                        language.tr("Music by Vojtech Klhufek"),
+                        credits_text_style,
+                    )
+                    .with_alignment(TextAlignment::Center),
                     ..default()
                 }
                 .with_style(Style {
@@ -91,7 +161,7 @@ fn spawn_start_text(mut commands: Commands, assets: Res<AssetServer>) {
             parent
                 .spawn(
                     TextBundle {
-                        text: Text::from_section("Left click to start", text_style)
+                        text: Text::from_section(language.tr("Left click to start"), text_style)
                             .with_alignment(TextAlignment::Center),
                         ..default()
                     }
@@ -124,7 +194,7 @@ fn spawn_start_text(mut commands: Commands, assets: Res<AssetServer>) {
                 })
                 .with_children(|parent| {
                     parent.spawn(TextBundle::from_section(
-                        "How to play",
+                        language.tr("How to play"),
                         TextStyle {
                             font: assets.load("fonts\\Love_Letters.ttf"),
                             font_size: 35.0,
@@ -133,6 +203,193 @@ fn spawn_start_text(mut commands: Commands, assets: Res<AssetServer>) {
                     ));
                 })
                 .insert(TutorialButton);
+            parent
+                .spawn(ButtonBundle {
+                    style: Style {
+                        width: Val::Px(200.0),
+                        height: Val::Px(65.0),
+                        margin: UiRect {
+                            bottom: Val::Percent(42.),
+                            top: Val::Percent(88.),
+                            left: Val::Px(20.),
+                            ..default()
+                        },
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::rgba(0., 0., 0., 0.1).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        language.tr("Options"),
+                        TextStyle {
+                            font: assets.load("fonts\\Love_Letters.ttf"),
+                            font_size: 35.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                    ));
+                })
+                .insert(OptionsButton);
+            parent
+                .spawn(ButtonBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Px(15.0),
+                        left: Val::Px(15.0),
+                        width: Val::Px(160.0),
+                        height: Val::Px(45.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::rgba(0., 0., 0., 0.1).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        mute_text(&volume, language),
+                        TextStyle {
+                            font: assets.load("fonts\\Love_Letters.ttf"),
+                            font_size: 30.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                    ));
+                })
+                .insert(MuteButton);
+            if Path::new(BEST_REPLAY).exists() {
+                parent
+                    .spawn(ButtonBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            top: Val::Px(15.0),
+                            left: Val::Px(15.0),
+                            width: Val::Px(200.0),
+                            height: Val::Px(50.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: Color::rgba(0., 0., 0., 0.1).into(),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            language.tr("Watch best run"),
+                            TextStyle {
+                                font: assets.load("fonts\\Love_Letters.ttf"),
+                                font_size: 30.0,
+                                color: Color::rgb(0.9, 0.9, 0.9),
+                            },
+                        ));
+                    })
+                    .insert(ReplayButton);
+            }
+            parent
+                .spawn(ButtonBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(15.0),
+                        right: Val::Px(15.0),
+                        width: Val::Px(200.0),
+                        height: Val::Px(50.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::rgba(0., 0., 0., 0.1).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        language.tr("Leaderboard"),
+                        TextStyle {
+                            font: assets.load("fonts\\Love_Letters.ttf"),
+                            font_size: 30.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                    ));
+                })
+                .insert(LeaderboardButton);
+            parent
+                .spawn(ButtonBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(75.0),
+                        right: Val::Px(15.0),
+                        width: Val::Px(200.0),
+                        height: Val::Px(50.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::rgba(0., 0., 0., 0.1).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        language.tr("Controls"),
+                        TextStyle {
+                            font: assets.load("fonts\\Love_Letters.ttf"),
+                            font_size: 30.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                    ));
+                })
+                .insert(ControlsButton);
+            parent
+                .spawn(ButtonBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(135.0),
+                        right: Val::Px(15.0),
+                        width: Val::Px(200.0),
+                        height: Val::Px(50.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::rgba(0., 0., 0., 0.1).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        players_text(*mode, language),
+                        TextStyle {
+                            font: assets.load("fonts\\Love_Letters.ttf"),
+                            font_size: 30.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                    ));
+                })
+                .insert(PlayersButton);
+            parent
+                .spawn(ButtonBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(195.0),
+                        right: Val::Px(15.0),
+                        width: Val::Px(200.0),
+                        height: Val::Px(50.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::rgba(0., 0., 0., 0.1).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        language.tr("LAN"),
+                        TextStyle {
+                            font: assets.load("fonts\\Love_Letters.ttf"),
+                            font_size: 30.0,
+                            color: Color::rgb(0.9, 0.9, 0.9),
+                        },
+                    ));
+                })
+                .insert(LanButton);
         })
         .insert(PlayButton)
         .insert(MainMenu);
@@ -202,16 +459,270 @@ fn tutorial_button_interaction(
     }
 }
 
+///Handles interactions with the [OptionsButton].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `loadtimer` - [Query] for [LoadTimer].
+/// * `options_interaction` - [Query] for [OptionsButton] and its [Interaction] when changed.
+/// * `next` - [NextState] of [GameState].
+fn options_button_interaction(
+    mut commands: Commands,
+    loadtimer: Query<Entity, With<LoadTimer>>,
+    mut options_interaction: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<OptionsButton>),
+    >,
+    mut next: ResMut<NextState<GameState>>,
+) {
+    for (interaction, mut color) in &mut options_interaction {
+        match *interaction {
+            Interaction::Pressed => {
+                for loadtimer in loadtimer.iter() {
+                    commands.entity(loadtimer).despawn();
+                }
+                next.set(GameState::Options);
+            }
+            Interaction::Hovered => {
+                *color = Color::rgba(0., 0., 0., 0.7).into();
+            }
+            Interaction::None => {
+                *color = Color::rgba(0., 0., 0., 0.1).into();
+            }
+        }
+    }
+}
+
+///Keeps the game from starting when [MuteButton] is clicked, muting itself is handled by
+/// [crate::volume::VolumePlugin].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `loadtimer` - [Query] for [LoadTimer].
+/// * `mute_interaction` - [Query] for [MuteButton] and its [Interaction] when changed.
+fn mute_button_interaction(
+    mut commands: Commands,
+    loadtimer: Query<Entity, With<LoadTimer>>,
+    mute_interaction: Query<&Interaction, (Changed<Interaction>, With<MuteButton>)>,
+) {
+    for interaction in &mute_interaction {
+        if *interaction == Interaction::Pressed {
+            for loadtimer in loadtimer.iter() {
+                commands.entity(loadtimer).despawn();
+            }
+        }
+    }
+}
+
+///Handles interactions with the [ReplayButton].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `loadtimer` - [Query] for [LoadTimer].
+/// * `replay_interaction` - [Query] for [ReplayButton] and its [Interaction] when changed.
+/// * `next` - [NextState] of [GameState].
+fn replay_button_interaction(
+    mut commands: Commands,
+    loadtimer: Query<Entity, With<LoadTimer>>,
+    mut replay_interaction: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ReplayButton>),
+    >,
+    mut next: ResMut<NextState<GameState>>,
+) {
+    for (interaction, mut color) in &mut replay_interaction {
+        match *interaction {
+            Interaction::Pressed => {
+                for loadtimer in loadtimer.iter() {
+                    commands.entity(loadtimer).despawn();
+                }
+                match Replay::load(BEST_REPLAY) {
+                    Ok(replay) => {
+                        commands.insert_resource(Playback::new(replay));
+                        next.set(GameState::Game);
+                    }
+                    Err(err) => warn!("Failed to load replay: {}", err),
+                }
+            }
+            Interaction::Hovered => {
+                *color = Color::rgba(0., 0., 0., 0.7).into();
+            }
+            Interaction::None => {
+                *color = Color::rgba(0., 0., 0., 0.1).into();
+            }
+        }
+    }
+}
+
+///Handles interactions with the [LeaderboardButton].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `loadtimer` - [Query] for [LoadTimer].
+/// * `leaderboard_interaction` - [Query] for [LeaderboardButton] and its [Interaction] when changed.
+/// * `next` - [NextState] of [GameState].
+fn leaderboard_button_interaction(
+    mut commands: Commands,
+    loadtimer: Query<Entity, With<LoadTimer>>,
+    mut leaderboard_interaction: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<LeaderboardButton>),
+    >,
+    mut next: ResMut<NextState<GameState>>,
+) {
+    for (interaction, mut color) in &mut leaderboard_interaction {
+        match *interaction {
+            Interaction::Pressed => {
+                for loadtimer in loadtimer.iter() {
+                    commands.entity(loadtimer).despawn();
+                }
+                next.set(GameState::Leaderboard);
+            }
+            Interaction::Hovered => {
+                *color = Color::rgba(0., 0., 0., 0.7).into();
+            }
+            Interaction::None => {
+                *color = Color::rgba(0., 0., 0., 0.1).into();
+            }
+        }
+    }
+}
+
+///Handles interactions with the [ControlsButton].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `loadtimer` - [Query] for [LoadTimer].
+/// * `controls_interaction` - [Query] for [ControlsButton] and its [Interaction] when changed.
+/// * `next` - [NextState] of [GameState].
+fn controls_button_interaction(
+    mut commands: Commands,
+    loadtimer: Query<Entity, With<LoadTimer>>,
+    mut controls_interaction: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<ControlsButton>),
+    >,
+    mut next: ResMut<NextState<GameState>>,
+) {
+    for (interaction, mut color) in &mut controls_interaction {
+        match *interaction {
+            Interaction::Pressed => {
+                for loadtimer in loadtimer.iter() {
+                    commands.entity(loadtimer).despawn();
+                }
+                next.set(GameState::Controls);
+            }
+            Interaction::Hovered => {
+                *color = Color::rgba(0., 0., 0., 0.7).into();
+            }
+            Interaction::None => {
+                *color = Color::rgba(0., 0., 0., 0.1).into();
+            }
+        }
+    }
+}
+
+///Handles interactions with the [PlayersButton]. Clicking it switches [GameMode] instead of starting the game.
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `loadtimer` - [Query] for [LoadTimer].
+/// * `players_interaction` - [Query] for [PlayersButton], its [Interaction] when changed and its children.
+/// * `text_q` - [Query] for [Text] of [PlayersButton].
+/// * `mode` - [GameMode].
+/// * `preferences` - [Preferences], its `language` is used.
+fn players_button_interaction(
+    mut commands: Commands,
+    loadtimer: Query<Entity, With<LoadTimer>>,
+    mut players_interaction: Query<
+        (&Interaction, &mut BackgroundColor, &Children),
+        (Changed<Interaction>, With<PlayersButton>),
+    >,
+    mut text_q: Query<&mut Text>,
+    mut mode: ResMut<GameMode>,
+    preferences: Res<Preferences>,
+) {
+    for (interaction, mut color, children) in &mut players_interaction {
+        match *interaction {
+            Interaction::Pressed => {
+                for loadtimer in loadtimer.iter() {
+                    commands.entity(loadtimer).despawn();
+                }
+                *mode = match *mode {
+                    GameMode::Single => GameMode::Local,
+                    GameMode::Local | GameMode::Lan => GameMode::Single,
+                };
+                for child in children.iter() {
+                    if let Ok(mut text) = text_q.get_mut(*child) {
+                        text.sections[0].value = players_text(*mode, preferences.language);
+                    }
+                }
+            }
+            Interaction::Hovered => {
+                *color = Color::rgba(0., 0., 0., 0.7).into();
+            }
+            Interaction::None => {
+                *color = Color::rgba(0., 0., 0., 0.1).into();
+            }
+        }
+    }
+}
+
+///Handles interactions with the [LanButton].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `loadtimer` - [Query] for [LoadTimer].
+/// * `lan_interaction` - [Query] for [LanButton] and its [Interaction] when changed.
+/// * `next` - [NextState] of [GameState].
+fn lan_button_interaction(
+    mut commands: Commands,
+    loadtimer: Query<Entity, With<LoadTimer>>,
+    mut lan_interaction: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<LanButton>),
+    >,
+    mut next: ResMut<NextState<GameState>>,
+) {
+    for (interaction, mut color) in &mut lan_interaction {
+        match *interaction {
+            Interaction::Pressed => {
+                for loadtimer in loadtimer.iter() {
+                    commands.entity(loadtimer).despawn();
+                }
+                next.set(GameState::Lan);
+            }
+            Interaction::Hovered => {
+                *color = Color::rgba(0., 0., 0., 0.7).into();
+            }
+            Interaction::None => {
+                *color = Color::rgba(0., 0., 0., 0.1).into();
+            }
+        }
+    }
+}
+
+///Color of [Flickering] [Text] with [Preferences] `steady_text`.
+const HIGHLIGHT_COLOR: Color = Color::rgb(1., 0.85, 0.3);
+
 ///Handles the fuctionality of [Flickering].
 /// # Arguments
 /// * `time` - [Time].
 /// * `query` - [Query] for [Flickering].
+/// * `preferences` - [Preferences].
 fn change_flick(
     time: Res<Time>,
-    mut query: Query<(&mut Flickering, &mut Visibility), With<Flickering>>,
+    mut query: Query<(&mut Flickering, &mut Visibility, &mut Text), With<Flickering>>,
+    preferences: Res<Preferences>,
 ) {
+    if preferences.steady_text {
+        for (_, mut visibility, mut text) in &mut query {
+            if *visibility != Visibility::Inherited {
+                *visibility = Visibility::Inherited;
+            }
+            for section in text.sections.iter_mut() {
+                if section.style.color != HIGHLIGHT_COLOR {
+                    section.style.color = HIGHLIGHT_COLOR;
+                }
+            }
+        }
+        return;
+    }
     //Makes entities containing Flickering component flicker
-    for (mut flickering, mut visibility) in &mut query {
+    for (mut flickering, mut visibility, _) in &mut query {
         flickering.timer.tick(time.delta());
         if flickering.timer.just_finished() {
             match *visibility {
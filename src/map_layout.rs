@@ -1,16 +1,26 @@
 use crate::{
     basic::create_basic,
     blackhole::create_hole,
+    catalogue::EnemyCatalogue,
     energybars::create_bar,
+    falling::FallSpeed,
+    fruit::create_fruit,
     homing::create_rainbow,
     import::import_structures,
+    loading::Atlases,
+    pacing::{pick_weighted, Pacing},
+    pixie::{create_pixies, Flocking},
     plane::{create_plane_sensor, PlaneDir},
     planet::create_planet,
-    EggSheet, EnergySheet, FullChocSheet, GameState, HolesSheet, KofolaSheet, LollySheet,
-    LoveSheet, Object, PartChocSheet, PlanetSheet, RainbowSheet,
+    playfield::Playfield,
+    rng::GameRng,
+    score::Score,
+    speed::Speed,
+    whitehole::create_white_hole,
+    GameState, GameplaySet, Settings,
 };
 
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
 
 ///[Plugin] which takes care of random enemy spawning, later with pre-designed structures and a structure
 pub struct MapPlugin;
@@ -19,7 +29,7 @@ impl Plugin for MapPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(GameState::Game), spawn_map)
             .add_systems(OnExit(GameState::Game), despawn_map)
-            .add_systems(Update, spawning.run_if(in_state(GameState::Game)));
+            .add_systems(FixedUpdate, spawning.in_set(GameplaySet::Logic));
     }
 }
 
@@ -30,7 +40,10 @@ impl Plugin for MapPlugin {
 /// * `RainbowE` - [spawning] will call the [create_rainbow] function
 /// * `PlaneE` - [spawning] will call the [create_plane_sensor] function
 /// * `BasicE` - [spawning] will call the [create_basic] function
-#[derive(PartialEq, Eq, PartialOrd, Ord)]
+/// * `FruitE` - [spawning] will call the [create_fruit] function
+/// * `WhiteHoleE` - [spawning] will call the [create_white_hole] function
+/// * `PixieE` - [spawning] will call the [create_pixies] function
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
 pub enum Enemy {
     HoleE,
     BarE,
@@ -38,72 +51,247 @@ pub enum Enemy {
     PlaneE,
     PlanetE,
     BasicE,
+    FruitE,
+    WhiteHoleE,
+    PixieE,
+}
+
+impl Enemy {
+    ///Every [Enemy] type.
+    pub const ALL: [Enemy; 9] = [
+        Enemy::HoleE,
+        Enemy::BarE,
+        Enemy::RainbowE,
+        Enemy::PlaneE,
+        Enemy::PlanetE,
+        Enemy::BasicE,
+        Enemy::FruitE,
+        Enemy::WhiteHoleE,
+        Enemy::PixieE,
+    ];
+}
+
+impl std::str::FromStr for Enemy {
+    type Err = ();
+
+    ///Reads the keyword used for the enemy in `./structures` files, the opposite of [Enemy]'s [std::fmt::Display].
+    fn from_str(keyword: &str) -> Result<Self, Self::Err> {
+        Enemy::ALL
+            .into_iter()
+            .find(|enemy| enemy.to_string() == keyword)
+            .ok_or(())
+    }
+}
+
+impl std::fmt::Display for Enemy {
+    ///Writes the keyword used for the enemy in `./structures` files.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let keyword = match self {
+            Enemy::HoleE => "blackhole",
+            Enemy::BarE => "energybar",
+            Enemy::RainbowE => "rainbow",
+            Enemy::PlaneE => "plane",
+            Enemy::PlanetE => "planet",
+            Enemy::BasicE => "regular",
+            Enemy::FruitE => "fruit",
+            Enemy::WhiteHoleE => "whitehole",
+            Enemy::PixieE => "pixie",
+        };
+        write!(f, "{}", keyword)
+    }
+}
+
+///Optional parameters of a single [SpawnEvent]. [None] keeps the enemy's random or default behaviour.
+/// # Fields
+/// * `dir` - Direction of the [crate::plane::Plane], [PlaneDir::Right] if not set.
+/// * `sprite` - Sprite variant of [crate::basic::BasicObject], [crate::planet::Planet] or [crate::fruit::Fruit].
+/// * `rotation` - Rotation in degrees.
+/// * `gravity` - [crate::Gravitating] strength of [crate::blackhole::Hole] or [crate::planet::Planet], the strength
+/// of the push of [crate::whitehole::WhiteHole].
+/// * `fall_speed` - Multiplier of the falling speed, see [FallSpeed].
+/// * `count` - Number of pixies in a swarm, see [crate::pixie::create_pixies].
+/// * `separation` - [Flocking] weight of pixies keeping apart.
+/// * `alignment` - [Flocking] weight of pixies flying the same way.
+/// * `cohesion` - [Flocking] weight of pixies staying together.
+/// * `chase` - [Flocking] weight of pixies chasing the player, negative ones flee.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct ObjectParams {
+    pub dir: Option<PlaneDir>,
+    pub sprite: Option<usize>,
+    pub rotation: Option<f32>,
+    pub gravity: Option<f32>,
+    pub fall_speed: Option<f32>,
+    pub count: Option<usize>,
+    pub separation: Option<f32>,
+    pub alignment: Option<f32>,
+    pub cohesion: Option<f32>,
+    pub chase: Option<f32>,
+}
+
+impl ObjectParams {
+    ///[FallSpeed] of the spawned enemy.
+    pub fn fall_speed(&self) -> FallSpeed {
+        FallSpeed(self.fall_speed.unwrap_or(1.))
+    }
+
+    ///[Flocking] weights of a pixie swarm, defaults where not set.
+    pub fn flocking(&self) -> Flocking {
+        let default = Flocking::default();
+        Flocking {
+            separation: self.separation.unwrap_or(default.separation),
+            alignment: self.alignment.unwrap_or(default.alignment),
+            cohesion: self.cohesion.unwrap_or(default.cohesion),
+            chase: self.chase.unwrap_or(default.chase),
+        }
+    }
+
+    ///Rotation of the spawned enemy, `default` if no rotation is set.
+    pub fn rotation_or(&self, default: Quat) -> Quat {
+        self.rotation.map_or(default, |degrees| {
+            Quat::from_rotation_z(degrees.to_radians())
+        })
+    }
 }
 
 ///The building block of [Structure].
 /// # Fields
 /// * `x` - x coordinate of enemy
-/// * `y` - y coordinate of enemy
+/// * `y` - y coordinate of enemy above [Playfield::spawn_y], the lowest object of a structure is at 0
 /// * `enemy` - Type of enemy. See [Enemy].
-#[derive(PartialEq, Eq, PartialOrd, Ord)]
+/// * `params` - [ObjectParams] of the enemy.
+#[derive(PartialEq, Debug)]
 pub struct SpawnEvent {
     pub x: Option<i32>,
     pub y: Option<i32>,
     pub enemy: Enemy,
+    pub params: ObjectParams,
 }
 
-/// A [Vec] containing all planned events. Currently used in debugging, later will be used for
-/// not so random structures in the beggining. Random spawning will be more of an end-game thing.
+/// A [Vec] containing all planned events. Speed and score windows decide which part of the run a structure belongs to,
+/// so hand-made structures can be kept for the beginning and harder ones for the end-game.
+/// * `name` - Name used in logs, the file name if the structure file does not name it.
 /// * `spawn_chance` - weight in probability calculation
+/// * `min_speed` - The structure only spawns once [Speed] reaches this value.
+/// * `max_speed` - The structure stops spawning once [Speed] exceeds this value.
+/// * `min_score` - The structure only spawns once [Score] reaches this value.
+/// * `max_score` - The structure stops spawning once [Score] exceeds this value.
+/// * `cooldown` - Seconds of gameplay after spawning before the structure can spawn again.
+/// * `max_repeats` - How many times the structure can spawn in one run.
 /// * `structure` - [Vec] of [SpawnEvent].
-#[derive(Component)]
+#[derive(Component, Default)]
 pub struct Structure {
+    pub name: String,
     pub spawn_chance: f64,
+    pub min_speed: Option<f32>,
+    pub max_speed: Option<f32>,
+    pub min_score: Option<f32>,
+    pub max_score: Option<f32>,
+    pub cooldown: Option<f32>,
+    pub max_repeats: Option<u32>,
     pub structure: Vec<SpawnEvent>,
 }
 
+impl Structure {
+    ///Vertical distance between the lowest and highest object of the structure.
+    pub fn height(&self) -> f32 {
+        let ys = self
+            .structure
+            .iter()
+            .filter_map(|spawn_event| spawn_event.y);
+        match (ys.clone().min(), ys.max()) {
+            (Some(min), Some(max)) => (max - min) as f32,
+            _ => 0.,
+        }
+    }
+
+    ///Whether the structure is allowed to spawn at given [Speed] and [Score], given its [SpawnHistory] in this run.
+    pub fn eligible(&self, speed: f32, score: f32, history: &SpawnHistory) -> bool {
+        self.min_speed.is_none_or(|min| speed >= min)
+            && self.max_speed.is_none_or(|max| speed <= max)
+            && self.min_score.is_none_or(|min| score >= min)
+            && self.max_score.is_none_or(|max| score <= max)
+            && self.max_repeats.is_none_or(|max| history.repeats < max)
+            && history.cooldown <= 0.
+    }
+}
+
+///What a [Structure] of [Map] did in the current run.
+/// # Fields
+/// * `repeats` - How many times it spawned.
+/// * `cooldown` - Seconds of gameplay left until it can spawn again.
+#[derive(Default, Clone)]
+pub struct SpawnHistory {
+    pub repeats: u32,
+    pub cooldown: f32,
+}
+
+///All [Structure]s the current run picks from.
+/// # Fields
+/// * `map` - The [Structure]s.
+/// * `history` - [SpawnHistory] of every [Structure], at the same index.
 #[derive(Component)]
 pub struct Map {
     pub map: Vec<Structure>,
+    pub history: Vec<SpawnHistory>,
 }
 
 /// Spawns [Map].
 /// # Arguments
 /// * `commands` - [Commands]
-fn spawn_map(mut commands: Commands) {
-    use crate::map_layout::Enemy::{BarE, BasicE, HoleE, PlaneE, PlanetE, RainbowE};
+/// * `catalogue` - [EnemyCatalogue], structure files are checked against it.
+fn spawn_map(mut commands: Commands, catalogue: Res<EnemyCatalogue>) {
+    use crate::map_layout::Enemy::{
+        BarE, BasicE, FruitE, HoleE, PixieE, PlaneE, PlanetE, RainbowE, WhiteHoleE,
+    };
     let mut map = Vec::from([]);
-    let singletons: [(f64, Enemy); 6] = [
+    let singletons: [(f64, Enemy); 9] = [
         (7., HoleE),
         (12., BarE),
         (0.2, RainbowE),
         (38., PlaneE),
         (19., PlanetE),
         (119., BasicE),
+        (3., FruitE),
+        (4., WhiteHoleE),
+        (5., PixieE),
     ];
     for singleton in singletons {
         map.push(Structure {
+            name: singleton.1.to_string(),
             spawn_chance: singleton.0,
             structure: Vec::from([SpawnEvent {
                 x: None,
                 y: None,
                 enemy: singleton.1,
+                params: ObjectParams::default(),
             }]),
+            ..Default::default()
         });
     }
-    map.extend(import_structures().unwrap_or_default());
-    set_to_sums(&mut map);
-    let structure = commands.spawn(Map { map }).id();
+    map.extend(import_structures(catalogue.sprites()).unwrap_or_default());
+    let history = vec![SpawnHistory::default(); map.len()];
+    let structure = commands.spawn(Map { map, history }).id();
     commands.entity(structure);
 }
 
-fn set_to_sums(vec: &mut Vec<Structure>) {
+///Returns cumulative sums of `spawn_chance` of [Structure]s in [Map]. Structures which are not eligible at given [Speed] and
+/// [Score] add nothing, so they can never be picked.
+/// # Arguments
+/// * `map` - [Map].
+/// * `speed` - Current [Speed].
+/// * `score` - Current [Score].
+fn set_to_sums(map: &Map, speed: f32, score: f32) -> Vec<f64> {
     let mut sum: f64 = 0.;
-    for structure in vec {
-        let before_addition = structure.spawn_chance;
-        structure.spawn_chance += sum;
-        sum += before_addition;
-    }
+    map.map
+        .iter()
+        .zip(&map.history)
+        .map(|(structure, history)| {
+            if structure.eligible(speed, score, history) {
+                sum += structure.spawn_chance;
+            }
+            sum
+        })
+        .collect()
 }
 
 /// Despawns [Structure].
@@ -114,77 +302,145 @@ fn despawn_map(mut commands: Commands, map: Query<Entity, With<Map>>) {
     commands.entity(map.single()).despawn();
 }
 
-/// Spawns [Enemy]s from [Structure] based on their `time_ms`. Once [Structure] is empty, spawns random [Enemy]s.
-/// Optimalization reducing the number of arguments are being thought through as you read this.
+///[TextureAtlas] handles of all enemies, grouped so [spawning] stays within the system parameter limit.
+/// # Fields
+/// * `atlases` - [Atlases], containing [TextureAtlas]es of special enemies.
+/// * `catalogue` - [EnemyCatalogue], containing [TextureAtlas]es of [crate::basic::BasicObject]s.
+#[derive(SystemParam)]
+struct EnemySheets<'w> {
+    atlases: Res<'w, Atlases>,
+    catalogue: Res<'w, EnemyCatalogue>,
+}
+
+/// Spawns [Enemy]s of a randomly picked [Structure] once [Pacing] says there is enough space for it.
 /// # Arguments
-/// * `time` - [Time].
 /// * `commands` - [Commands].
-/// * `planets` - [Resource] containing handle for planet [TextureAtlas].
-/// * `hole` - [Resource] containing handle for black hole [TextureAtlas].
-/// * `energy_bar` - [Resource] containing handle for energy_bar [TextureAtlas].
-/// * `rainbow` - [Resource] containing handle for rainbow [TextureAtlas].
-/// * `full_choc` - [Resource] containing handle for full chocolate [TextureAtlas].
-/// * `part_choc` - [Resource] containing handle for partial chocolate [TextureAtlas].
-/// * `egg` - [Resource] containing handle for chocolate egg [TextureAtlas].
-/// * `lolly` - [Resource] containing handle for lollipop [TextureAtlas].
-/// * `love` - [Resource] containing handle for round gingerbread [TextureAtlas].
-/// * `drink` - [Resource] containing handle for drink [TextureAtlas].
+/// * `sheets` - [EnemySheets].
 /// * `query` -[Query] for [Structure].
-/// * `speed` - [Speed].
+/// * `rng` - [GameRng], enemies, structure picks and gaps use its gameplay stream.
+/// * `pacing` - [Pacing].
+/// * `speed` - [Speed]. Only [Structure]s eligible at the current speed are picked.
+/// * `score` - [Score]. Only [Structure]s eligible at the current score are picked.
+/// * `time` - [FixedTime], used to count down [SpawnHistory] cooldowns.
+/// * `settings` - [Settings], used to access the `fall_speed`, `min_gap` and `max_gap` fields.
+/// * `playfield` - [Playfield], enemies without a position are placed within it.
 fn spawning(
     mut commands: Commands,
-    planets: Res<PlanetSheet>,
-    hole: Res<HolesSheet>,
-    energy_bar: Res<EnergySheet>,
-    rainbow: Res<RainbowSheet>,
-    full_choc: Res<FullChocSheet>,
-    part_choc: Res<PartChocSheet>,
-    egg: Res<EggSheet>,
-    lolly: Res<LollySheet>,
-    love: Res<LoveSheet>,
-    drink: Res<KofolaSheet>,
+    sheets: EnemySheets,
     mut query: Query<&mut Map, With<Map>>,
-    object_q: Query<&Transform, With<Object>>,
+    mut rng: ResMut<GameRng>,
+    mut pacing: ResMut<Pacing>,
+    speed: Res<Speed>,
+    score: Res<Score>,
+    time: Res<FixedTime>,
+    settings: Res<Settings>,
+    playfield: Res<Playfield>,
 ) {
-    if object_q.iter().any(|x| x.translation.y > 300.) {
+    let delta = time.period.as_secs_f32();
+    let mut map = query.single_mut();
+    for history in map.history.iter_mut() {
+        history.cooldown = (history.cooldown - delta).max(0.);
+    }
+    if !pacing.advance(settings.fall_speed * speed.speed * delta) {
         return;
     }
-    let map = &query.single_mut().map;
-    let random_num = rand::random::<f64>() * (map.last().unwrap().spawn_chance);
-    for i in 0..map.len() {
-        if map[i].spawn_chance < random_num {
-            continue;
-        }
-        for spawn_event in &map[i].structure {
-            let (x, y, enemy) = (
-                spawn_event.x.map(|x| x as f32),
-                spawn_event.y.map(|y| y as f32),
-                &spawn_event.enemy,
-            );
-
-            match enemy {
-                &Enemy::HoleE => create_hole(x, y, &mut commands, &hole.0),
-
-                &Enemy::BarE => create_bar(x, y, &mut commands, &energy_bar.0),
-
-                &Enemy::RainbowE => create_rainbow(x, y, &mut commands, &rainbow.0),
-                &Enemy::PlaneE => create_plane_sensor(y, PlaneDir::Right, &mut commands),
-                &Enemy::PlanetE => create_planet(x, y, &mut commands, &planets.0),
-                &Enemy::BasicE => create_basic(
-                    x,
-                    y,
-                    &mut commands,
-                    &full_choc.0,
-                    &part_choc.0,
-                    &egg.0,
-                    &lolly.0,
-                    &love.0,
-                    &drink.0,
-                ),
-            }
-        }
+    let sums = set_to_sums(&map, speed.speed, score.score);
+    let rng = &mut rng.gameplay;
+    let Some(i) = pick_weighted(&sums, rng) else {
+        return;
+    };
+    let map = &mut *map;
+    let structure = &map.map[i];
+    map.history[i].repeats += 1;
+    map.history[i].cooldown = structure.cooldown.unwrap_or(0.);
+    pacing.schedule(structure.height(), settings.min_gap, settings.max_gap, rng);
+    debug!("Spawning structure {}", structure.name);
+    for spawn_event in &structure.structure {
+        let (x, y, enemy, params) = (
+            spawn_event.x.map(|x| x as f32),
+            spawn_event.y.map(|y| y as f32 + playfield.spawn_y()),
+            &spawn_event.enemy,
+            &spawn_event.params,
+        );
 
-        println!("");
-        break;
+        match enemy {
+            &Enemy::HoleE => create_hole(
+                x,
+                y,
+                params,
+                &mut commands,
+                rng,
+                &playfield,
+                &sheets.atlases.get("holes"),
+            ),
+
+            &Enemy::BarE => create_bar(
+                x,
+                y,
+                params,
+                &mut commands,
+                rng,
+                &playfield,
+                &sheets.atlases.get("energy"),
+            ),
+
+            &Enemy::PixieE => create_pixies(
+                x,
+                y,
+                params,
+                &mut commands,
+                rng,
+                &playfield,
+                &sheets.atlases.get("pixies"),
+            ),
+
+            &Enemy::WhiteHoleE => create_white_hole(
+                x,
+                y,
+                params,
+                &mut commands,
+                rng,
+                &playfield,
+                &sheets.atlases.get("white_holes"),
+            ),
+
+            &Enemy::FruitE => create_fruit(
+                x,
+                y,
+                params,
+                &mut commands,
+                rng,
+                &playfield,
+                &sheets.atlases.get("fruit"),
+            ),
+
+            &Enemy::RainbowE => create_rainbow(
+                x,
+                y,
+                &mut commands,
+                rng,
+                &playfield,
+                &sheets.atlases.get("rainbow"),
+            ),
+            &Enemy::PlaneE => create_plane_sensor(y, params, &mut commands, &playfield),
+            &Enemy::PlanetE => create_planet(
+                x,
+                y,
+                params,
+                &mut commands,
+                rng,
+                &playfield,
+                &sheets.atlases.get("planets"),
+            ),
+            &Enemy::BasicE => create_basic(
+                x,
+                y,
+                params,
+                &mut commands,
+                rng,
+                &playfield,
+                &sheets.catalogue,
+            ),
+        }
     }
 }
@@ -0,0 +1,341 @@
+use crate::{speed::Speed, volume::Volume, GameState};
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use bevy_kira_audio::Audio;
+use serde::Deserialize;
+use std::fmt;
+use std::fs::read_to_string;
+use std::io::ErrorKind;
+use std::time::Duration;
+
+///File [MusicConfig] is loaded from, unless a different one is passed with `--music PATH`.
+pub const MUSIC_FILE: &str = "./assets/music.ron";
+
+///[Plugin] playing every [MusicLayer] of [MusicConfig] and mixing them based on [GameState] and [Speed].
+pub struct MusicPlugin;
+
+///A single looped track. All layers start together, so stems of the same length stay in sync, and are faded in and
+/// out as the game goes on.
+/// # Fields
+/// * `file` - Sound in the assets folder.
+/// * `volume` - Volume when fully audible.
+/// * `states` - [GameState]s the layer is audible in, every state if empty. Two layers with different states
+///   crossfade when the state changes.
+/// * `speed` - [Speed] at which the layer starts fading in and at which it is fully audible. [None] if it does not
+///   depend on [Speed].
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MusicLayer {
+    pub file: String,
+    #[serde(default = "default_volume")]
+    pub volume: f64,
+    #[serde(default)]
+    pub states: Vec<GameState>,
+    #[serde(default)]
+    pub speed: Option<(f32, f32)>,
+}
+
+///How music changes once the game speeds up.
+/// # Fields
+/// * `from` - [Speed] at which the music starts speeding up.
+/// * `to` - [Speed] at which the music reaches `rate`.
+/// * `rate` - Highest playback rate, 1 is the original tempo and pitch.
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Tempo {
+    pub from: f32,
+    pub to: f32,
+    pub rate: f64,
+}
+
+///Content of [MUSIC_FILE].
+/// # Fields
+/// * `layers` - Every [MusicLayer].
+/// * `fade` - Seconds it takes to fade between two mixes.
+/// * `end_volume` - Volume multiplier in [GameState::EndScreen], ducking the music after death.
+/// * `end_rate` - Playback rate in [GameState::EndScreen], slowing the music down a bit.
+/// * `tempo` - [Tempo] during [GameState::Game], [None] if the music keeps its tempo.
+#[derive(Resource, Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MusicConfig {
+    pub layers: Vec<MusicLayer>,
+    #[serde(default = "default_fade")]
+    pub fade: f32,
+    #[serde(default = "default_volume")]
+    pub end_volume: f64,
+    #[serde(default = "default_volume")]
+    pub end_rate: f64,
+    #[serde(default)]
+    pub tempo: Option<Tempo>,
+}
+
+///Default of [MusicLayer] `volume`, [MusicConfig] `end_volume` and `end_rate`.
+fn default_volume() -> f64 {
+    1.
+}
+
+///Default of [MusicConfig] `fade`.
+fn default_fade() -> f32 {
+    1.
+}
+
+///Used without [MUSIC_FILE]. It has no layers, the music track is not part of the repository, so the default can't
+/// count on any file being there.
+impl Default for MusicConfig {
+    fn default() -> Self {
+        MusicConfig {
+            layers: Vec::new(),
+            fade: 1.,
+            end_volume: 0.4,
+            end_rate: 0.9,
+            tempo: Some(Tempo {
+                from: 2.5,
+                to: 5.,
+                rate: 1.08,
+            }),
+        }
+    }
+}
+
+///Errors returned by [MusicConfig::load].
+/// * `Io` - The file exists but could not be read.
+/// * `Parse` - The file is not valid RON or contains unknown fields.
+/// * `Invalid` - A value is out of its allowed range.
+#[derive(Debug)]
+pub enum MusicError {
+    Io {
+        path: String,
+        error: std::io::Error,
+    },
+    Parse {
+        path: String,
+        error: ron::error::SpannedError,
+    },
+    Invalid {
+        path: String,
+        field: &'static str,
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for MusicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MusicError::Io { path, error } => write!(f, "{}: {}", path, error),
+            MusicError::Parse { path, error } => write!(
+                f,
+                "{}:{}:{}: {}",
+                path, error.position.line, error.position.col, error.code
+            ),
+            MusicError::Invalid {
+                path,
+                field,
+                reason,
+            } => write!(f, "{}: `{}` {}", path, field, reason),
+        }
+    }
+}
+
+impl std::error::Error for MusicError {}
+
+impl MusicConfig {
+    ///Loads [MusicConfig] from a RON file. If the file does not exist, the default one without music is returned.
+    pub fn load(path: &str) -> Result<MusicConfig, MusicError> {
+        let content = match read_to_string(path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(MusicConfig::default()),
+            Err(error) => {
+                return Err(MusicError::Io {
+                    path: path.to_string(),
+                    error,
+                })
+            }
+        };
+        let config: MusicConfig = ron::from_str(&content).map_err(|error| MusicError::Parse {
+            path: path.to_string(),
+            error,
+        })?;
+        config
+            .validate()
+            .map_err(|(field, reason)| MusicError::Invalid {
+                path: path.to_string(),
+                field,
+                reason,
+            })?;
+        Ok(config)
+    }
+
+    ///Returns the first field with a value out of its allowed range and why.
+    fn validate(&self) -> Result<(), (&'static str, &'static str)> {
+        if self.layers.iter().any(|layer| layer.volume < 0.) {
+            return Err(("volume", "must not be negative"));
+        }
+        if self
+            .layers
+            .iter()
+            .filter_map(|layer| layer.speed)
+            .any(|(from, to)| from >= to)
+        {
+            return Err(("speed", "must start below where it ends"));
+        }
+        if self.fade < 0. {
+            return Err(("fade", "must not be negative"));
+        }
+        if self.end_volume < 0. {
+            return Err(("end_volume", "must not be negative"));
+        }
+        if self.end_rate <= 0. {
+            return Err(("end_rate", "must be greater than 0"));
+        }
+        if let Some(tempo) = self.tempo {
+            if tempo.from >= tempo.to {
+                return Err(("tempo", "must start below where it ends"));
+            }
+            if tempo.rate <= 0. {
+                return Err(("tempo", "rate must be greater than 0"));
+            }
+        }
+        Ok(())
+    }
+
+    ///Returns the volume `layer` should play at.
+    /// # Arguments
+    /// * `layer` - [MusicLayer].
+    /// * `state` - Current [GameState].
+    /// * `speed` - Current [Speed].
+    pub fn volume(&self, layer: &MusicLayer, state: GameState, speed: f32) -> f64 {
+        if !layer.states.is_empty() && !layer.states.contains(&state) {
+            return 0.;
+        }
+        let intensity = match layer.speed {
+            Some((from, to)) => ((speed - from) / (to - from)).clamp(0., 1.) as f64,
+            None => 1.,
+        };
+        let duck = if state == GameState::EndScreen {
+            self.end_volume
+        } else {
+            1.
+        };
+        layer.volume * intensity * duck
+    }
+
+    ///Returns the playback rate of every layer.
+    /// # Arguments
+    /// * `state` - Current [GameState].
+    /// * `speed` - Current [Speed].
+    pub fn rate(&self, state: GameState, speed: f32) -> f64 {
+        match (state, self.tempo) {
+            (GameState::EndScreen, _) => self.end_rate,
+            (GameState::Game, Some(tempo)) => {
+                let t = ((speed - tempo.from) / (tempo.to - tempo.from)).clamp(0., 1.) as f64;
+                1. + t * (tempo.rate - 1.)
+            }
+            _ => 1.,
+        }
+    }
+}
+
+///Reads [MusicConfig] from the file given by `--music PATH`, or from [MUSIC_FILE] if no path is given.
+/// # Arguments
+/// * `args` - Command line arguments.
+pub fn music_from_args(args: &[String]) -> Result<MusicConfig, String> {
+    let path = match args.iter().position(|arg| arg == "--music") {
+        Some(position) => args
+            .get(position + 1)
+            .ok_or_else(|| "--music expects a path".to_string())?,
+        None => MUSIC_FILE,
+    };
+    MusicConfig::load(path).map_err(|err| err.to_string())
+}
+
+///Playing instance of every [MusicLayer] and the volume and rate they were last set to.
+/// # Fields
+/// * `instances` - [AudioInstance] of every layer, in the same order as in [MusicConfig].
+/// * `applied` - Volume and rate each layer is fading to, [None] until its instance starts playing.
+#[derive(Resource, Default)]
+struct MusicMix {
+    instances: Vec<Handle<AudioInstance>>,
+    applied: Vec<Option<(f64, f64)>>,
+}
+
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MusicConfig>()
+            .init_resource::<MusicMix>()
+            .add_systems(Startup, start_music)
+            .add_systems(Update, mix_music);
+    }
+}
+
+///Starts every [MusicLayer] on repeat, muted. [mix_music] fades them in.
+/// # Arguments
+/// * `asset_server` - [AssetServer], used to load the music in.
+/// * `audio` - [Audio].
+/// * `config` - [MusicConfig].
+/// * `mix` - [MusicMix].
+fn start_music(
+    asset_server: Res<AssetServer>,
+    audio: Res<Audio>,
+    config: Res<MusicConfig>,
+    mut mix: ResMut<MusicMix>,
+) {
+    mix.instances = config
+        .layers
+        .iter()
+        .map(|layer| {
+            audio
+                .play(asset_server.load(layer.file.as_str()))
+                .with_volume(0.)
+                .looped()
+                .handle()
+        })
+        .collect();
+    mix.applied = vec![None; config.layers.len()];
+}
+
+///Fades every [MusicLayer] towards the volume and rate given by [MusicConfig] for the current [GameState] and
+/// [Speed], scaled by [Volume]. A new fade only starts once the target noticeably changes, so [Speed] rising every
+/// tick does not restart it every frame. Changes of [Volume] are applied almost immediately instead of faded.
+/// # Arguments
+/// * `config` - [MusicConfig].
+/// * `mix` - [MusicMix].
+/// * `instances` - [Assets] of [AudioInstance].
+/// * `state` - Current [GameState].
+/// * `speed` - [Speed].
+/// * `user_volume` - [Volume].
+fn mix_music(
+    config: Res<MusicConfig>,
+    mut mix: ResMut<MusicMix>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+    state: Res<State<GameState>>,
+    speed: Res<Speed>,
+    user_volume: Res<Volume>,
+) {
+    let state = *state.get();
+    let rate = config.rate(state, speed.speed);
+    let fade = if user_volume.is_changed() {
+        0.1
+    } else {
+        config.fade
+    };
+    let tween = AudioTween::new(Duration::from_secs_f32(fade), AudioEasing::InOutPowi(2));
+    let mix = &mut *mix;
+    for ((layer, handle), applied) in config
+        .layers
+        .iter()
+        .zip(&mix.instances)
+        .zip(mix.applied.iter_mut())
+    {
+        let volume = config.volume(layer, state, speed.speed) * user_volume.music();
+        if let Some((old_volume, old_rate)) = *applied {
+            if (old_volume - volume).abs() < 0.01 && (old_rate - rate).abs() < 0.005 {
+                continue;
+            }
+        }
+        if let Some(instance) = instances.get_mut(handle) {
+            instance.set_volume(volume, tween);
+            instance.set_playback_rate(rate, tween);
+            *applied = Some((volume, rate));
+        }
+    }
+}
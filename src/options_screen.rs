@@ -0,0 +1,698 @@
+use crate::{
+    controls::{Controls, InputDevice},
+    playfield::PlayfieldPanel,
+    preferences::{Language, Palette, Preferences},
+    volume::{mute_text, MuteButton, Volume},
+    GameState,
+};
+use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+use bevy_pkv::PkvStore;
+
+///[Plugin] taking care of the options screen, where [Volume], [Controls] `sensitivity` and device of the first
+/// player and [Preferences] are set.
+pub struct OptionsScreenPlugin;
+
+///Labels all [entities](Entity) which are spawned on enter to [GameState::Options] and despawn on exit.
+#[derive(Component)]
+struct OptionsScreen;
+
+///Button in the top left corner that takes player back to the main menu. When clicked, [GameState] changes
+/// to [GameState::MainMenu]. When hovered, its background color changes until unhovered.
+#[derive(Component)]
+struct OptionsBackButton;
+
+///Group of options shown on the options screen at once.
+/// * `Audio` - [Volume].
+/// * `Input` - [InputDevice] of the first player and [Controls] `sensitivity`.
+/// * `Display` - Window mode and [Language].
+/// * `Accessibility` - Screen shake, flickering, outlines, [Palette] and size of the HUD.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+enum Section {
+    #[default]
+    Audio,
+    Input,
+    Display,
+    Accessibility,
+}
+
+impl Section {
+    ///Every [Section], in the order their tabs are shown.
+    const ALL: [Section; 4] = [
+        Section::Audio,
+        Section::Input,
+        Section::Display,
+        Section::Accessibility,
+    ];
+
+    ///Name shown on its tab.
+    fn name(&self) -> &'static str {
+        match self {
+            Section::Audio => "Audio",
+            Section::Input => "Input",
+            Section::Display => "Display",
+            Section::Accessibility => "Accessibility",
+        }
+    }
+}
+
+///The [Section] currently shown. Kept when leaving the options screen.
+#[derive(Resource, Default)]
+struct OpenSection(Section);
+
+///A single value which can be set by a [Slider].
+/// * `Master` - [Volume] `master`.
+/// * `Music` - [Volume] `music`.
+/// * `Sfx` - [Volume] `sfx`.
+/// * `Sensitivity` - [Controls] `sensitivity`.
+/// * `Shake` - [Preferences] `shake`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Setting {
+    Master,
+    Music,
+    Sfx,
+    Sensitivity,
+    Shake,
+}
+
+///Lowest [Controls] `sensitivity` the [Slider] allows.
+const MIN_SENSITIVITY: f32 = 0.2;
+///Highest [Controls] `sensitivity` the [Slider] allows.
+const MAX_SENSITIVITY: f32 = 3.;
+
+impl Setting {
+    ///Every [Setting] of [Volume], in the order they are shown.
+    const VOLUME: [Setting; 3] = [Setting::Master, Setting::Music, Setting::Sfx];
+
+    ///Name shown next to its [Slider].
+    fn name(&self) -> &'static str {
+        match self {
+            Setting::Master => "Master",
+            Setting::Music => "Music",
+            Setting::Sfx => "Effects",
+            Setting::Sensitivity => "Sensitivity",
+            Setting::Shake => "Screen shake",
+        }
+    }
+
+    ///How full its [Slider] is, from 0 to 1.
+    fn fill(&self, volume: &Volume, controls: &Controls, preferences: &Preferences) -> f64 {
+        match self {
+            Setting::Master => volume.master,
+            Setting::Music => volume.music,
+            Setting::Sfx => volume.sfx,
+            Setting::Sensitivity => {
+                ((controls.sensitivity - MIN_SENSITIVITY) / (MAX_SENSITIVITY - MIN_SENSITIVITY))
+                    as f64
+            }
+            Setting::Shake => preferences.shake as f64,
+        }
+    }
+
+    ///Text shown on its [SliderLabel].
+    fn text(&self, volume: &Volume, controls: &Controls, preferences: &Preferences) -> String {
+        match self {
+            Setting::Sensitivity => format!("{:.1}x", controls.sensitivity),
+            _ => format!("{:.0}%", self.fill(volume, controls, preferences) * 100.),
+        }
+    }
+}
+
+///Bar setting a [Setting] to where it is clicked or dragged.
+#[derive(Component)]
+struct Slider(Setting);
+
+///Labels the filled part of a [Slider], its width shows the value.
+#[derive(Component)]
+struct SliderFill(Setting);
+
+///Labels the [Text] showing the value of a [Slider].
+#[derive(Component)]
+struct SliderLabel(Setting);
+
+///[Volume] values and [Preferences] `shake` snap to multiples of this.
+const PERCENT_STEP: f64 = 0.05;
+///[Controls] `sensitivity` snaps to multiples of this.
+const SENSITIVITY_STEP: f32 = 0.1;
+
+///Something picked by clicking a [ChoiceButton].
+/// * `Section` - Opens a [Section].
+/// * `Device` - [InputDevice] of the first player.
+/// * `Fullscreen` - [Preferences] `fullscreen`.
+/// * `Language` - [Preferences] `language`.
+/// * `SteadyText` - [Preferences] `steady_text`.
+/// * `Outlines` - [Preferences] `outlines`.
+/// * `Palette` - [Preferences] `palette`.
+/// * `LargeHud` - [Preferences] `large_hud`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Choice {
+    Section(Section),
+    Device(InputDevice),
+    Fullscreen(bool),
+    Language(Language),
+    SteadyText(bool),
+    Outlines(bool),
+    Palette(Palette),
+    LargeHud(bool),
+}
+
+impl Choice {
+    ///Text shown on its [ChoiceButton].
+    /// # Arguments
+    /// * `language` - [Language] of the text.
+    fn text(&self, language: Language) -> &'static str {
+        match self {
+            Choice::Section(section) => language.tr(section.name()),
+            Choice::Device(device) => language.tr(device.name()),
+            Choice::Fullscreen(false) => language.tr("Windowed"),
+            Choice::Fullscreen(true) => language.tr("Fullscreen"),
+            Choice::Language(language) => language.name(),
+            Choice::SteadyText(false) => language.tr("Flashing"),
+            Choice::SteadyText(true) => language.tr("Steady"),
+            Choice::Outlines(true) => language.tr("On"),
+            Choice::Outlines(false) => language.tr("Off"),
+            Choice::Palette(palette) => language.tr(palette.name()),
+            Choice::LargeHud(false) => language.tr("Normal"),
+            Choice::LargeHud(true) => language.tr("Large"),
+        }
+    }
+
+    ///Whether this is what is currently picked.
+    fn picked(&self, section: Section, controls: &Controls, preferences: &Preferences) -> bool {
+        match *self {
+            Choice::Section(choice) => choice == section,
+            Choice::Device(device) => controls.device == device,
+            Choice::Fullscreen(fullscreen) => preferences.fullscreen == fullscreen,
+            Choice::Language(language) => preferences.language == language,
+            Choice::SteadyText(steady) => preferences.steady_text == steady,
+            Choice::Outlines(outlines) => preferences.outlines == outlines,
+            Choice::Palette(palette) => preferences.palette == palette,
+            Choice::LargeHud(large) => preferences.large_hud == large,
+        }
+    }
+}
+
+///Button picking a [Choice]. The picked one is highlighted.
+#[derive(Component)]
+struct ChoiceButton(Choice);
+
+///Background color of a button.
+const BUTTON_COLOR: Color = Color::rgba(0., 0., 0., 0.1);
+///Background color of a hovered button.
+const HOVERED_COLOR: Color = Color::rgba(0., 0., 0., 0.7);
+///Background color of the picked [ChoiceButton].
+const PICKED_COLOR: Color = Color::rgba(1.0, 0.8, 0.9, 0.5);
+///Color of the filled part of a [Slider].
+const FILL_COLOR: Color = Color::rgba(1.0, 0.8, 0.9, 0.8);
+
+impl Plugin for OptionsScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OpenSection>()
+            .add_systems(
+                Update,
+                (
+                    spawn_options_screen,
+                    back_button_interaction,
+                    choice_button_interaction,
+                    drag_sliders,
+                    refresh_options_screen,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Options)),
+            )
+            .add_systems(
+                OnExit(GameState::Options),
+                (save_options, despawn_options_screen),
+            );
+    }
+}
+
+///Spawns [OptionsScreen] with [OptionsBackButton], a [ChoiceButton] for every [Section] and the options of the
+/// open one. Once [OpenSection] or [Language] changes, the screen is spawned again.
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `assets` - [AssetServer]. Used to load font.
+/// * `screen_q` - [Query] for the spawned [OptionsScreen].
+/// * `section` - [OpenSection].
+/// * `volume` - [Volume].
+/// * `controls` - [Controls].
+/// * `preferences` - [Preferences].
+/// * `spawned` - [Section] and [Language] the spawned [OptionsScreen] shows.
+fn spawn_options_screen(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    screen_q: Query<Entity, With<OptionsScreen>>,
+    section: Res<OpenSection>,
+    volume: Res<Volume>,
+    controls: Res<Controls>,
+    preferences: Res<Preferences>,
+    mut spawned: Local<Option<(Section, Language)>>,
+) {
+    let language = preferences.language;
+    if !screen_q.is_empty() && *spawned == Some((section.0, language)) {
+        return;
+    }
+    for entity in &screen_q {
+        commands.entity(entity).despawn_recursive();
+    }
+    *spawned = Some((section.0, language));
+
+    let font = assets.load("fonts\\Love_Letters.ttf");
+    let text_style = |font_size| TextStyle {
+        font: font.clone(),
+        font_size,
+        color: Color::rgb(0.9, 0.9, 0.9),
+    };
+    let row = || NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            margin: UiRect::all(Val::Px(4.)),
+            ..default()
+        },
+        ..default()
+    };
+    let label = |text: &str, width: f32| {
+        TextBundle::from_section(text, text_style(26.)).with_style(Style {
+            width: Val::Px(width),
+            ..default()
+        })
+    };
+    let note = |text: &str| {
+        TextBundle::from_section(text, text_style(20.))
+            .with_text_alignment(TextAlignment::Center)
+            .with_style(Style {
+                margin: UiRect::top(Val::Px(15.)),
+                ..default()
+            })
+    };
+    let choice = |parent: &mut ChildBuilder, choice: Choice, width: f32| {
+        let color = if choice.picked(section.0, &controls, &preferences) {
+            PICKED_COLOR
+        } else {
+            BUTTON_COLOR
+        };
+        parent
+            .spawn(ButtonBundle {
+                style: Style {
+                    width: Val::Px(width),
+                    height: Val::Px(45.),
+                    margin: UiRect::all(Val::Px(4.)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: color.into(),
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    choice.text(language),
+                    text_style(28.),
+                ));
+            })
+            .insert(ChoiceButton(choice));
+    };
+    let slider = |parent: &mut ChildBuilder, setting: Setting| {
+        parent.spawn(row()).with_children(|parent| {
+            parent.spawn(label(language.tr(setting.name()), 130.));
+            parent
+                .spawn(ButtonBundle {
+                    style: Style {
+                        width: Val::Px(300.),
+                        height: Val::Px(30.),
+                        margin: UiRect::horizontal(Val::Px(10.)),
+                        ..default()
+                    },
+                    background_color: BUTTON_COLOR.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                width: Val::Percent(
+                                    setting.fill(&volume, &controls, &preferences) as f32 * 100.,
+                                ),
+                                height: Val::Percent(100.),
+                                ..default()
+                            },
+                            background_color: FILL_COLOR.into(),
+                            ..default()
+                        })
+                        .insert(SliderFill(setting));
+                })
+                .insert(RelativeCursorPosition::default())
+                .insert(Slider(setting));
+            parent
+                .spawn(label(&setting.text(&volume, &controls, &preferences), 70.))
+                .insert(SliderLabel(setting));
+        });
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                margin: UiRect::all(Val::Auto),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::rgba(0.25, 0.15, 0.15, 0.7).into(), //Grey
+            ..default()
+        })
+        .insert(PlayfieldPanel)
+        .with_children(|parent| {
+            parent
+                .spawn(ButtonBundle {
+                    style: Style {
+                        width: Val::Px(250.0),
+                        height: Val::Px(65.0),
+                        margin: UiRect {
+                            left: Val::Px(10.),
+                            top: Val::Px(10.),
+                            ..default()
+                        },
+                        align_self: AlignSelf::FlexStart,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: BUTTON_COLOR.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        language.tr("Return to menu"),
+                        text_style(35.),
+                    ));
+                })
+                .insert(OptionsBackButton);
+            parent.spawn(TextBundle::from_section(
+                language.tr("Options"),
+                text_style(55.),
+            ));
+            parent.spawn(row()).with_children(|parent| {
+                for tab in Section::ALL {
+                    let width = if tab == Section::Accessibility {
+                        190.
+                    } else {
+                        130.
+                    };
+                    choice(parent, Choice::Section(tab), width);
+                }
+            });
+
+            match section.0 {
+                Section::Audio => {
+                    for setting in Setting::VOLUME {
+                        slider(parent, setting);
+                    }
+                    parent
+                        .spawn(ButtonBundle {
+                            style: Style {
+                                width: Val::Px(200.0),
+                                height: Val::Px(50.0),
+                                margin: UiRect::all(Val::Px(10.)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: BUTTON_COLOR.into(),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                mute_text(&volume, language),
+                                text_style(30.),
+                            ));
+                        })
+                        .insert(MuteButton);
+                    parent.spawn(note(language.tr(
+                        "Click or drag a bar to change it. F10 mutes the game anywhere.",
+                    )));
+                }
+                Section::Input => {
+                    parent.spawn(row()).with_children(|parent| {
+                        parent.spawn(label(language.tr("Play with"), 130.));
+                        for device in InputDevice::ALL {
+                            choice(parent, Choice::Device(device), 140.);
+                        }
+                    });
+                    slider(parent, Setting::Sensitivity);
+                    parent.spawn(note(language.tr(
+                        "Sensitivity scales how far the unicorn moves, for every device.",
+                    )));
+                    parent.spawn(note(
+                        language.tr("The second player's device is picked in Controls."),
+                    ));
+                }
+                Section::Display => {
+                    parent.spawn(row()).with_children(|parent| {
+                        parent.spawn(label(language.tr("Window"), 160.));
+                        choice(parent, Choice::Fullscreen(false), 120.);
+                        choice(parent, Choice::Fullscreen(true), 190.);
+                    });
+                    parent.spawn(row()).with_children(|parent| {
+                        parent.spawn(label(language.tr("Language"), 160.));
+                        for language in Language::ALL {
+                            choice(parent, Choice::Language(language), 140.);
+                        }
+                    });
+                    parent.spawn(note(language.tr("F11 switches the window mode too.")));
+                }
+                Section::Accessibility => {
+                    slider(parent, Setting::Shake);
+                    parent.spawn(row()).with_children(|parent| {
+                        parent.spawn(label(language.tr("Menu text"), 200.));
+                        choice(parent, Choice::SteadyText(false), 140.);
+                        choice(parent, Choice::SteadyText(true), 140.);
+                    });
+                    parent.spawn(row()).with_children(|parent| {
+                        parent.spawn(label(language.tr("Hazard outlines"), 200.));
+                        choice(parent, Choice::Outlines(true), 140.);
+                        choice(parent, Choice::Outlines(false), 140.);
+                    });
+                    parent.spawn(row()).with_children(|parent| {
+                        parent.spawn(label(language.tr("Colors"), 200.));
+                        for palette in Palette::ALL {
+                            choice(parent, Choice::Palette(palette), 140.);
+                        }
+                    });
+                    parent.spawn(row()).with_children(|parent| {
+                        parent.spawn(label(language.tr("HUD text"), 200.));
+                        choice(parent, Choice::LargeHud(false), 140.);
+                        choice(parent, Choice::LargeHud(true), 140.);
+                    });
+                    parent.spawn(note(
+                        language.tr("Colorblind colors change stars and energy bars."),
+                    ));
+                }
+            }
+        })
+        .insert(OptionsScreen);
+}
+
+///Handles interactions with [OptionsBackButton].
+/// # Arguments
+/// * `interaction_query` - [Query] for [OptionsBackButton] and its [Interaction] when changed.
+/// * `next` - [NextState] of [GameState].
+fn back_button_interaction(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<OptionsBackButton>),
+    >,
+    mut next: ResMut<NextState<GameState>>,
+) {
+    for (interaction, mut color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                next.set(GameState::MainMenu);
+            }
+            Interaction::Hovered => {
+                *color = HOVERED_COLOR.into();
+            }
+            Interaction::None => {
+                *color = BUTTON_COLOR.into();
+            }
+        }
+    }
+}
+
+///Handles interactions with [ChoiceButton]s. Everything picked is applied and stored right away.
+/// # Arguments
+/// * `interaction_query` - [Query] for [ChoiceButton]s and their [Interaction] when changed.
+/// * `section` - [OpenSection].
+/// * `controls` - [Controls].
+/// * `preferences` - [Preferences].
+/// * `pkv` - [PkvStore].
+fn choice_button_interaction(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor, &ChoiceButton),
+        Changed<Interaction>,
+    >,
+    mut section: ResMut<OpenSection>,
+    mut controls: ResMut<Controls>,
+    mut preferences: ResMut<Preferences>,
+    mut pkv: ResMut<PkvStore>,
+) {
+    for (interaction, mut color, choice) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                match choice.0 {
+                    Choice::Section(picked) => section.0 = picked,
+                    Choice::Device(device) => controls.set_device(0, device),
+                    Choice::Fullscreen(fullscreen) => preferences.fullscreen = fullscreen,
+                    Choice::Language(language) => preferences.language = language,
+                    Choice::SteadyText(steady) => preferences.steady_text = steady,
+                    Choice::Outlines(outlines) => preferences.outlines = outlines,
+                    Choice::Palette(palette) => preferences.palette = palette,
+                    Choice::LargeHud(large) => preferences.large_hud = large,
+                }
+                match choice.0 {
+                    Choice::Section(_) => {}
+                    Choice::Device(_) => controls.save(&mut pkv),
+                    _ => preferences.save(&mut pkv),
+                }
+            }
+            Interaction::Hovered => {
+                *color = HOVERED_COLOR.into();
+            }
+            Interaction::None => {
+                *color = if choice.0.picked(section.0, &controls, &preferences) {
+                    PICKED_COLOR.into()
+                } else {
+                    BUTTON_COLOR.into()
+                };
+            }
+        }
+    }
+}
+
+///Sets the [Setting] of a pressed [Slider] to where the cursor is, for as long as it is held. Stores the settings
+/// once the [Slider] is released.
+/// # Arguments
+/// * `slider_q` - [Query] for [Slider]s, their [Interaction] and [RelativeCursorPosition].
+/// * `volume` - [Volume].
+/// * `controls` - [Controls].
+/// * `preferences` - [Preferences].
+/// * `pkv` - [PkvStore].
+/// * `dragging` - Whether a [Slider] was held last frame.
+fn drag_sliders(
+    slider_q: Query<(&Interaction, &RelativeCursorPosition, &Slider)>,
+    mut volume: ResMut<Volume>,
+    mut controls: ResMut<Controls>,
+    mut preferences: ResMut<Preferences>,
+    mut pkv: ResMut<PkvStore>,
+    mut dragging: Local<bool>,
+) {
+    let held = slider_q
+        .iter()
+        .any(|(interaction, _, _)| *interaction == Interaction::Pressed);
+    if *dragging && !held {
+        volume.save(&mut pkv);
+        controls.save(&mut pkv);
+        preferences.save(&mut pkv);
+    }
+    *dragging = held;
+    for (interaction, cursor, slider) in &slider_q {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(position) = cursor.normalized else {
+            continue;
+        };
+        let position = position.x.clamp(0., 1.);
+        let percent = (position as f64 / PERCENT_STEP).round() * PERCENT_STEP;
+        match slider.0 {
+            Setting::Master if volume.master != percent => {
+                volume.master = percent;
+            }
+            Setting::Music if volume.music != percent => {
+                volume.music = percent;
+            }
+            Setting::Sfx if volume.sfx != percent => {
+                volume.sfx = percent;
+            }
+            Setting::Sensitivity => {
+                let sensitivity = ((MIN_SENSITIVITY
+                    + position * (MAX_SENSITIVITY - MIN_SENSITIVITY))
+                    / SENSITIVITY_STEP)
+                    .round()
+                    * SENSITIVITY_STEP;
+                if controls.sensitivity != sensitivity {
+                    controls.sensitivity = sensitivity;
+                }
+            }
+            Setting::Shake if preferences.shake != percent as f32 => {
+                preferences.shake = percent as f32;
+            }
+            _ => {}
+        }
+    }
+}
+
+///Updates [SliderFill]s, [SliderLabel]s and [ChoiceButton] colors once anything shown changes.
+/// # Arguments
+/// * `section` - [OpenSection].
+/// * `volume` - [Volume].
+/// * `controls` - [Controls].
+/// * `preferences` - [Preferences].
+/// * `fill_q` - [Query] for [SliderFill]s.
+/// * `label_q` - [Query] for [SliderLabel]s.
+/// * `choice_q` - [Query] for [ChoiceButton]s.
+fn refresh_options_screen(
+    section: Res<OpenSection>,
+    volume: Res<Volume>,
+    controls: Res<Controls>,
+    preferences: Res<Preferences>,
+    mut fill_q: Query<(&mut Style, &SliderFill)>,
+    mut label_q: Query<(&mut Text, &SliderLabel)>,
+    mut choice_q: Query<(&mut BackgroundColor, &Interaction, &ChoiceButton)>,
+) {
+    if !volume.is_changed() && !controls.is_changed() && !preferences.is_changed() {
+        return;
+    }
+    for (mut style, fill) in &mut fill_q {
+        style.width = Val::Percent(fill.0.fill(&volume, &controls, &preferences) as f32 * 100.);
+    }
+    for (mut text, label) in &mut label_q {
+        text.sections[0].value = label.0.text(&volume, &controls, &preferences);
+    }
+    for (mut color, interaction, choice) in &mut choice_q {
+        if *interaction == Interaction::None {
+            *color = if choice.0.picked(section.0, &controls, &preferences) {
+                PICKED_COLOR.into()
+            } else {
+                BUTTON_COLOR.into()
+            };
+        }
+    }
+}
+
+///Stores [Volume], [Controls] and [Preferences] on exit from [GameState::Options].
+/// # Arguments
+/// * `volume` - [Volume].
+/// * `controls` - [Controls].
+/// * `preferences` - [Preferences].
+/// * `pkv` - [PkvStore].
+fn save_options(
+    volume: Res<Volume>,
+    controls: Res<Controls>,
+    preferences: Res<Preferences>,
+    mut pkv: ResMut<PkvStore>,
+) {
+    volume.save(&mut pkv);
+    controls.save(&mut pkv);
+    preferences.save(&mut pkv);
+}
+
+///Despawns all [OptionsScreen] [entities](Entity).
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `query` - [Query] for [OptionsScreen].
+fn despawn_options_screen(mut commands: Commands, query: Query<Entity, With<OptionsScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,122 @@
+use crate::GameState;
+use bevy::prelude::*;
+use rand::Rng;
+
+///[Plugin] taking care of [Pacing].
+pub struct PacingPlugin;
+
+///Spacing between [crate::map_layout::Structure]s, measured in distance the world fell since the last one spawned.
+/// # Fields
+/// * `travelled` - Distance fallen since the last structure spawned.
+/// * `gap` - Distance which has to be fallen before the next structure spawns, the height of the last structure
+/// plus the free space below the next one.
+#[derive(Resource, Default)]
+pub struct Pacing {
+    pub travelled: f32,
+    pub gap: f32,
+}
+
+impl Plugin for PacingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Pacing>()
+            .add_systems(OnEnter(GameState::Game), reset_pacing);
+    }
+}
+
+impl Pacing {
+    ///Adds fallen distance. Returns whether the next structure should spawn.
+    /// # Arguments
+    /// * `distance` - Distance the world fell since the last call.
+    pub fn advance(&mut self, distance: f32) -> bool {
+        self.travelled += distance;
+        self.travelled >= self.gap
+    }
+
+    ///Starts measuring after a structure spawned. The free space between its top and the next structure
+    /// is picked between `min_gap` and `max_gap`.
+    /// # Arguments
+    /// * `height` - Height of the spawned structure.
+    /// * `min_gap` - Smallest allowed gap.
+    /// * `max_gap` - Largest allowed gap.
+    /// * `rng` - Gameplay stream of [crate::rng::GameRng].
+    pub fn schedule(&mut self, height: f32, min_gap: f32, max_gap: f32, rng: &mut impl Rng) {
+        self.travelled = 0.;
+        self.gap = height + min_gap + rng.gen::<f32>() * (max_gap - min_gap);
+    }
+}
+
+///Picks an index at random, each with a chance proportional to its weight.
+/// Returns [None] if all weights are 0. Runs in O(log n) thanks to binary search.
+/// # Arguments
+/// * `sums` - Cumulative sums of the weights, like the ones [crate::map_layout] picks [crate::map_layout::Structure]s from.
+/// * `rng` - Gameplay stream of [crate::rng::GameRng].
+pub fn pick_weighted(sums: &[f64], rng: &mut impl Rng) -> Option<usize> {
+    let total = sums.last().copied().unwrap_or(0.);
+    if total <= 0. {
+        return None;
+    }
+    let random_num = rng.gen::<f64>() * total;
+    Some(sums.partition_point(|&sum| sum <= random_num))
+}
+
+///Resets [Pacing] on enter to [GameState::Game], so the first structure of a run spawns right away.
+/// # Arguments
+/// * `pacing` - [Pacing].
+fn reset_pacing(mut pacing: ResMut<Pacing>) {
+    *pacing = Pacing::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn no_weights_pick_nothing() {
+        let mut rng = StdRng::seed_from_u64(1);
+        assert_eq!(pick_weighted(&[], &mut rng), None);
+        assert_eq!(pick_weighted(&[0., 0., 0.], &mut rng), None);
+    }
+
+    #[test]
+    fn boundaries_skip_zero_weights() {
+        //Weights 0, 1, 0, 2
+        let sums = [0., 1., 1., 3.];
+        //Lowest and highest number the generator can give
+        assert_eq!(pick_weighted(&sums, &mut StepRng::new(0, 0)), Some(1));
+        assert_eq!(
+            pick_weighted(&sums, &mut StepRng::new(u64::MAX, 0)),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn picks_follow_weights() {
+        let sums = [0., 1., 1., 3.];
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut counts = [0; 4];
+        for _ in 0..3000 {
+            counts[pick_weighted(&sums, &mut rng).unwrap()] += 1;
+        }
+        assert_eq!(counts[0], 0);
+        assert_eq!(counts[2], 0);
+        assert!((800..1200).contains(&counts[1]), "{:?}", counts);
+        assert_eq!(counts[1] + counts[3], 3000);
+    }
+
+    #[test]
+    fn gaps_stay_within_bounds() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut pacing = Pacing::default();
+        assert!(pacing.advance(0.));
+        for _ in 0..1000 {
+            pacing.schedule(150., 200., 400., &mut rng);
+            let gap = pacing.gap - 150.;
+            assert!((200. ..=400.).contains(&gap), "{}", gap);
+            assert!(!pacing.advance(349.));
+            assert!(pacing.advance(pacing.gap - 349.));
+        }
+    }
+}
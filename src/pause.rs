@@ -0,0 +1,234 @@
+use crate::{
+    playfield::PlayfieldPanel,
+    preferences::Preferences,
+    replay::PendingInputs,
+    volume::{mute_text, MuteButton, Volume},
+    GameState,
+};
+use bevy::prelude::*;
+use bevy::window::WindowFocused;
+
+///[Plugin] taking care of pausing and the [PauseMenu].
+pub struct PausePlugin;
+
+///Whether the game is paused. Only ever [PauseState::Paused] in [GameState::Game]. While paused, no gameplay tick runs
+/// and cosmetic effects like [crate::player::Star]s and [crate::clouds::Cloud]s stop spawning.
+/// * `Running` - The game runs normally.
+/// * `Paused` - The game is frozen and [PauseMenu] is shown.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, Default, States)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+}
+
+///Labels all [entities](Entity) which are spawned on enter to [PauseState::Paused] and despawn on exit.
+#[derive(Component)]
+struct PauseMenu;
+
+///The "Resume" button. When clicked, [PauseState] changes to [PauseState::Running].
+/// When hovered, its background color changes until unhovered.
+#[derive(Component)]
+struct ResumeButton;
+
+///The "Quit" button. When clicked, the run ends and [GameState] changes to [GameState::EndScreen].
+/// When hovered, its background color changes until unhovered.
+#[derive(Component)]
+struct QuitButton;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (toggle_pause, pause_on_focus_loss).run_if(in_state(GameState::Game)),
+        )
+        .add_systems(
+            Update,
+            pause_menu_interaction.run_if(in_state(PauseState::Paused)),
+        )
+        .add_systems(OnEnter(PauseState::Paused), spawn_pause_menu)
+        .add_systems(
+            OnExit(PauseState::Paused),
+            (despawn_pause_menu, reset_tick_clock),
+        )
+        .add_systems(OnExit(GameState::Game), unpause);
+    }
+}
+
+///Toggles [PauseState] when Escape or the right mouse button is pressed.
+/// # Arguments
+/// * `keys` - Keyboard input.
+/// * `buttons` - Mouse input.
+/// * `state` - Current [PauseState].
+/// * `next` - [NextState] of [PauseState].
+fn toggle_pause(
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<MouseButton>>,
+    state: Res<State<PauseState>>,
+    mut next: ResMut<NextState<PauseState>>,
+) {
+    if keys.just_pressed(KeyCode::Escape) || buttons.just_pressed(MouseButton::Right) {
+        next.set(match state.get() {
+            PauseState::Running => PauseState::Paused,
+            PauseState::Paused => PauseState::Running,
+        });
+    }
+}
+
+///Pauses the game once the window loses focus.
+/// # Arguments
+/// * `focus` - Reads [WindowFocused] events.
+/// * `next` - [NextState] of [PauseState].
+fn pause_on_focus_loss(
+    mut focus: EventReader<WindowFocused>,
+    mut next: ResMut<NextState<PauseState>>,
+) {
+    if focus.iter().any(|event| !event.focused) {
+        next.set(PauseState::Paused);
+    }
+}
+
+///Makes sure the game is not left paused on exit from [GameState::Game].
+/// # Arguments
+/// * `next` - [NextState] of [PauseState].
+fn unpause(mut next: ResMut<NextState<PauseState>>) {
+    next.set(PauseState::Running);
+}
+
+///Throws away time and input gathered while paused on exit from [PauseState::Paused],
+/// so the game does not catch up on missed gameplay ticks and the click on [ResumeButton] does not hide [crate::player::Player].
+/// # Arguments
+/// * `fixed_time` - [FixedTime].
+/// * `pending` - [PendingInputs].
+fn reset_tick_clock(mut fixed_time: ResMut<FixedTime>, mut pending: ResMut<PendingInputs>) {
+    *fixed_time = FixedTime::new(fixed_time.period);
+    pending.discard();
+}
+
+///Spawns [PauseMenu] with [ResumeButton], [MuteButton] and [QuitButton].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `assets` - [AssetServer]. Used to load font.
+/// * `volume` - [Volume], shown on [MuteButton].
+/// * `preferences` - [Preferences], texts are in its `language`.
+fn spawn_pause_menu(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    volume: Res<Volume>,
+    preferences: Res<Preferences>,
+) {
+    let language = preferences.language;
+    let font = assets.load("fonts\\Love_Letters.ttf");
+    let title_style = TextStyle {
+        font: font.clone(),
+        font_size: 55.0,
+        color: Color::rgb(0.9, 0.9, 0.9),
+    };
+    let button_style = TextStyle {
+        font,
+        font_size: 35.0,
+        color: Color::rgb(0.9, 0.9, 0.9),
+    };
+    let button = || ButtonBundle {
+        style: Style {
+            width: Val::Px(200.0),
+            height: Val::Px(65.0),
+            margin: UiRect::all(Val::Px(10.)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        background_color: Color::rgba(0., 0., 0., 0.1).into(),
+        ..default()
+    };
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                margin: UiRect::all(Val::Auto),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::rgba(0.25, 0.15, 0.15, 0.7).into(), //Grey
+            ..default()
+        })
+        .insert(PlayfieldPanel)
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(language.tr("Paused"), title_style).with_style(Style {
+                    margin: UiRect::bottom(Val::Px(30.)),
+                    ..default()
+                }),
+            );
+            parent
+                .spawn(button())
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        language.tr("Resume"),
+                        button_style.clone(),
+                    ));
+                })
+                .insert(ResumeButton);
+            parent
+                .spawn(button())
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        mute_text(&volume, language),
+                        button_style.clone(),
+                    ));
+                })
+                .insert(MuteButton);
+            parent
+                .spawn(button())
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(language.tr("Quit"), button_style));
+                })
+                .insert(QuitButton);
+        })
+        .insert(PauseMenu);
+}
+
+///Handles interactions with [ResumeButton] and [QuitButton].
+/// # Arguments
+/// * `interaction_query` - [Query] for the buttons and their [Interaction] when changed.
+/// * `next_pause` - [NextState] of [PauseState].
+/// * `next_game` - [NextState] of [GameState].
+fn pause_menu_interaction(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor, Option<&QuitButton>),
+        (
+            Changed<Interaction>,
+            Or<(With<ResumeButton>, With<QuitButton>)>,
+        ),
+    >,
+    mut next_pause: ResMut<NextState<PauseState>>,
+    mut next_game: ResMut<NextState<GameState>>,
+) {
+    for (interaction, mut color, quit) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                next_pause.set(PauseState::Running);
+                if quit.is_some() {
+                    next_game.set(GameState::EndScreen);
+                }
+            }
+            Interaction::Hovered => {
+                *color = Color::rgba(0., 0., 0., 0.7).into();
+            }
+            Interaction::None => {
+                *color = Color::rgba(0., 0., 0., 0.1).into();
+            }
+        }
+    }
+}
+
+///Despawns all [PauseMenu] [entities](Entity).
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `query` - [Query] for [PauseMenu].
+fn despawn_pause_menu(mut commands: Commands, query: Query<Entity, With<PauseMenu>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{
+    collisions::Damaging,
+    falling::FallTimer,
+    map_layout::{Enemy, ObjectParams},
+    player::{nearest_player, Hidden, Player},
+    playfield::Playfield,
+    walls::enemy_groups,
+    AnimationTimer, GameplaySet, Object,
+};
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::{rngs::StdRng, Rng};
+
+///Biggest swarm a structure file can ask for.
+pub const MAX_SWARM: usize = 50;
+///Pixies in a swarm when the structure does not say otherwise.
+const DEFAULT_SWARM: usize = 5;
+///Distance in which pixies of the same swarm see each other.
+const NEIGHBOUR_RADIUS: f32 = 90.;
+///Distance under which pixies try to keep apart.
+const SEPARATION_RADIUS: f32 = 30.;
+///Distance in which pixies notice [Player].
+const CHASE_RADIUS: f32 = 350.;
+///Fastest a pixie flies on its own, on top of falling, in px/s.
+const MAX_SPEED: f32 = 160.;
+///Strongest a pixie can steer at weight 1, in px/s².
+const MAX_STEER: f32 = 300.;
+
+///[Plugin] taking care of [Pixie] flocking.
+pub struct PixiePlugin;
+
+///How much each rule steers a pixie. Weights are multiplied by [MAX_STEER].
+/// # Fields
+/// * `separation` - Keeping apart from pixies closer than [SEPARATION_RADIUS].
+/// * `alignment` - Flying the same way as the rest of the swarm.
+/// * `cohesion` - Flying towards the middle of the swarm.
+/// * `chase` - Flying towards [Player], negative values fly away from him.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Flocking {
+    pub separation: f32,
+    pub alignment: f32,
+    pub cohesion: f32,
+    pub chase: f32,
+}
+
+impl Default for Flocking {
+    fn default() -> Self {
+        Flocking {
+            separation: 1.5,
+            alignment: 1.,
+            cohesion: 1.,
+            chase: 0.8,
+        }
+    }
+}
+
+///A small damaging pixie flying in a swarm.
+/// # Fields
+/// * `swarm` - Pixies only flock with pixies of the same swarm.
+/// * `velocity` - Its own velocity, falling is added by [crate::falling::FallPlugin].
+/// * `flocking` - [Flocking] weights of its swarm.
+#[derive(Component)]
+pub struct Pixie {
+    pub swarm: u32,
+    pub velocity: Vec2,
+    pub flocking: Flocking,
+}
+
+impl Plugin for PixiePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, flocking.in_set(GameplaySet::Logic));
+    }
+}
+
+///Spawns a swarm of [Pixie]s scattered around one point.
+/// # Arguments
+/// * `x` - if [None], a random `x` within [Playfield] is chosen.
+/// * `y` - if [None], it is set to [Playfield::spawn_y], above the upper bound.
+/// * `params` - [ObjectParams], `count`, `separation`, `alignment`, `cohesion`, `chase` and `fall_speed` are used.
+/// * `commands` - [Commands].
+/// * `rng` - Gameplay stream of [crate::rng::GameRng].
+/// * `playfield` - [Playfield].
+/// * `texture` - Handle for pixie [TextureAtlas]
+pub fn create_pixies(
+    x: Option<f32>,
+    y: Option<f32>,
+    params: &ObjectParams,
+    commands: &mut Commands,
+    rng: &mut StdRng,
+    playfield: &Playfield,
+    texture: &Handle<TextureAtlas>,
+) {
+    let x = x.unwrap_or(playfield.random_x(rng));
+    let y = y.unwrap_or(playfield.spawn_y());
+    let swarm = rng.gen();
+    let flocking = params.flocking();
+
+    for _ in 0..params.count.unwrap_or(DEFAULT_SWARM) {
+        let offset = Vec2::new(rng.gen::<f32>() - 0.5, rng.gen::<f32>() - 0.5) * 60.;
+        let heading = Vec2::from_angle(rng.gen::<f32>() * std::f32::consts::TAU);
+        let mut sprite = TextureAtlasSprite::new(rng.gen::<usize>() % 2);
+        sprite.custom_size = Some(Vec2::splat(32.));
+        commands
+            .spawn(SpriteSheetBundle {
+                sprite,
+                texture_atlas: texture.clone(),
+                transform: Transform::from_xyz(x + offset.x, y + offset.y, 850. + rng.gen::<f32>()),
+                ..Default::default()
+            })
+            .insert(Pixie {
+                swarm,
+                velocity: heading * MAX_SPEED / 2.,
+                flocking,
+            })
+            .insert(Damaging(Enemy::PixieE))
+            .insert(Collider::ball(10.))
+            .insert(Sensor)
+            .insert(FallTimer(Timer::from_seconds(9.85, TimerMode::Once)))
+            .insert(params.fall_speed())
+            .insert(AnimationTimer(Timer::from_seconds(
+                0.12,
+                TimerMode::Repeating,
+            )))
+            .insert(enemy_groups())
+            .insert(Object);
+    }
+}
+
+///Returns how a pixie steers, following the boids rules.
+/// # Arguments
+/// * `position` - Position of the pixie.
+/// * `velocity` - Its velocity.
+/// * `neighbours` - Positions and velocities of pixies of the same swarm within [NEIGHBOUR_RADIUS].
+/// * `target` - Position of [Player], [None] if he is hidden or too far.
+/// * `flocking` - [Flocking] weights.
+pub fn steer(
+    position: Vec2,
+    velocity: Vec2,
+    neighbours: &[(Vec2, Vec2)],
+    target: Option<Vec2>,
+    flocking: &Flocking,
+) -> Vec2 {
+    let mut steering = Vec2::ZERO;
+    if !neighbours.is_empty() {
+        let count = neighbours.len() as f32;
+        let separation: Vec2 = neighbours
+            .iter()
+            .map(|(other, _)| position - *other)
+            .filter(|away| away.length() < SEPARATION_RADIUS)
+            .map(|away| away.normalize_or_zero() * (1. - away.length() / SEPARATION_RADIUS))
+            .sum();
+        let heading = neighbours.iter().map(|(_, other)| *other).sum::<Vec2>() / count;
+        let centre = neighbours.iter().map(|(other, _)| *other).sum::<Vec2>() / count;
+        steering += separation.clamp_length_max(1.) * flocking.separation;
+        steering += ((heading - velocity) / MAX_SPEED).clamp_length_max(1.) * flocking.alignment;
+        steering +=
+            ((centre - position) / NEIGHBOUR_RADIUS).clamp_length_max(1.) * flocking.cohesion;
+    }
+    if let Some(target) = target {
+        steering += (target - position).normalize_or_zero() * flocking.chase;
+    }
+    steering * MAX_STEER
+}
+
+///Moves [Pixie]s every gameplay tick. Every pixie steers by [steer], then flies with its velocity capped at [MAX_SPEED],
+/// bouncing off the sides of the screen.
+/// # Arguments
+/// * `pixie_q` - [Query] for [Pixie]s.
+/// * `player_q` - [Query] for [Player].
+/// * `time` - [FixedTime].
+/// * `playfield` - [Playfield], pixies bounce off its sides.
+fn flocking(
+    mut pixie_q: Query<(&mut Pixie, &mut Transform, &mut TextureAtlasSprite), Without<Player>>,
+    player_q: Query<(&Hidden, &Transform), With<Player>>,
+    time: Res<FixedTime>,
+    playfield: Res<Playfield>,
+) {
+    let delta = time.period.as_secs_f32();
+    let players = player_q
+        .iter()
+        .filter(|(hidden, _)| !hidden.hidden)
+        .map(|(_, transform)| transform.translation.truncate())
+        .collect::<Vec<Vec2>>();
+    //Every pixie steers by where the others were at the start of the tick
+    let snapshot: Vec<(u32, Vec2, Vec2)> = pixie_q
+        .iter()
+        .map(|(pixie, transform, _)| {
+            (
+                pixie.swarm,
+                transform.translation.truncate(),
+                pixie.velocity,
+            )
+        })
+        .collect();
+    let hori_bound = playfield.half_width();
+    let mut neighbours = Vec::new();
+    for (mut pixie, mut transform, mut sprite) in &mut pixie_q {
+        let position = transform.translation.truncate();
+        neighbours.clear();
+        neighbours.extend(
+            snapshot
+                .iter()
+                .filter(|(swarm, other, _)| {
+                    *swarm == pixie.swarm
+                        && *other != position
+                        && other.distance(position) < NEIGHBOUR_RADIUS
+                })
+                .map(|(_, other, velocity)| (*other, *velocity)),
+        );
+        let target = nearest_player(position, players.iter().copied())
+            .filter(|player| player.distance(position) < CHASE_RADIUS);
+        let steering = steer(
+            position,
+            pixie.velocity,
+            &neighbours,
+            target,
+            &pixie.flocking,
+        );
+        pixie.velocity = (pixie.velocity + steering * delta).clamp_length_max(MAX_SPEED);
+        if (position.x <= -hori_bound && pixie.velocity.x < 0.)
+            || (position.x >= hori_bound && pixie.velocity.x > 0.)
+        {
+            pixie.velocity.x = -pixie.velocity.x;
+        }
+        transform.translation += (pixie.velocity * delta).extend(0.);
+        sprite.flip_x = pixie.velocity.x < 0.;
+    }
+}
@@ -1,8 +1,16 @@
 use crate::{
-    collisions::Damaging, falling::FallTimer, speed::Speed, AnimationTimer, GameState, Object,
+    collisions::Damaging,
+    falling::FallTimer,
+    map_layout::{Enemy, ObjectParams},
+    pause::PauseState,
+    playfield::Playfield,
+    speed::Speed,
+    walls::enemy_groups,
+    AnimationTimer, GameState, GameplaySet, Object,
 };
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
+use rand::{rngs::StdRng, Rng};
 
 ///[Plugin] taking care of functionalities corelating with [Plane]
 pub struct PlanePlugin;
@@ -29,7 +37,7 @@ pub struct PlaneSensor {
 /// # Fields
 /// * `Left`
 /// * `Right`
-#[derive(PartialEq, Eq, Component, Clone, Copy)]
+#[derive(PartialEq, Eq, Component, Clone, Copy, Debug)]
 pub enum PlaneDir {
     Left,
     Right,
@@ -38,21 +46,34 @@ pub enum PlaneDir {
 impl Plugin for PlanePlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
-            Update,
-            (plane_movement, despawn_planes).run_if(in_state(GameState::Game)),
+            FixedUpdate,
+            (plane_movement, despawn_planes).in_set(GameplaySet::Logic),
         )
         .add_systems(OnExit(GameState::EndScreen), plane_endscreen_despawn)
-        .add_systems(Update, plane_movement);
+        .add_systems(
+            FixedUpdate,
+            plane_movement
+                .after(GameplaySet::Logic)
+                .before(PhysicsSet::SyncBackend)
+                .run_if(in_state(PauseState::Running)),
+        );
     }
 }
 
 ///Spawns a [PlaneSensor].
 /// # Arguments
-/// * `y` - if [None], it is set 100px above upper bound.
-/// * `dir` - [PlaneDir]
+/// * `y` - if [None], it is set to [Playfield::spawn_y], above the upper bound.
+/// * `params` - [ObjectParams], `dir` sets the [PlaneDir] of the spawned [Plane].
 /// * `commands` - [Commands]
-pub fn create_plane_sensor(y: Option<f32>, dir: PlaneDir, commands: &mut Commands) {
-    let y = y.unwrap_or(600.);
+/// * `playfield` - [Playfield].
+pub fn create_plane_sensor(
+    y: Option<f32>,
+    params: &ObjectParams,
+    commands: &mut Commands,
+    playfield: &Playfield,
+) {
+    let y = y.unwrap_or(playfield.spawn_y());
+    let dir = params.dir.unwrap_or(PlaneDir::Right);
     //no optional custom x because its hitbox is "infinitely" long
     let sensor = commands
         .spawn(TransformBundle {
@@ -65,7 +86,9 @@ pub fn create_plane_sensor(y: Option<f32>, dir: PlaneDir, commands: &mut Command
         .insert(Collider::cuboid(2000., 0.1))
         .insert(Sensor)
         .insert(FallTimer(Timer::from_seconds(11., TimerMode::Once)))
+        .insert(params.fall_speed())
         .insert(PlaneSensor { dir })
+        .insert(enemy_groups())
         .insert(Object)
         .id();
     commands.entity(sensor);
@@ -73,13 +96,18 @@ pub fn create_plane_sensor(y: Option<f32>, dir: PlaneDir, commands: &mut Command
 
 ///Spawns a [Plane].
 /// # Arguments
-/// * `y` - if [None], it is set 100px above upper bound.
+/// * `y` - Height of the [PlaneSensor] that spawned it.
 /// * `dir` - [PlaneDir].
 /// * `commands` - [Commands].
+/// * `rng` - Gameplay stream of [crate::rng::GameRng].
+/// * `playfield` - [Playfield], planes start just behind its edge.
+/// * `texture` - [Handle] for plane [TextureAtlas].
 pub fn create_plane(
     dir: PlaneDir,
     y: f32,
     commands: &mut Commands,
+    rng: &mut StdRng,
+    playfield: &Playfield,
     texture: &Handle<TextureAtlas>,
 ) {
     let mut sprite = TextureAtlasSprite::new(0);
@@ -94,9 +122,9 @@ pub fn create_plane(
             texture_atlas: texture.clone(),
             transform: Transform {
                 translation: Vec3::new(
-                    (1920. / 6. + 100.) * if dir == PlaneDir::Right { -1. } else { 1. },
+                    (playfield.half_width() + 100.) * if dir == PlaneDir::Right { -1. } else { 1. },
                     y + 300.,
-                    900. + rand::random::<f32>(),
+                    900. + rng.gen::<f32>(),
                 ),
                 scale: Vec3::splat(0.6),
                 ..Default::default()
@@ -107,7 +135,7 @@ pub fn create_plane(
             dir,
             timer: Timer::from_seconds(3.5, TimerMode::Once),
         })
-        .insert(Damaging)
+        .insert(Damaging(Enemy::PlaneE))
         .insert(RigidBody::Fixed)
         .insert(Collider::compound(vec![(
             Vec2::new(0., -17.),
@@ -118,6 +146,7 @@ pub fn create_plane(
             0.2,
             TimerMode::Repeating,
         )))
+        .insert(enemy_groups())
         .insert(Object)
         .id();
     commands.entity(plane);
@@ -126,21 +155,22 @@ pub fn create_plane(
 ///Moves [Plane] both vertically down and horizontally based on its `dir`. Vertical velocity is slower than of [FallTimer] objects.
 /// # Arguments
 /// * `plane_query` - [Query] for [Plane].
-/// * `time` - [Time].
+/// * `time` - [FixedTime].
 /// * `speed` - [Speed]. Only speeds up *vertical* velocity.
 fn plane_movement(
     mut plane_query: Query<(&mut Transform, &Plane)>,
-    time: Res<Time>,
+    time: Res<FixedTime>,
     speed: Res<Speed>,
 ) {
     let speed = speed.speed;
+    let delta = time.period.as_secs_f32();
     for (mut transform, plane) in plane_query.iter_mut() {
         match plane.dir {
-            PlaneDir::Right => transform.translation.x += 200. * time.delta_seconds(),
-            PlaneDir::Left => transform.translation.x -= 200. * time.delta_seconds(),
+            PlaneDir::Right => transform.translation.x += 200. * delta,
+            PlaneDir::Left => transform.translation.x -= 200. * delta,
         }
         //needs to fall slower and despawn not based on speed
-        transform.translation.y -= 100. * time.delta_seconds() * speed;
+        transform.translation.y -= 100. * delta * speed;
     }
 }
 
@@ -148,14 +178,14 @@ fn plane_movement(
 /// # Arguments
 /// * `commands` - [Commands].
 /// * `query` - [Query] for [Plane].
-/// * `time` - [Time].
+/// * `time` - [FixedTime].
 fn despawn_planes(
     mut commands: Commands,
     mut query: Query<(Entity, &mut Plane), With<Plane>>,
-    time: Res<Time>,
+    time: Res<FixedTime>,
 ) {
     for (entity, mut plane) in &mut query {
-        plane.timer.tick(time.delta());
+        plane.timer.tick(time.period);
         if plane.timer.just_finished() {
             commands.entity(entity).despawn();
         }
@@ -1,7 +1,13 @@
-use crate::{falling::FallTimer, Gravitating, Object};
+use crate::{
+    falling::FallTimer, map_layout::ObjectParams, playfield::Playfield, walls::enemy_groups,
+    Gravitating, Object,
+};
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::{Collider, RigidBody};
+use rand::{rngs::StdRng, Rng};
 pub const PLANET_SIZE: f32 = 120.;
+///Number of sprites in the planet [TextureAtlas].
+pub const PLANET_SPRITES: usize = 15;
 
 ///Labels a planet [Entity], a non-damaging solid ball enemy with [Gravitating] property.
 #[derive(Component)]
@@ -9,37 +15,48 @@ pub struct Planet;
 
 ///Spawns a [Planet].
 /// # Arguments
-/// * `x` - if [None], a random `x` within resolution is chosen.
-/// * `y` - if [None], it is set 100px above upper bound.
+/// * `x` - if [None], a random `x` within [Playfield] is chosen.
+/// * `y` - if [None], it is set to [Playfield::spawn_y], above the upper bound.
+/// * `params` - [ObjectParams], `sprite`, `rotation`, `gravity` and `fall_speed` are used.
 /// * `commands` - [Commands].
+/// * `rng` - Gameplay stream of [crate::rng::GameRng].
+/// * `playfield` - [Playfield].
 /// * `texture` - [Handle] for planet [TextureAtlas].
 pub fn create_planet(
     x: Option<f32>,
     y: Option<f32>,
+    params: &ObjectParams,
     commands: &mut Commands,
+    rng: &mut StdRng,
+    playfield: &Playfield,
     texture: &Handle<TextureAtlas>,
 ) {
-    let random_num: usize = rand::random();
-    let mut sprite = TextureAtlasSprite::new(random_num % 15);
+    let random_num: usize = rng.gen();
+    let mut sprite = TextureAtlasSprite::new(params.sprite.unwrap_or(random_num % PLANET_SPRITES));
     sprite.custom_size = Some(Vec2::splat(PLANET_SIZE));
-    let x = x.unwrap_or((rand::random::<f32>() - 0.5) * (1920. / 3.));
-    let y = y.unwrap_or(600.);
+    let x = x.unwrap_or(playfield.random_x(rng));
+    let y = y.unwrap_or(playfield.spawn_y());
     let planet = commands
         .spawn(SpriteSheetBundle {
             sprite,
             texture_atlas: texture.clone(),
             transform: Transform {
-                translation: Vec3::new(x, y, 900. + rand::random::<f32>()),
-                rotation: Quat::from_rotation_z((random_num % 360) as f32 / 180.),
+                translation: Vec3::new(x, y, 900. + rng.gen::<f32>()),
+                rotation: params
+                    .rotation_or(Quat::from_rotation_z((random_num % 360) as f32 / 180.)),
                 ..Default::default()
             },
             ..Default::default()
         })
         .insert(Planet)
         .insert(FallTimer(Timer::from_seconds(7., TimerMode::Once)))
+        .insert(params.fall_speed())
         .insert(RigidBody::Fixed)
-        .insert(Gravitating { strength: 1. })
+        .insert(Gravitating {
+            strength: params.gravity.unwrap_or(1.),
+        })
         .insert(Collider::ball(57.))
+        .insert(enemy_groups())
         .insert(Object)
         .id();
     commands.entity(planet);
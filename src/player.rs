@@ -1,17 +1,47 @@
-use crate::{speed::Speed, GameState, Gravitating, Settings, StarsSheet, UnicornSheet};
+use crate::{
+    audio::SfxEvent,
+    blackhole::{Hole, BLACKHOLE_SIZE},
+    loading::Atlases,
+    map_layout::Enemy,
+    pause::PauseState,
+    preferences::Preferences,
+    replay::TickInputs,
+    rng::GameRng,
+    speed::Speed,
+    walls::player_groups,
+    GameState, GameplaySet, Gravitating, Settings,
+};
 use bevy::prelude::*;
-use bevy_mouse_tracking_plugin::{mouse_motion::MouseMotionPlugin, MouseMotion};
 use bevy_rapier2d::prelude::*;
+use rand::Rng;
 
 ///[Plugin] taking care of functionalities corelating with [Player]
 pub struct PlayerPlugin;
 
+///Most hp [Player] can start with, the unicorn spritesheet has a damage stage for every hp from 0 to this.
+pub const MAX_PLAYER_HP: i32 = 3;
+
+///Index of the unicorn sprite showing given hp. The sheet has a visible and a hidden sprite for every damage stage.
+/// Hp above [MAX_PLAYER_HP], which fruit can heal up to with a higher `max_hp`, shows the healthiest stage.
+/// # Arguments
+/// * `hp` - [Player] `hp`.
+/// * `hidden` - Whether the unicorn is hidden.
+pub fn damage_sprite(hp: i32, hidden: bool) -> usize {
+    ((MAX_PLAYER_HP - hp.clamp(0, MAX_PLAYER_HP)) * 2 + i32::from(hidden)) as usize
+}
+
 ///Labels the main [Entity], the player itself. Collision functions only activate when this entity collides.
 /// # Fields
+/// * `index` - Which player it is, 0 for the first one. Picks his input in [TickInputs].
 /// * `hp` - Remaining hp. Player's sprite changes based on the value of `hp`.
+/// * `last_hit` - Type of the [Enemy] that damaged the player last, [None] if never hit.
+/// * `hits` - How many times the player was hit, healing does not lower it.
 #[derive(Component)]
 pub struct Player {
+    pub index: usize,
     pub hp: i32,
+    pub last_hit: Option<Enemy>,
+    pub hits: u32,
 }
 
 ///[Component] that is inserted everytime [Player] collides, activating the [cam_shake] function
@@ -23,9 +53,9 @@ pub struct ScreenShaker {
 }
 
 ///Inserted together with [Player], containing his hit info, mainly used
-///  for his immunity when player left clicks or gets damaged.
+///  for his immunity when player hides or gets damaged.
 /// # Fields
-/// * `hidden` - A bool indicating whether [Player]'s hitbox is off.
+/// * `hidden` - A bool indicating whether [Player]'s hitbox is off, he then only collides with [crate::walls::Wall]s.
 /// * `hit` - A bool indicating whether [Player] has been hit recently, turning his hitbox involuntarily off.
 /// * `hit_energy` - Counts for how long the player *will* remain `hit`
 /// * `energy` - Counts for how long the player *can* remain `hidden`
@@ -36,6 +66,10 @@ pub struct Hidden {
     pub hit_energy: f32,
     pub energy: f32,
 }
+///Labels [Player] while he is trapped in a [Hole], so [SfxEvent::Capture] is only sent when he gets trapped.
+#[derive(Component)]
+pub struct Captured;
+
 ///Despawn timer for [Star]
 #[derive(Component, Deref, DerefMut)]
 struct StarTimer(Timer);
@@ -46,55 +80,118 @@ pub struct Star;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(MouseMotionPlugin)
-            .add_systems(Update, cam_shake)
+        app.add_systems(Update, cam_shake)
             .add_systems(OnEnter(GameState::MainMenu), spawn_player)
             .add_systems(
-                Update,
-                (movement, hide, gravity_interaction, player_was_hit)
-                    .run_if(in_state(GameState::Game)),
+                FixedUpdate,
+                (
+                    movement,
+                    hide,
+                    gravity_interaction,
+                    detect_capture,
+                    player_was_hit,
+                )
+                    .in_set(GameplaySet::Logic),
             )
             .add_systems(OnExit(GameState::EndScreen), despawn_player)
-            .add_systems(Update, (spawn_stars, despawn_stars, star_movement));
+            .add_systems(
+                Update,
+                (spawn_stars, despawn_stars, star_movement).run_if(in_state(PauseState::Running)),
+            );
     }
 }
-///Affects [Player] by [Gravitating] [entities](Entity) like [crate::planet::Planet] or [crate::blackhole::Hole].
+///Acceleration of [Gravitating] [entities](Entity) at [Gravitating] strength 1 and distance 1px. Chosen so that,
+/// together with [Player]'s damping, the unicorn drifts about as fast as it used to be moved directly.
+const GRAVITY: f32 = 60_000.;
+
+///Returns the acceleration a [Gravitating] [Entity] gives [Player]. It falls off with distance like 1/d, but is softened,
+/// so it grows smoothly to its peak at `softening` px and then drops to zero in the centre instead of blowing up.
+/// Positive `strength` pulls [Player] closer, negative pushes him away.
+/// # Arguments
+/// * `offset` - Position of [Player] relative to the [Gravitating] [Entity].
+/// * `strength` - [Gravitating] strength.
+/// * `softening` - Distance at which the acceleration peaks.
+pub fn gravity_acceleration(offset: Vec2, strength: f32, softening: f32) -> Vec2 {
+    -offset * GRAVITY * strength / (offset.length_squared() + softening * softening)
+}
+
+///Affects [Player] by [Gravitating] [entities](Entity) like [crate::planet::Planet], [crate::blackhole::Hole] or
+/// [crate::whitehole::WhiteHole]. The accelerations are summed, capped and added to [Player]'s [Velocity], so
+/// physics and damping keep the movement smooth.
 /// # Arguments
 /// * `player_query` - [Query] for [Player].
 /// * `gravitating_query` - [Query] for [Gravitating].
-/// * `time` - [Time].
+/// * `time` - [FixedTime].
+/// * `settings` - [Settings], used to access the `gravity_radius`, `gravity_softening` and `max_gravity` fields.
 fn gravity_interaction(
-    mut player_query: Query<(&Hidden, &mut Transform), With<Player>>,
-    mut gravitating_query: Query<
-        (&Gravitating, &mut Transform),
-        (With<Gravitating>, Without<Player>),
-    >,
-    time: Res<Time>,
+    mut player_query: Query<(&Hidden, &Transform, &mut Velocity), With<Player>>,
+    gravitating_query: Query<(&Gravitating, &Transform), (With<Gravitating>, Without<Player>)>,
+    time: Res<FixedTime>,
+    settings: Res<Settings>,
 ) {
-    for (hidden, mut transform_player) in player_query.iter_mut() {
+    let delta = time.period.as_secs_f32();
+    for (hidden, transform_player, mut velocity) in player_query.iter_mut() {
         if !hidden.hidden {
-            let player_x = transform_player.translation.x;
-            let player_y = transform_player.translation.y;
-            for (gravitating, transform_hole) in gravitating_query.iter_mut() {
-                let hole_x = transform_hole.translation.x;
-                let hole_y = transform_hole.translation.y;
-                let distance = point_distance(player_x, player_y, hole_x, hole_y);
-                if distance <= 400. {
-                    let x_gravity = (10_000. / (distance * distance)) * (player_x - hole_x);
-                    let y_gravity = (10_000. / (distance * distance)) * (player_y - hole_y);
-                    if x_gravity.abs() < 1500. && y_gravity.abs() < 1500. {
-                        transform_player.translation.x -=
-                            x_gravity * time.delta_seconds() * gravitating.strength;
-                        transform_player.translation.y -=
-                            y_gravity * time.delta_seconds() * gravitating.strength;
-                    } else {
-                        transform_player.translation = transform_hole.translation;
-                    }
-                }
+            let acceleration: Vec2 = gravitating_query
+                .iter()
+                .map(|(gravitating, transform_hole)| {
+                    (
+                        gravitating,
+                        (transform_player.translation - transform_hole.translation).truncate(),
+                    )
+                })
+                .filter(|(_, offset)| offset.length() <= settings.gravity_radius)
+                .map(|(gravitating, offset)| {
+                    gravity_acceleration(offset, gravitating.strength, settings.gravity_softening)
+                })
+                .sum();
+            velocity.linvel += acceleration.clamp_length_max(settings.max_gravity) * delta;
+        }
+    }
+}
+///Inserts [Captured] into [Player] once he gets closer to a [Hole]'s centre than its radius and removes it once he
+/// leaves or hides. Sends [SfxEvent::Capture] when he gets trapped.
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `player_query` - [Query] for [Player].
+/// * `hole_query` - [Query] for [Hole].
+/// * `sfx` - Sends [SfxEvent]s.
+fn detect_capture(
+    mut commands: Commands,
+    player_query: Query<(Entity, &Hidden, &Transform, Option<&Captured>), With<Player>>,
+    hole_query: Query<&Transform, (With<Hole>, Without<Player>)>,
+    mut sfx: EventWriter<SfxEvent>,
+) {
+    for (entity, hidden, transform_player, captured) in &player_query {
+        let trapped = !hidden.hidden
+            && hole_query.iter().any(|transform_hole| {
+                transform_player
+                    .translation
+                    .truncate()
+                    .distance(transform_hole.translation.truncate())
+                    < BLACKHOLE_SIZE / 2.
+            });
+        match (trapped, captured.is_some()) {
+            (true, false) => {
+                commands.entity(entity).insert(Captured);
+                sfx.send(SfxEvent::Capture);
+            }
+            (false, true) => {
+                commands.entity(entity).remove::<Captured>();
             }
+            _ => {}
         }
     }
 }
+
+///Returns the position of the [Player] nearest to `position` out of `players`, [None] if there are none.
+/// # Arguments
+/// * `position` - Position to measure from.
+/// * `players` - Positions of players, usually only the ones which are not hidden.
+pub fn nearest_player(position: Vec2, players: impl Iterator<Item = Vec2>) -> Option<Vec2> {
+    players.min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)))
+}
+
 ///Returns distance between two points
 /// # Arguments
 /// * `x1` - x coordinate of the first point
@@ -105,89 +202,56 @@ pub fn point_distance(x1: f32, y1: f32, x2: f32, y2: f32) -> f32 {
     ((x2 - x1) * (x2 - x1) + (y2 - y1) * (y2 - y1)).sqrt()
 }
 
-///Moves [Player] based on [MouseMotion].
+///Moves every [Player] based on the movement in his [TickInputs], already scaled by [crate::controls::Controls] `sensitivity`. [crate::walls::Wall]s keep him inside [Playfield](crate::playfield::Playfield).
 /// # Arguments
 /// * `player_query` - [Query] for [Player].    
-/// * `mouse` - [MouseMotion]. Contains easily readable information about mouse movement
-fn movement(
-    mut player_query: Query<(&mut Transform, &mut Velocity), With<Player>>,
-    mouse: Res<MouseMotion>,
-) {
-    for (mut transform, mut velocity) in player_query.iter_mut() {
-        let delta = mouse.delta;
-        let x = transform.translation.x;
-        let y = transform.translation.y;
-        let vert_bound = 700. / 2.;
-        let hori_bound = 1920. / 6.;
-        let is_left = x <= -1. * hori_bound/*  + 192. */;
-        let is_right = x >= hori_bound/*  - 192. */;
-        let is_down = y <= -1. * vert_bound/*  + 108. */;
-        let is_up = y >= vert_bound/*  - 108. */;
-        if (!is_right && !is_left)
-            || (is_left && velocity.linvel.x >= 0.)
-            || (is_right && velocity.linvel.x <= 0.)
-        {
-            velocity.linvel.x += delta.x * 4.;
-        } else {
-            if is_right {
-                transform.translation.x = hori_bound;
-            } else {
-                transform.translation.x = hori_bound * -1.;
-            }
-            velocity.linvel.x = 0.;
-        }
-        if (!is_down && !is_up)
-            || (is_down && velocity.linvel.y >= 0.)
-            || (is_up && velocity.linvel.y <= 0.)
-        {
-            velocity.linvel.y -= delta.y * 4.;
-        } else {
-            if is_up {
-                transform.translation.y = vert_bound;
-            } else {
-                transform.translation.y = vert_bound * -1.;
-            }
-            velocity.linvel.y = 0.;
-        }
+/// * `inputs` - [TickInputs].
+fn movement(mut player_query: Query<(&Player, &mut Velocity)>, inputs: Res<TickInputs>) {
+    for (player, mut velocity) in player_query.iter_mut() {
+        let delta = inputs.0[player.index].delta;
+        velocity.linvel.x += delta.x * 4.;
+        velocity.linvel.y -= delta.y * 4.;
     }
 }
 
 /// Handles sprite and hitbox changes when player is hit
 /// # Arguments
 /// * `commands` - [Commands].
-/// * `time` - [Time].
+/// * `time` - [FixedTime].
 /// * `player_query` - [Query] for [Player].   
 /// * `settings` - [Settings].
-/// * `buttons` - Mouse input, needed so once [Hidden].hit_energy runs out and left mouse button is pressed, player doesnt unhide
-/// for even a frame
+/// * `inputs` - [TickInputs], needed so once [Hidden].hit_energy runs out and hiding is requested, player doesnt unhide
+/// for even a tick
 fn player_was_hit(
     mut commands: Commands,
-    time: Res<Time>,
+    time: Res<FixedTime>,
     mut player_query: Query<(&Player, Entity, &mut TextureAtlasSprite, &mut Hidden), With<Player>>,
     settings: Res<Settings>,
-    buttons: Res<Input<MouseButton>>,
+    inputs: Res<TickInputs>,
 ) {
     for (player, e, mut sprite, mut hidden) in player_query.iter_mut() {
+        let input = &inputs.0[player.index];
         if hidden.hit && player.hp >= 0 {
             if hidden.hit_energy == settings.hit_resistence {
                 hidden.hidden = true;
-                //Using % 4 so the game doesnt crash when i debug with 1000 hp
-                sprite.index = ((3 - (player.hp % 4)) * 2 + 1) as usize;
-                commands.entity(e).remove::<Collider>();
+                sprite.index = damage_sprite(player.hp, true);
+                commands.entity(e).insert(player_groups(true));
             }
 
-            hidden.hit_energy -= 30. * time.delta_seconds();
+            hidden.hit_energy -= 30. * time.period.as_secs_f32();
 
             if hidden.hit_energy <= 0. {
                 hidden.hit = false;
-                if !buttons.pressed(MouseButton::Left) {
+                if !input.hide {
                     hidden.hidden = false;
-                    //Using % 4 so the game doesnt crash when i debug with 1000 hp
-                    sprite.index = ((3 - (player.hp % 4)) * 2) as usize;
-                    commands.entity(e).insert(Collider::compound(vec![
-                        (Vec2::new(0., -14.), 0.15, Collider::capsule_x(18., 25.)),
-                        (Vec2::new(0., -1.), 0., Collider::capsule_y(15., 27.)),
-                    ]));
+                    sprite.index = damage_sprite(player.hp, false);
+                    commands.entity(e).insert((
+                        Collider::compound(vec![
+                            (Vec2::new(0., -14.), 0.15, Collider::capsule_x(18., 25.)),
+                            (Vec2::new(0., -1.), 0., Collider::capsule_y(15., 27.)),
+                        ]),
+                        player_groups(false),
+                    ));
                 }
             };
         }
@@ -196,59 +260,77 @@ fn player_was_hit(
 
 /// Handles sprite and hitbox changes when player is hit
 /// # Arguments
-/// * `buttons` - Mouse input.
+/// * `inputs` - [TickInputs].
 /// * `commands` - [Commands].
-/// * `time` - [Time].
+/// * `time` - [FixedTime].
 /// * `player_query` - [Query] for [Player].   
+/// * `settings` - [Settings], used to access the `energy_drain` and `energy_regen` fields.
+/// * `sfx` - Sends [SfxEvent]s.
 fn hide(
-    buttons: Res<Input<MouseButton>>,
+    inputs: Res<TickInputs>,
     mut commands: Commands,
-    time: Res<Time>,
+    time: Res<FixedTime>,
     mut player_query: Query<(&Player, Entity, &mut TextureAtlasSprite, &mut Hidden), With<Player>>,
+    settings: Res<Settings>,
+    mut sfx: EventWriter<SfxEvent>,
 ) {
+    let delta = time.period.as_secs_f32();
     for (player, e, mut sprite, mut hidden) in player_query.iter_mut() {
+        let input = &inputs.0[player.index];
         let has_energy = hidden.energy > 0.;
         if !hidden.hit {
-            if buttons.just_pressed(MouseButton::Left) && has_energy {
+            if input.hide_just_pressed() && has_energy {
                 hidden.hidden = true;
-                sprite.index = ((3 - (player.hp % 4)) * 2 + 1) as usize;
-                commands.entity(e).remove::<Collider>();
+                sfx.send(SfxEvent::Hide);
+                sprite.index = damage_sprite(player.hp, true);
+                commands.entity(e).insert(player_groups(true));
             }
 
-            if buttons.pressed(MouseButton::Left) && has_energy {
-                hidden.energy -= 35. * time.delta_seconds();
+            if input.hide && has_energy {
+                hidden.energy -= settings.energy_drain * delta;
             } else if hidden.energy < 100. {
-                hidden.energy += 4. * time.delta_seconds();
+                hidden.energy += settings.energy_regen * delta;
             }
 
-            if buttons.just_released(MouseButton::Left) || !has_energy {
+            if input.hide_just_released() || !has_energy {
+                if hidden.hidden {
+                    sfx.send(SfxEvent::Unhide);
+                }
                 hidden.hidden = false;
-                sprite.index = ((3 - (player.hp % 4)) * 2) as usize;
-                commands.entity(e).insert(Collider::compound(vec![
-                    (Vec2::new(0., -14.), 0.15, Collider::capsule_x(18., 25.)),
-                    (Vec2::new(0., -1.), 0., Collider::capsule_y(15., 27.)),
-                ]));
+                sprite.index = damage_sprite(player.hp, false);
+                commands.entity(e).insert((
+                    Collider::compound(vec![
+                        (Vec2::new(0., -14.), 0.15, Collider::capsule_x(18., 25.)),
+                        (Vec2::new(0., -1.), 0., Collider::capsule_y(15., 27.)),
+                    ]),
+                    player_groups(false),
+                ));
             }
         }
     }
 }
 
-///Shakes camera for each [ScreenShaker].
+///Shakes camera for each [ScreenShaker], scaled by [Preferences] `shake`.
 /// # Arguments
 /// * `commands` - [Commands].
 /// * `time` - [Time].
 /// * `camera` - [Query] for [Camera].
 /// * `screen_shakers` - [Query] for [ScreenShaker].
+/// * `preferences` - [Preferences].
 fn cam_shake(
     mut commands: Commands,
     time: Res<Time>,
     mut camera: Query<&mut Transform, (With<Camera>, Without<Player>)>,
     mut screen_shakers: Query<(&mut ScreenShaker, Entity), With<ScreenShaker>>,
+    preferences: Res<Preferences>,
 ) {
+    let amplitude = 6. * preferences.shake;
+    let Ok(mut camera) = camera.get_single_mut() else {
+        return;
+    };
     if time.elapsed_seconds() % 0.10 < time.delta_seconds() {
-        let mut camera = camera.single_mut();
         for (mut shaker, entity) in &mut screen_shakers {
-            camera.translation.x += 6. * if shaker.shakes % 2 == 0 { -1. } else { 1. };
+            camera.translation.x += amplitude * if shaker.shakes % 2 == 0 { -1. } else { 1. };
             if shaker.shakes > 0 {
                 shaker.shakes -= 1;
             } else {
@@ -258,13 +340,32 @@ fn cam_shake(
     }
 }
 
-///Spawns [Player].
+///Spawns the first [Player].
 /// # Arguments
 /// * `commands` - [Commands].
-/// * `uni` - [Resource] containing unicorn [TextureAtlas].
-fn spawn_player(mut commands: Commands, uni: Res<UnicornSheet>) {
-    let mut sprite = TextureAtlasSprite::new(0);
+/// * `atlases` - [Atlases], containing unicorn [TextureAtlas].
+/// * `settings` - [Settings], used to access the `player_hp` field.
+fn spawn_player(mut commands: Commands, atlases: Res<Atlases>, settings: Res<Settings>) {
+    create_player(0, &mut commands, &atlases, &settings);
+}
+
+///Spawns a [Player]. Every other player than the first one starts a bit to the right and is tinted, so they can be told apart.
+/// # Arguments
+/// * `index` - [Player] `index`.
+/// * `commands` - [Commands].
+/// * `atlases` - [Atlases], containing unicorn [TextureAtlas].
+/// * `settings` - [Settings], used to access the `player_hp` field.
+pub fn create_player(
+    index: usize,
+    commands: &mut Commands,
+    atlases: &Atlases,
+    settings: &Settings,
+) {
+    let mut sprite = TextureAtlasSprite::new(damage_sprite(settings.player_hp, false));
     sprite.custom_size = Some(Vec2::new(100., 120.));
+    if index > 0 {
+        sprite.color = Color::rgb(0.7, 0.85, 1.);
+    }
 
     let physics = (
         LockedAxes::ROTATION_LOCKED,
@@ -281,15 +382,20 @@ fn spawn_player(mut commands: Commands, uni: Res<UnicornSheet>) {
     let player = commands
         .spawn(SpriteSheetBundle {
             sprite,
-            texture_atlas: uni.0.clone(),
+            texture_atlas: atlases.get("unicorn"),
             transform: Transform {
-                translation: Vec3::new(0.0, -250.0, 900.0),
+                translation: Vec3::new(150. * index as f32, -250.0, 900.0),
                 scale: Vec3::splat(1.0),
                 ..Default::default()
             },
             ..Default::default()
         })
-        .insert(Player { hp: 3 })
+        .insert(Player {
+            index,
+            hp: settings.player_hp,
+            last_hit: None,
+            hits: 0,
+        })
         .insert(Hidden {
             hidden: false,
             hit: false,
@@ -306,6 +412,7 @@ fn spawn_player(mut commands: Commands, uni: Res<UnicornSheet>) {
         .insert(physics.1)
         .insert(physics.2)
         .insert(Ccd::enabled())
+        .insert(player_groups(false))
         .id();
     commands.entity(player);
 }
@@ -323,30 +430,34 @@ pub fn despawn_player(mut commands: Commands, player_query: Query<Entity, With<P
 ///Spawns [Star] on [Player] with different colors and slightly different x, y and sizes.
 /// # Arguments
 /// * `commands` - [Commands].
-/// * `star` - [Resource] containing handle for star [TextureAtlas].
+/// * `atlases` - [Atlases], containing star [TextureAtlas].
 /// * `time` - [Time].
 /// * `player_query` - [Query] for [Player].
+/// * `rng` - [GameRng], stars use its cosmetic stream.
+/// * `preferences` - [Preferences], stars are colored by its `palette`.
 fn spawn_stars(
     mut commands: Commands,
-    star: Res<StarsSheet>,
+    atlases: Res<Atlases>,
     time: Res<Time>,
     player_query: Query<(&Transform, &Hidden), With<Player>>,
+    mut rng: ResMut<GameRng>,
+    preferences: Res<Preferences>,
 ) {
-    let spawn_chance: f32 = rand::random();
+    let rng = &mut rng.cosmetic;
+    let spawn_chance: f32 = rng.gen();
     if time.elapsed_seconds() % 0.2 < time.delta_seconds() && spawn_chance < 0.9 {
         for (player_trans, hidden) in player_query.iter() {
-            let scale_num: f32 = rand::random::<f32>() / 2.;
-            let x_num: f32 = rand::random();
-            let y_num: f32 = rand::random();
-            let color_num: f32 = rand::random();
+            let scale_num: f32 = rng.gen::<f32>() / 2.;
+            let x_num: f32 = rng.gen();
+            let y_num: f32 = rng.gen();
+            let color_num: f32 = rng.gen();
             let mut sprite = TextureAtlasSprite::new(0);
-            sprite.color =
-                bevy::render::color::Color::hsl(color_num * 360. /* 60. */, 0.97, 0.79);
+            sprite.color = preferences.palette.star_color(color_num);
             let star_y = player_trans.translation.y + if hidden.hidden { 0. } else { -40. };
             let player = commands
                 .spawn(SpriteSheetBundle {
                     sprite,
-                    texture_atlas: star.0.clone(),
+                    texture_atlas: atlases.get("stars"),
                     transform: Transform {
                         translation: Vec3::new(
                             player_trans.translation.x - 40. + x_num * 80.,
@@ -0,0 +1,162 @@
+use bevy::prelude::*;
+use bevy::render::camera::{ScalingMode, Viewport};
+use bevy::window::{PrimaryWindow, WindowResized};
+use rand::{rngs::StdRng, Rng};
+
+///Color of the bars around [Playfield] when the window has a different aspect ratio.
+pub const LETTERBOX: Color = Color::rgb(0.12, 0.08, 0.15);
+
+///[Plugin] fitting [Playfield] into the window. The camera always shows exactly the whole [Playfield], scaled up
+/// or down with the window and letterboxed by [LETTERBOX] bars. UI is scaled by the same factor.
+pub struct PlayfieldPlugin;
+
+///Size of the area the game is played in, in world units. Everything gameplay related is placed relative to it,
+/// no matter how big the window is. The default matches the original window.
+/// # Fields
+/// * `width` - Width of the playfield.
+/// * `height` - Height of the playfield.
+#[derive(Resource, Clone, Copy, PartialEq, Debug)]
+pub struct Playfield {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for Playfield {
+    fn default() -> Self {
+        Playfield {
+            width: 1920. / 3.,
+            height: 700.,
+        }
+    }
+}
+
+impl Playfield {
+    ///Distance from the center to the left or right edge.
+    pub fn half_width(&self) -> f32 {
+        self.width / 2.
+    }
+
+    ///Distance from the center to the top or bottom edge.
+    pub fn half_height(&self) -> f32 {
+        self.height / 2.
+    }
+
+    ///Height objects spawn at if their structure does not say otherwise, a bit above the top edge.
+    pub fn spawn_y(&self) -> f32 {
+        self.half_height() + 250.
+    }
+
+    ///Height under which fallen objects are despawned, a bit below the bottom edge.
+    pub fn despawn_y(&self) -> f32 {
+        -self.half_height() - 200.
+    }
+
+    ///Random x coordinate within the playfield.
+    /// # Arguments
+    /// * `rng` - Random number generator, usually a stream of [crate::rng::GameRng].
+    pub fn random_x(&self, rng: &mut StdRng) -> f32 {
+        (rng.gen::<f32>() - 0.5) * self.width
+    }
+}
+
+///Returns the largest rectangle with the aspect ratio of `playfield` which fits into `window`, centered in it.
+/// # Arguments
+/// * `window` - Size of the window in physical pixels.
+/// * `playfield` - [Playfield].
+pub fn letterbox(window: UVec2, playfield: &Playfield) -> (UVec2, UVec2) {
+    let window_size = window.as_vec2();
+    let scale = (window_size.x / playfield.width).min(window_size.y / playfield.height);
+    let size = (Vec2::new(playfield.width, playfield.height) * scale)
+        .round()
+        .as_uvec2()
+        .max(UVec2::ONE)
+        .min(window.max(UVec2::ONE));
+    let position = (window.max(size) - size) / 2;
+    (position, size)
+}
+
+///Labels the sprite drawing the background of [Playfield], the rest of the window is [LETTERBOX].
+#[derive(Component)]
+struct Background;
+
+///Labels a UI node covering exactly the part of the window showing [Playfield], like a menu or the HUD. Its size is
+/// set by [fit_panels], centering it is up to its [Style], usually `margin: UiRect::all(Val::Auto)`.
+#[derive(Component)]
+pub struct PlayfieldPanel;
+
+impl Plugin for PlayfieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ClearColor(LETTERBOX))
+            .add_systems(Startup, spawn_background)
+            .add_systems(Update, (fit_to_window, fit_panels));
+    }
+}
+
+///Spawns [Background].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `playfield` - [Playfield].
+fn spawn_background(mut commands: Commands, playfield: Res<Playfield>) {
+    commands
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                color: crate::CLEAR,
+                custom_size: Some(Vec2::new(playfield.width, playfield.height)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0., 0., 1.),
+            ..default()
+        })
+        .insert(Background);
+}
+
+///Letterboxes the camera and scales UI whenever the window is resized or [Playfield] changes.
+/// # Arguments
+/// * `resized` - Reads [WindowResized] events.
+/// * `window_q` - [Query] for [PrimaryWindow].
+/// * `camera_q` - [Query] for [Camera] and its [OrthographicProjection].
+/// * `playfield` - [Playfield].
+/// * `ui_scale` - [UiScale].
+fn fit_to_window(
+    mut resized: EventReader<WindowResized>,
+    window_q: Query<&Window, With<PrimaryWindow>>,
+    mut camera_q: Query<(&mut Camera, &mut OrthographicProjection)>,
+    playfield: Res<Playfield>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let resized = resized.iter().count() > 0;
+    let Ok(window) = window_q.get_single() else {
+        return;
+    };
+    for (mut camera, mut projection) in &mut camera_q {
+        if !resized && !playfield.is_changed() && camera.viewport.is_some() {
+            continue;
+        }
+        let window_size = UVec2::new(window.physical_width(), window.physical_height());
+        let (position, size) = letterbox(window_size, &playfield);
+        camera.viewport = Some(Viewport {
+            physical_position: position,
+            physical_size: size,
+            ..default()
+        });
+        projection.scaling_mode = ScalingMode::Fixed {
+            width: playfield.width,
+            height: playfield.height,
+        };
+        ui_scale.scale = size.y as f64 / window.scale_factor() / playfield.height as f64;
+    }
+}
+
+///Sizes every new [PlayfieldPanel] to [Playfield], all of them once [Playfield] changes. [UiScale] maps [Playfield]
+/// onto the letterboxed viewport, so one UI pixel is one world unit, see [fit_to_window].
+/// # Arguments
+/// * `playfield` - [Playfield].
+/// * `panel_q` - [Query] for [PlayfieldPanel]s.
+fn fit_panels(playfield: Res<Playfield>, mut panel_q: Query<(Ref<PlayfieldPanel>, &mut Style)>) {
+    for (panel, mut style) in &mut panel_q {
+        if playfield.is_changed() || panel.is_added() {
+            style.width = Val::Px(playfield.width);
+            style.height = Val::Px(playfield.height);
+        }
+    }
+}
@@ -0,0 +1,374 @@
+use crate::tutorial_screen::TUTORIAL;
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowMode};
+use bevy_pkv::PkvStore;
+use serde::{Deserialize, Serialize};
+
+///[PkvStore] key [Preferences] are stored under.
+pub const PREFERENCES_KEY: &str = "preferences";
+
+///[Plugin] loading [Preferences] and applying the window mode.
+pub struct PreferencesPlugin;
+
+///Language of menu texts.
+/// * `English` - The original texts.
+/// * `Czech` - Texts from [CZECH], without diacritics as the font does not have them.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Language {
+    #[default]
+    English,
+    Czech,
+}
+
+///Czech translations of English menu texts. Texts missing here stay English.
+const CZECH: [(&str, &str); 98] = [
+    ("Left click to start", "Klikni a hraj"),
+    ("How to play", "Jak hrat"),
+    ("Options", "Nastaveni"),
+    ("Watch best run", "Nejlepsi hra"),
+    ("Leaderboard", "Zebricek"),
+    ("Controls", "Ovladani"),
+    ("Players", "Hraci"),
+    ("LAN race", "Zavod po LAN"),
+    ("Music by Vojtech Klhufek", "Hudba: Vojtech Klhufek"),
+    ("Return to menu", "Zpet do menu"),
+    ("Paused", "Pauza"),
+    ("Resume", "Pokracovat"),
+    ("Quit", "Ukoncit"),
+    ("Sound: on", "Zvuk: zap"),
+    ("Sound: off", "Zvuk: vyp"),
+    ("Audio", "Zvuk"),
+    ("Master", "Celkova"),
+    ("Music", "Hudba"),
+    ("Effects", "Efekty"),
+    ("Input", "Vstup"),
+    ("Play with", "Hrat s"),
+    ("Mouse", "Mys"),
+    ("Keyboard", "Klavesnice"),
+    ("Gamepad", "Gamepad"),
+    ("Sensitivity", "Citlivost"),
+    ("Display", "Obraz"),
+    ("Screen shake", "Otresy"),
+    ("On", "Zap"),
+    ("Off", "Vyp"),
+    ("Window", "Okno"),
+    ("Windowed", "V okne"),
+    ("Fullscreen", "Cela obrazovka"),
+    ("Language", "Jazyk"),
+    (
+        "Click or drag a bar to change it. F10 mutes the game anywhere.",
+        "Klikni na pruh nebo ho tahni. F10 kdekoli ztlumi hru.",
+    ),
+    (
+        "Sensitivity scales how far the unicorn moves, for every device.",
+        "Citlivost meni, jak daleko se jednorozec pohne, u vsech zarizeni.",
+    ),
+    (
+        "The second player's device is picked in Controls.",
+        "Zarizeni druheho hrace se vybira v Ovladani.",
+    ),
+    (
+        "F11 switches the window mode too.",
+        "F11 take prepina rezim okna.",
+    ),
+    ("Accessibility", "Pristupnost"),
+    ("Menu text", "Text v menu"),
+    ("Flashing", "Blika"),
+    ("Steady", "Staly"),
+    ("Hazard outlines", "Obrysy nebezpeci"),
+    ("Colors", "Barvy"),
+    ("Default", "Vychozi"),
+    ("Colorblind", "Pro barvoslepe"),
+    ("HUD text", "Text ve hre"),
+    ("Normal", "Normalni"),
+    ("Large", "Velky"),
+    (
+        "Colorblind colors change stars and energy bars.",
+        "Barvy pro barvoslepe meni hvezdy a energeticke tycinky.",
+    ),
+    ("Player", "Hrac"),
+    ("Player {} wins!", "Vyhral hrac {}!"),
+    ("It's a draw!", "Remiza!"),
+    ("Score:", "Skore:"),
+    ("Highscore:", "Rekord:"),
+    ("Opponent:", "Souper:"),
+    ("Opponent left", "Souper odesel"),
+    ("Opponent never showed up", "Souper se neukazal"),
+    ("Opponent is still flying", "Souper jeste leti"),
+    ("You win!", "Vyhral jsi!"),
+    ("Opponent wins!", "Vyhral souper!"),
+    ("Left click to continue", "Klikni a pokracuj"),
+    ("New highscore! Type your name:", "Novy rekord! Napis sve jmeno:"),
+    ("Host a game or join one", "Zaloz hru nebo se pripoj"),
+    ("Host", "Zalozit"),
+    ("Join", "Pripojit"),
+    ("Host address:", "Adresa hostitele:"),
+    (
+        "Both games need the same settings.ron and enemies.ron.\nThe run starts as soon as the other game connects.",
+        "Obe hry potrebuji stejny settings.ron a enemies.ron.\nHra zacne, jakmile se druha hra pripoji.",
+    ),
+    ("Waiting for a player, join", "Cekam na hrace, pripoj se na"),
+    ("Waiting for a player on port", "Cekam na hrace na portu"),
+    ("Connecting to", "Pripojuji se k"),
+    ("Can't host:", "Nelze zalozit hru:"),
+    ("Can't join:", "Nelze se pripojit:"),
+    ("Type the address of the host", "Napis adresu hostitele"),
+    ("This is not an address", "Tohle neni adresa"),
+    (
+        "Your settings differ from the host's",
+        "Tvoje nastaveni se lisi od hostitele",
+    ),
+    (
+        "Your enemies differ from the host's",
+        "Tvoji nepratele se lisi od hostitele",
+    ),
+    ("Name", "Jmeno"),
+    ("Score", "Skore"),
+    ("Date", "Datum"),
+    ("Time", "Cas"),
+    ("Speed", "Rychl."),
+    ("Hits", "Zasahy"),
+    ("No runs yet", "Zatim zadne hry"),
+    ("seed", "semeno"),
+    ("Press...", "Stiskni..."),
+    ("Up", "Nahoru"),
+    ("Down", "Dolu"),
+    ("Left", "Doleva"),
+    ("Right", "Doprava"),
+    ("Hide", "Schovat"),
+    (
+        "Click a binding and press a key or button.\nEscape cancels, Delete clears.\n\
+        If both players use the keyboard, player 1 uses the left keys and player 2 the right ones.",
+        "Klikni na prirazeni a stiskni klavesu nebo tlacitko.\nEscape rusi, Delete maze.\n\
+        Pokud oba hraci hraji na klavesnici, hrac 1 pouziva leve klavesy a hrac 2 prave.",
+    ),
+    ("Loading...", "Nacitani..."),
+    (
+        "Some files could not be loaded",
+        "Nektere soubory se nepodarilo nacist",
+    ),
+    (
+        "Check that the assets folder is next to the game.\nThe game can go on, but things may be invisible or silent.",
+        "Zkontroluj, ze je slozka assets vedle hry.\nHra muze pokracovat, ale neco muze byt neviditelne nebo tiche.",
+    ),
+    ("Continue anyway", "Presto pokracovat"),
+    ("ENERGY", "ENERGIE"),
+    ("OUT", "VYRAZEN"),
+    (
+        TUTORIAL,
+        "\n
+                Jednorozce ovladas mysi, klavesnici
+                nebo gamepadem, viz Ovladani v menu.
+
+                Je tu spousta nepratel, kteri ti mohou
+                zneprijemnit zivot...
+                    Letadla (zrani jednorozce)
+                    Bonbony (zrani jednorozce)
+                    Duhy (zrani jednorozce)
+                    Planety (pritahuji jednorozce)
+                    Cerne diry (vtahnou jednorozce)
+                    Bile diry (odpuzuji jednorozce)
+                    Vily (letaji v hejnech, zrani jednorozce)
+
+                Levym tlacitkem mysi (nebo klavesou schovani)
+                jednorozce schovas,
+                takze na nej nic nepusobi.
+                Schovavani stoji energii, ukazanou vlevo dole.
+                Energie se pomalu obnovuje.
+                Proteinove tycinky doplni energii.
+                Ovoce jednorozce vyleci.
+
+                Cim dal doletis, tim vetsi skore.
+                Konec neexistuje.
+                Escape nebo prave tlacitko hru pozastavi.
+
+                Nastav v menu Hrace na 2 a hraj
+                s kamaradem, vyhraje posledni letici jednorozec.
+                Nebo zavodte s nekym na jinem pocitaci, viz LAN.",
+    ),
+];
+
+impl Language {
+    ///Every [Language], in the order they are offered.
+    pub const ALL: [Language; 2] = [Language::English, Language::Czech];
+
+    ///Name of the language in itself.
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Czech => "Cestina",
+        }
+    }
+
+    ///Translates an English menu text. Texts without a translation are returned unchanged.
+    /// # Arguments
+    /// * `text` - English text.
+    pub fn tr(self, text: &'static str) -> &'static str {
+        match self {
+            Language::English => text,
+            Language::Czech => CZECH
+                .iter()
+                .find(|(english, _)| *english == text)
+                .map_or(text, |(_, czech)| czech),
+        }
+    }
+}
+
+///Colors of cosmetic [entities](Entity) which would otherwise only differ in hue.
+/// * `Default` - The original colors, stars in every hue and energy bars untinted.
+/// * `Colorblind` - Colors from the Okabe-Ito palette, which stay distinguishable with the common kinds of
+///   color blindness. Energy bars are tinted blue, so they do not blend in with brown obstacles.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Palette {
+    #[default]
+    Default,
+    Colorblind,
+}
+
+///Colors stars are picked from in [Palette::Colorblind].
+const COLORBLIND_STARS: [Color; 5] = [
+    Color::rgb(0.9, 0.62, 0.),    //Orange
+    Color::rgb(0.34, 0.71, 0.91), //Sky blue
+    Color::rgb(0.94, 0.89, 0.26), //Yellow
+    Color::rgb(0., 0.45, 0.7),    //Blue
+    Color::rgb(0.8, 0.47, 0.65),  //Reddish purple
+];
+
+impl Palette {
+    ///Every [Palette], in the order they are offered.
+    pub const ALL: [Palette; 2] = [Palette::Default, Palette::Colorblind];
+
+    ///Name shown in the options screen.
+    pub fn name(self) -> &'static str {
+        match self {
+            Palette::Default => "Default",
+            Palette::Colorblind => "Colorblind",
+        }
+    }
+
+    ///Color of a [crate::player::Star].
+    /// # Arguments
+    /// * `t` - Random number from 0 to 1 picking the color.
+    pub fn star_color(self, t: f32) -> Color {
+        match self {
+            Palette::Default => Color::hsl(t * 360., 0.97, 0.79),
+            Palette::Colorblind => {
+                let index = (t * COLORBLIND_STARS.len() as f32) as usize;
+                COLORBLIND_STARS[index.min(COLORBLIND_STARS.len() - 1)]
+            }
+        }
+    }
+
+    ///Tint of [crate::energybars::EnergyBar]s.
+    pub fn energy_tint(self) -> Color {
+        match self {
+            Palette::Default => Color::WHITE,
+            Palette::Colorblind => Color::rgb(0.55, 0.8, 1.),
+        }
+    }
+}
+
+///Player's preferences which do not affect the game itself. Stored in [PkvStore] under [PREFERENCES_KEY], changed
+/// in the options screen.
+/// # Fields
+/// * `shake` - How much the camera shakes when [crate::player::Player] is hit, from 0 (not at all) to 1.
+/// * `fullscreen` - Whether the window is borderless fullscreen. Also switched by F11.
+/// * `language` - [Language] of menu texts.
+/// * `steady_text` - Whether [crate::mainmenu::Flickering] texts are highlighted instead of blinking.
+/// * `outlines` - Whether [crate::collisions::Damaging] [entities](Entity) get a high-contrast outline,
+///   see [crate::accessibility].
+/// * `palette` - [Palette] of stars and energy bars.
+/// * `large_hud` - Whether score and energy in the game are shown in a larger font.
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Preferences {
+    pub shake: f32,
+    pub fullscreen: bool,
+    pub language: Language,
+    pub steady_text: bool,
+    pub outlines: bool,
+    pub palette: Palette,
+    pub large_hud: bool,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Preferences {
+            shake: 1.,
+            fullscreen: false,
+            language: Language::English,
+            steady_text: false,
+            outlines: false,
+            palette: Palette::Default,
+            large_hud: false,
+        }
+    }
+}
+
+impl Preferences {
+    ///Loads [Preferences] from [PkvStore], the default ones if none are stored yet.
+    pub fn load(pkv: &PkvStore) -> Preferences {
+        pkv.get::<Preferences>(PREFERENCES_KEY).unwrap_or_default()
+    }
+
+    ///Stores [Preferences] in [PkvStore].
+    pub fn save(&self, pkv: &mut PkvStore) {
+        if let Err(err) = pkv.set(PREFERENCES_KEY, self) {
+            warn!("Failed to store preferences: {}", err);
+        }
+    }
+}
+
+impl Plugin for PreferencesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreStartup, load_preferences)
+            .add_systems(Update, (toggle_fullscreen, apply_window_mode).chain());
+    }
+}
+
+///Loads [Preferences] at startup.
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `pkv` - [PkvStore].
+fn load_preferences(mut commands: Commands, pkv: Res<PkvStore>) {
+    commands.insert_resource(Preferences::load(&pkv));
+}
+
+///Switches [Preferences] `fullscreen` on F11 and stores it.
+/// # Arguments
+/// * `keys` - Keyboard input.
+/// * `preferences` - [Preferences].
+/// * `pkv` - [PkvStore].
+fn toggle_fullscreen(
+    keys: Res<Input<KeyCode>>,
+    mut preferences: ResMut<Preferences>,
+    mut pkv: ResMut<PkvStore>,
+) {
+    if keys.just_pressed(KeyCode::F11) {
+        preferences.fullscreen = !preferences.fullscreen;
+        preferences.save(&mut pkv);
+    }
+}
+
+///Switches the window between windowed and borderless fullscreen mode once [Preferences] change.
+/// # Arguments
+/// * `preferences` - [Preferences].
+/// * `window_q` - [Query] for [PrimaryWindow].
+fn apply_window_mode(
+    preferences: Res<Preferences>,
+    mut window_q: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !preferences.is_changed() {
+        return;
+    }
+    let mode = if preferences.fullscreen {
+        WindowMode::BorderlessFullscreen
+    } else {
+        WindowMode::Windowed
+    };
+    for mut window in &mut window_q {
+        if window.mode != mode {
+            window.mode = mode;
+        }
+    }
+}
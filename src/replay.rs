@@ -0,0 +1,389 @@
+use crate::{
+    player::Player,
+    rng::{reseed, GameRng},
+    score::Score,
+    speed::Speed,
+    versus::{GameMode, MAX_PLAYERS},
+    GameState, GameplaySet, Settings,
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs::{create_dir_all, read_to_string, write};
+
+///Folder replays are saved into.
+pub const REPLAY_DIR: &str = "./replays";
+///Replay of the last finished run.
+pub const LAST_REPLAY: &str = "./replays/last.ron";
+///Replay of the run with the highest score.
+pub const BEST_REPLAY: &str = "./replays/best.ron";
+
+///[Plugin] taking care of sampling input for every gameplay tick, recording it and playing it back.
+pub struct ReplayPlugin;
+
+///Whether finished runs are saved into [REPLAY_DIR]. Turned off in [crate::headless] mode, so scripted runs do not replace the player's.
+#[derive(Resource)]
+pub struct SaveReplays(pub bool);
+
+///Input of one player collected since the last gameplay tick, filled by the [crate::controls::InputDevice] he uses.
+/// There may be several frames between two ticks or several ticks in one frame.
+/// # Fields
+/// * `delta` - Summed movement, in mouse movement units.
+/// * `held` - Whether the hide button is currently held.
+/// * `clicked` - Whether the hide button was pressed at any point since the last tick, so short clicks are not lost.
+/// * `ignore_held` - Whether the hide button is ignored until it is released, see [PendingInput::discard].
+#[derive(Default)]
+pub struct PendingInput {
+    delta: Vec2,
+    held: bool,
+    clicked: bool,
+    ignore_held: bool,
+}
+
+impl PendingInput {
+    ///Adds movement, in mouse movement units.
+    pub fn add_motion(&mut self, delta: Vec2) {
+        self.delta += delta;
+    }
+
+    ///Updates the state of the hide button.
+    /// # Arguments
+    /// * `held` - Whether it is held.
+    /// * `just_pressed` - Whether it was pressed this frame.
+    pub fn press(&mut self, held: bool, just_pressed: bool) {
+        if self.ignore_held && !held {
+            self.ignore_held = false;
+        }
+        if !self.ignore_held {
+            self.held = held;
+            self.clicked |= just_pressed;
+        }
+    }
+
+    ///Throws away collected input. If the hide button is currently held, for example because the left mouse button
+    /// clicked a menu button, it is ignored until released so it does not count as hiding.
+    pub fn discard(&mut self) {
+        self.delta = Vec2::ZERO;
+        self.clicked = false;
+        self.ignore_held = self.held;
+        self.held = false;
+    }
+}
+
+///[PendingInput] of every player, indexed by [Player] `index`.
+#[derive(Resource, Default)]
+pub struct PendingInputs(pub [PendingInput; MAX_PLAYERS]);
+
+impl PendingInputs {
+    ///Throws away input collected for every player, see [PendingInput::discard].
+    pub fn discard(&mut self) {
+        for pending in self.0.iter_mut() {
+            pending.discard();
+        }
+    }
+}
+
+///Input consumed by a single [Player] during the current gameplay tick.
+/// # Fields
+/// * `delta` - Movement, in mouse movement units.
+/// * `hide` - Whether hiding is requested (the hide button is held).
+/// * `was_hiding` - Value of `hide` in the previous tick.
+#[derive(Default, Clone, Copy)]
+pub struct TickInput {
+    pub delta: Vec2,
+    pub hide: bool,
+    pub was_hiding: bool,
+}
+
+impl TickInput {
+    ///Whether hiding was requested this tick but not the previous one.
+    pub fn hide_just_pressed(&self) -> bool {
+        self.hide && !self.was_hiding
+    }
+
+    ///Whether hiding was requested the previous tick but not this one.
+    pub fn hide_just_released(&self) -> bool {
+        !self.hide && self.was_hiding
+    }
+}
+
+///[TickInput] of every player, indexed by [Player] `index`.
+#[derive(Resource, Default)]
+pub struct TickInputs(pub [TickInput; MAX_PLAYERS]);
+
+///Input of a single gameplay tick as it is saved in a [Replay]. Only runs of a single player are saved.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct RecordedInput {
+    pub dx: f32,
+    pub dy: f32,
+    pub hide: bool,
+}
+
+///Everything needed to play a run again.
+/// # Fields
+/// * `seed` - Seed of [GameRng].
+/// * `settings` - [Settings] the run was played with.
+/// * `score` - Final score, used to pick the best replay.
+/// * `held_at_start` - Whether the hide button was still held from the main menu click once the run started.
+/// * `inputs` - [RecordedInput] for every tick of the run.
+#[derive(Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub settings: Settings,
+    pub score: f32,
+    pub held_at_start: bool,
+    pub inputs: Vec<RecordedInput>,
+}
+
+impl Replay {
+    ///Loads a [Replay] from a RON file.
+    pub fn load(path: &str) -> Result<Replay, String> {
+        let content = read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+        ron::from_str(&content).map_err(|err| format!("{}: {}", path, err))
+    }
+
+    ///Saves the [Replay] into a RON file, creating [REPLAY_DIR] if needed.
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        create_dir_all(REPLAY_DIR).map_err(|err| format!("{}: {}", REPLAY_DIR, err))?;
+        let content = ron::to_string(self).map_err(|err| format!("{}: {}", path, err))?;
+        write(path, content).map_err(|err| format!("{}: {}", path, err))
+    }
+}
+
+///Inputs of the current run, filled unless a [Playback] is being watched.
+/// # Fields
+/// * `held_at_start` - See [Replay].
+/// * `inputs` - [RecordedInput] for every tick played so far.
+#[derive(Resource, Default)]
+pub struct Recording {
+    held_at_start: bool,
+    inputs: Vec<RecordedInput>,
+}
+
+///[Replay] being watched. While this [Resource] exists, the first player's [TickInput] is read from it instead of
+/// his input.
+/// # Fields
+/// * `replay` - The watched [Replay].
+/// * `tick` - Index of the next [RecordedInput].
+/// * `live_settings` - [Settings] to restore once the replay ends.
+#[derive(Resource)]
+pub struct Playback {
+    replay: Replay,
+    tick: usize,
+    live_settings: Option<Settings>,
+}
+
+impl Playback {
+    ///Creates a [Playback] starting at the first tick of `replay`.
+    pub fn new(replay: Replay) -> Playback {
+        Playback {
+            replay,
+            tick: 0,
+            live_settings: None,
+        }
+    }
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingInputs>()
+            .init_resource::<TickInputs>()
+            .init_resource::<Recording>()
+            .insert_resource(SaveReplays(true))
+            .add_systems(
+                OnEnter(GameState::Game),
+                (
+                    start_recording.run_if(not(resource_exists::<Playback>())),
+                    start_playback
+                        .after(reseed)
+                        .run_if(resource_exists::<Playback>()),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    sample_live_input.run_if(not(resource_exists::<Playback>())),
+                    sample_replay_input.run_if(resource_exists::<Playback>()),
+                )
+                    .in_set(GameplaySet::Input),
+            )
+            .add_systems(
+                OnEnter(GameState::EndScreen),
+                save_replay
+                    .run_if(not(resource_exists::<Playback>()))
+                    .run_if(|mode: Res<GameMode>| *mode == GameMode::Single)
+                    .run_if(|save: Res<SaveReplays>| save.0),
+            )
+            .add_systems(
+                OnExit(GameState::EndScreen),
+                stop_playback.run_if(resource_exists::<Playback>()),
+            );
+    }
+}
+
+///Clears input collected in the menus and starts a new [Recording] on enter to [GameState::Game].
+/// # Arguments
+/// * `pending` - [PendingInputs].
+/// * `inputs` - [TickInputs].
+/// * `recording` - [Recording].
+fn start_recording(
+    mut pending: ResMut<PendingInputs>,
+    mut inputs: ResMut<TickInputs>,
+    mut recording: ResMut<Recording>,
+) {
+    for (pending, input) in pending.0.iter_mut().zip(inputs.0.iter_mut()) {
+        pending.delta = Vec2::ZERO;
+        pending.clicked = false;
+        //The click that started the game may still be held, it should not count as a new press
+        *input = TickInput {
+            delta: Vec2::ZERO,
+            hide: pending.held,
+            was_hiding: pending.held,
+        };
+    }
+    *recording = Recording {
+        held_at_start: pending.0[0].held,
+        inputs: Vec::new(),
+    };
+}
+
+///Sets up everything the watched [Replay] needs on enter to [GameState::Game]. Runs after [reseed].
+/// # Arguments
+/// * `playback` - [Playback].
+/// * `inputs` - [TickInputs].
+/// * `settings` - [Settings], replaced by the ones of the replay until it ends.
+/// * `rng` - [GameRng], reseeded with the seed of the replay.
+/// * `speed` - [Speed].
+/// * `score` - [Score].
+/// * `player_q` - [Query] for [Player], spawned with the player's [Settings] before the replay started.
+fn start_playback(
+    mut playback: ResMut<Playback>,
+    mut inputs: ResMut<TickInputs>,
+    mut settings: ResMut<Settings>,
+    mut rng: ResMut<GameRng>,
+    mut speed: ResMut<Speed>,
+    mut score: ResMut<Score>,
+    mut player_q: Query<&mut Player>,
+) {
+    let replay_settings = playback.replay.settings.clone();
+    if playback.live_settings.is_none() {
+        playback.live_settings = Some(std::mem::replace(&mut *settings, replay_settings));
+    }
+    playback.tick = 0;
+    *rng = GameRng::new(playback.replay.seed);
+    speed.speed = settings.startup_speed;
+    score.score = settings.startup_score;
+    for mut player in &mut player_q {
+        player.hp = settings.player_hp;
+        player.hits = 0;
+    }
+    *inputs = TickInputs::default();
+    inputs.0[0] = TickInput {
+        delta: Vec2::ZERO,
+        hide: playback.replay.held_at_start,
+        was_hiding: playback.replay.held_at_start,
+    };
+}
+
+///Fills [TickInputs] from [PendingInputs] and records the first player's input. Runs every gameplay tick unless a
+/// [Playback] is watched.
+/// # Arguments
+/// * `pending` - [PendingInputs].
+/// * `inputs` - [TickInputs].
+/// * `recording` - [Recording].
+fn sample_live_input(
+    mut pending: ResMut<PendingInputs>,
+    mut inputs: ResMut<TickInputs>,
+    mut recording: ResMut<Recording>,
+) {
+    for (pending, input) in pending.0.iter_mut().zip(inputs.0.iter_mut()) {
+        *input = TickInput {
+            delta: pending.delta,
+            hide: pending.held || pending.clicked,
+            was_hiding: input.hide,
+        };
+        pending.delta = Vec2::ZERO;
+        pending.clicked = false;
+    }
+    let input = inputs.0[0];
+    recording.inputs.push(RecordedInput {
+        dx: input.delta.x,
+        dy: input.delta.y,
+        hide: input.hide,
+    });
+}
+
+///Fills the first player's [TickInput] from [Playback]. Runs every gameplay tick while a [Playback] is watched.
+/// Ends the game if the replay runs out of input.
+/// # Arguments
+/// * `playback` - [Playback].
+/// * `inputs` - [TickInputs].
+/// * `next` - [NextState] of [GameState].
+fn sample_replay_input(
+    mut playback: ResMut<Playback>,
+    mut inputs: ResMut<TickInputs>,
+    mut next: ResMut<NextState<GameState>>,
+) {
+    let Some(recorded) = playback.replay.inputs.get(playback.tick).copied() else {
+        next.set(GameState::EndScreen);
+        return;
+    };
+    playback.tick += 1;
+    let input = &mut inputs.0[0];
+    *input = TickInput {
+        delta: Vec2::new(recorded.dx, recorded.dy),
+        hide: recorded.hide,
+        was_hiding: input.hide,
+    };
+}
+
+///Saves the finished run as the last replay, and as the best replay if it beat it, on enter to [GameState::EndScreen].
+/// # Arguments
+/// * `recording` - [Recording].
+/// * `settings` - [Settings].
+/// * `rng` - [GameRng].
+/// * `score` - [Score].
+fn save_replay(
+    mut recording: ResMut<Recording>,
+    settings: Res<Settings>,
+    rng: Res<GameRng>,
+    score: Res<Score>,
+) {
+    let replay = Replay {
+        seed: rng.seed,
+        settings: settings.clone(),
+        score: score.score,
+        held_at_start: recording.held_at_start,
+        inputs: std::mem::take(&mut recording.inputs),
+    };
+    if let Err(err) = replay.save(LAST_REPLAY) {
+        warn!("Failed to save replay: {}", err);
+    }
+    let is_best = Replay::load(BEST_REPLAY).map_or(true, |best| best.score < replay.score);
+    if is_best {
+        if let Err(err) = replay.save(BEST_REPLAY) {
+            warn!("Failed to save replay: {}", err);
+        }
+    }
+}
+
+///Removes [Playback] on exit from [GameState::EndScreen], restoring the player's [Settings].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `playback` - [Playback].
+/// * `settings` - [Settings].
+/// * `speed` - [Speed].
+/// * `score` - [Score].
+fn stop_playback(
+    mut commands: Commands,
+    mut playback: ResMut<Playback>,
+    mut settings: ResMut<Settings>,
+    mut speed: ResMut<Speed>,
+    mut score: ResMut<Score>,
+) {
+    if let Some(live_settings) = playback.live_settings.take() {
+        *settings = live_settings;
+    }
+    speed.speed = settings.startup_speed;
+    score.score = settings.startup_score;
+    commands.remove_resource::<Playback>();
+}
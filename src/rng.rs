@@ -0,0 +1,78 @@
+use crate::GameState;
+use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+///[Plugin] taking care of [GameRng] seeding.
+pub struct RngPlugin;
+
+///Seed used for every run. If [None], a new random seed is picked at the start of each run.
+#[derive(Resource, Default)]
+pub struct SeedConfig(pub Option<u64>);
+
+///Random number generators used instead of [rand::random], so runs can be reproduced.
+/// Reseeded at the start of every run.
+/// # Fields
+/// * `seed` - Seed of the current run.
+/// * `gameplay` - Stream for everything that affects the game, like enemy types and positions.
+/// * `cosmetic` - Stream for effects that do not affect the game, like [crate::player::Star]s and [crate::clouds::Cloud]s.
+///   Kept separate so the number of stars or clouds spawned does not change the enemy sequence.
+#[derive(Resource)]
+pub struct GameRng {
+    pub seed: u64,
+    pub gameplay: StdRng,
+    pub cosmetic: StdRng,
+}
+
+impl GameRng {
+    ///Creates both streams from a single seed.
+    pub fn new(seed: u64) -> GameRng {
+        GameRng {
+            seed,
+            gameplay: StdRng::seed_from_u64(seed),
+            //Any constant works, it only has to differ from the gameplay stream
+            cosmetic: StdRng::seed_from_u64(seed ^ 0x5eed_c10d_5eed_c10d),
+        }
+    }
+}
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SeedConfig>()
+            .add_systems(PreStartup, spawn_rng)
+            .add_systems(OnEnter(GameState::Game), reseed);
+    }
+}
+
+///Reads [SeedConfig] from command line arguments. The seed is given as `--seed N`.
+/// # Arguments
+/// * `args` - Command line arguments.
+pub fn seed_from_args(args: &[String]) -> Result<SeedConfig, String> {
+    let Some(position) = args.iter().position(|arg| arg == "--seed") else {
+        return Ok(SeedConfig(None));
+    };
+    match args.get(position + 1).map(|seed| seed.parse::<u64>()) {
+        Some(Ok(seed)) => Ok(SeedConfig(Some(seed))),
+        _ => Err("--seed expects a non-negative number".to_string()),
+    }
+}
+
+///Picks a seed from [SeedConfig] or a random one if no seed is configured.
+fn pick_seed(config: &SeedConfig) -> u64 {
+    config.0.unwrap_or_else(rand::random)
+}
+
+///Spawns [GameRng].
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `config` - [SeedConfig].
+fn spawn_rng(mut commands: Commands, config: Res<SeedConfig>) {
+    commands.insert_resource(GameRng::new(pick_seed(&config)));
+}
+
+///Reseeds [GameRng] on enter to [GameState::Game].
+/// # Arguments
+/// * `rng` - [GameRng].
+/// * `config` - [SeedConfig].
+pub fn reseed(mut rng: ResMut<GameRng>, config: Res<SeedConfig>) {
+    *rng = GameRng::new(pick_seed(&config));
+}
@@ -0,0 +1,48 @@
+use crate::{speed::Speed, GameState, GameplaySet, Settings};
+use bevy::prelude::*;
+
+///[Plugin] taking care of functionalities corelating with [Score].
+pub struct ScorePlugin;
+
+///Current achieved score, increments with time.
+/// # Fields
+/// `score` - current score.
+#[derive(Resource)]
+pub struct Score {
+    pub score: f32,
+}
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_score)
+            .add_systems(FixedUpdate, score_update.in_set(GameplaySet::Logic))
+            .add_systems(OnExit(GameState::EndScreen), reset_score);
+    }
+}
+
+///Spawns [Score].
+/// # Arguments
+/// * `commands` - [Commands]
+/// * `settings` - [Settings], used to access the `startup_score` field.
+fn spawn_score(mut commands: Commands, settings: Res<Settings>) {
+    commands.insert_resource(Score {
+        score: settings.startup_score,
+    });
+}
+
+///Increments score every gameplay tick. Incrementing scales with [Speed].
+/// # Arguments
+/// * `score` - [Score].
+/// * `speed` - [Speed].
+/// * `time` - [FixedTime].
+fn score_update(mut score: ResMut<Score>, speed: Res<Speed>, time: Res<FixedTime>) {
+    score.score += time.period.as_secs_f32() * 50. * speed.speed;
+}
+
+///Resets [Score] on exit from [GameState::EndScreen].
+/// # Arguments
+/// * `settings` - [Settings], used to access the `startup_score` field.
+/// * `score` - [Score].
+fn reset_score(settings: Res<Settings>, mut score: ResMut<Score>) {
+    score.score = settings.startup_score;
+}
@@ -0,0 +1,327 @@
+use crate::{player::MAX_PLAYER_HP, Settings};
+use std::fmt;
+use std::fs::read_to_string;
+use std::io::ErrorKind;
+
+///File [Settings] are loaded from, unless a different one is passed with `--settings PATH`.
+pub const SETTINGS_FILE: &str = "./settings.ron";
+
+///Reasons why [Settings] could not be loaded.
+/// * `Io` - The file exists, but could not be read.
+/// * `Parse` - The file is not valid RON or contains unknown fields.
+/// * `Invalid` - A value is out of its allowed range.
+#[derive(Debug)]
+pub enum SettingsError {
+    Io {
+        path: String,
+        error: std::io::Error,
+    },
+    Parse {
+        path: String,
+        error: ron::error::SpannedError,
+    },
+    Invalid {
+        path: String,
+        field: &'static str,
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Io { path, error } => write!(f, "{}: {}", path, error),
+            SettingsError::Parse { path, error } => write!(
+                f,
+                "{}:{}:{}: {}",
+                path, error.position.line, error.position.col, error.code
+            ),
+            SettingsError::Invalid {
+                path,
+                field,
+                reason,
+            } => write!(f, "{}: `{}` {}", path, field, reason),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            startup_score: 120.,
+            startup_speed: 1.6,
+            speed_scaling: 0.01,
+            hit_resistence: 100.,
+            shakes: 4,
+            player_hp: 3,
+            max_hp: 3,
+            energy_drain: 35.,
+            energy_regen: 4.,
+            energy_pickup: 60.,
+            fall_speed: 200.,
+            gravity_radius: 400.,
+            gravity_softening: 30.,
+            max_gravity: 4000.,
+            min_gap: 250.,
+            max_gap: 350.,
+        }
+    }
+}
+
+impl Settings {
+    ///Loads [Settings] from a RON file. Fields missing in the file keep their default value.
+    /// If the file does not exist, default [Settings] are returned.
+    pub fn load(path: &str) -> Result<Settings, SettingsError> {
+        let content = match read_to_string(path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Settings::default()),
+            Err(error) => {
+                return Err(SettingsError::Io {
+                    path: path.to_string(),
+                    error,
+                })
+            }
+        };
+        let settings: Settings = ron::from_str(&content).map_err(|error| SettingsError::Parse {
+            path: path.to_string(),
+            error,
+        })?;
+        settings
+            .validate()
+            .map_err(|(field, reason)| SettingsError::Invalid {
+                path: path.to_string(),
+                field,
+                reason,
+            })?;
+        Ok(settings)
+    }
+
+    ///Checks that every value makes sense, returning the name of the first wrong field and what is wrong with it.
+    fn validate(&self) -> Result<(), (&'static str, &'static str)> {
+        let positive = [
+            ("startup_speed", self.startup_speed),
+            ("hit_resistence", self.hit_resistence),
+            ("fall_speed", self.fall_speed),
+            ("gravity_softening", self.gravity_softening),
+            ("max_gravity", self.max_gravity),
+        ];
+        let non_negative = [
+            ("startup_score", self.startup_score),
+            ("speed_scaling", self.speed_scaling),
+            ("energy_drain", self.energy_drain),
+            ("energy_regen", self.energy_regen),
+            ("energy_pickup", self.energy_pickup),
+            ("gravity_radius", self.gravity_radius),
+            ("min_gap", self.min_gap),
+            ("max_gap", self.max_gap),
+        ];
+        for (field, value) in positive {
+            if !value.is_finite() || value <= 0. {
+                return Err((field, "must be a number greater than 0"));
+            }
+        }
+        for (field, value) in non_negative {
+            if !value.is_finite() || value < 0. {
+                return Err((field, "must be a number greater than or equal to 0"));
+            }
+        }
+        if self.shakes == 0 {
+            return Err(("shakes", "must be at least 1"));
+        }
+        if !(0..=MAX_PLAYER_HP).contains(&self.player_hp) {
+            return Err(("player_hp", "must be between 0 and 3"));
+        }
+        if self.max_hp < self.player_hp {
+            return Err(("max_hp", "must not be less than player_hp"));
+        }
+        if self.min_gap > self.max_gap {
+            return Err(("min_gap", "must not be greater than max_gap"));
+        }
+        Ok(())
+    }
+}
+
+///Reads the settings file path from command line arguments, given as `--settings PATH`, and loads [Settings] from it.
+/// Without the argument, [SETTINGS_FILE] is used.
+/// # Arguments
+/// * `args` - Command line arguments.
+pub fn settings_from_args(args: &[String]) -> Result<Settings, String> {
+    let path = match args.iter().position(|arg| arg == "--settings") {
+        Some(position) => args
+            .get(position + 1)
+            .ok_or_else(|| "--settings expects a path".to_string())?,
+        None => SETTINGS_FILE,
+    };
+    Settings::load(path).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{remove_file, write};
+
+    ///Writes `content` to a file named `name` in the temporary folder, loads [Settings] from it and removes it.
+    fn load(name: &str, content: &str) -> Result<Settings, SettingsError> {
+        let path = std::env::temp_dir().join(name);
+        write(&path, content).unwrap();
+        let settings = Settings::load(path.to_str().unwrap());
+        remove_file(&path).unwrap();
+        settings
+    }
+
+    ///Returns the field and reason [Settings::validate] rejects `settings` with.
+    fn invalid(settings: Settings) -> (&'static str, &'static str) {
+        settings.validate().expect_err("settings should be invalid")
+    }
+
+    #[test]
+    fn missing_file_gives_defaults() {
+        let settings = Settings::load("./no_such_settings.ron").unwrap();
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn shipped_file_matches_defaults() {
+        assert_eq!(Settings::load(SETTINGS_FILE).unwrap(), Settings::default());
+    }
+
+    #[test]
+    fn missing_fields_keep_defaults() {
+        let settings = load("pupik_partial_settings.ron", "(shakes: 7)").unwrap();
+        assert_eq!(settings.shakes, 7);
+        assert_eq!(settings.player_hp, Settings::default().player_hp);
+    }
+
+    #[test]
+    fn unknown_field_names_line_and_column() {
+        let error = load(
+            "pupik_unknown_settings.ron",
+            "(\n    shakes: 2,\n    lives: 3,\n)",
+        )
+        .unwrap_err();
+        let SettingsError::Parse { error, .. } = &error else {
+            panic!("{} should be a parse error", error);
+        };
+        assert_eq!(error.position.line, 3);
+        assert!(error.position.col > 1);
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        let positive = [
+            "startup_speed",
+            "hit_resistence",
+            "fall_speed",
+            "gravity_softening",
+            "max_gravity",
+        ];
+        for field in positive {
+            for value in [0., -1., f32::NAN] {
+                let mut settings = Settings::default();
+                *field_mut(&mut settings, field) = value;
+                assert_eq!(
+                    invalid(settings),
+                    (field, "must be a number greater than 0"),
+                    "{} = {}",
+                    field,
+                    value
+                );
+            }
+        }
+        let non_negative = [
+            "startup_score",
+            "speed_scaling",
+            "energy_drain",
+            "energy_regen",
+            "energy_pickup",
+            "gravity_radius",
+            "min_gap",
+            "max_gap",
+        ];
+        for field in non_negative {
+            for value in [-1., f32::INFINITY] {
+                let mut settings = Settings::default();
+                *field_mut(&mut settings, field) = value;
+                assert_eq!(
+                    invalid(settings),
+                    (field, "must be a number greater than or equal to 0"),
+                    "{} = {}",
+                    field,
+                    value
+                );
+            }
+        }
+        let mut zero = Settings::default();
+        *field_mut(&mut zero, "min_gap") = 0.;
+        assert!(zero.validate().is_ok());
+    }
+
+    #[test]
+    fn invalid_counts_and_ranges_are_rejected() {
+        let no_shakes = Settings {
+            shakes: 0,
+            ..Settings::default()
+        };
+        assert_eq!(invalid(no_shakes).0, "shakes");
+        for player_hp in [-1, MAX_PLAYER_HP + 1] {
+            let settings = Settings {
+                player_hp,
+                max_hp: 10,
+                ..Settings::default()
+            };
+            assert_eq!(invalid(settings).0, "player_hp");
+        }
+        let max_hp = Settings {
+            player_hp: 3,
+            max_hp: 2,
+            ..Settings::default()
+        };
+        assert_eq!(
+            invalid(max_hp),
+            ("max_hp", "must not be less than player_hp")
+        );
+        let gaps = Settings {
+            min_gap: 400.,
+            max_gap: 300.,
+            ..Settings::default()
+        };
+        assert_eq!(
+            invalid(gaps),
+            ("min_gap", "must not be greater than max_gap")
+        );
+    }
+
+    #[test]
+    fn invalid_value_names_the_field() {
+        let error = load("pupik_invalid_settings.ron", "(player_hp: 4)").unwrap_err();
+        assert!(matches!(
+            error,
+            SettingsError::Invalid {
+                field: "player_hp",
+                ..
+            }
+        ));
+    }
+
+    ///Returns the float field of `settings` named `field`.
+    fn field_mut<'a>(settings: &'a mut Settings, field: &str) -> &'a mut f32 {
+        match field {
+            "startup_speed" => &mut settings.startup_speed,
+            "speed_scaling" => &mut settings.speed_scaling,
+            "startup_score" => &mut settings.startup_score,
+            "hit_resistence" => &mut settings.hit_resistence,
+            "energy_drain" => &mut settings.energy_drain,
+            "energy_regen" => &mut settings.energy_regen,
+            "energy_pickup" => &mut settings.energy_pickup,
+            "fall_speed" => &mut settings.fall_speed,
+            "gravity_radius" => &mut settings.gravity_radius,
+            "gravity_softening" => &mut settings.gravity_softening,
+            "max_gravity" => &mut settings.max_gravity,
+            "min_gap" => &mut settings.min_gap,
+            "max_gap" => &mut settings.max_gap,
+            _ => panic!("unknown field {}", field),
+        }
+    }
+}
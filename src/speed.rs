@@ -1,4 +1,4 @@
-use crate::{GameState, Settings};
+use crate::{GameState, GameplaySet, Settings};
 use bevy::prelude::*;
 
 ///[Plugin] taking care of functionalities corelating with [Speed].
@@ -15,18 +15,18 @@ pub struct Speed {
 impl Plugin for SpeedPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, spawn_tachometer)
-            .add_systems(Update, scaling.run_if(in_state(GameState::Game)))
+            .add_systems(FixedUpdate, scaling.in_set(GameplaySet::Logic))
             .add_systems(OnExit(GameState::EndScreen), reset_speed);
     }
 }
 
-///Scales [Speed] with time.
+///Scales [Speed] every gameplay tick.
 /// # Arguments
 /// * `speed` - [Speed].
-/// * `time` - [Time].
+/// * `time` - [FixedTime].
 /// * `settings` - [Settings], used to access the `speed_scaling` field.
-fn scaling(mut speed: ResMut<Speed>, time: ResMut<Time>, settings: Res<Settings>) {
-    speed.speed += settings.speed_scaling * time.delta_seconds();
+fn scaling(mut speed: ResMut<Speed>, time: Res<FixedTime>, settings: Res<Settings>) {
+    speed.speed += settings.speed_scaling * time.period.as_secs_f32();
 }
 
 ///Spawns [Speed].
@@ -1,64 +1,104 @@
 use crate::{
     player::{Hidden, Player},
-    speed::Speed,
-    GameState, Settings,
+    playfield::PlayfieldPanel,
+    preferences::{Language, Preferences},
+    score::Score,
+    versus::GameMode,
+    GameState,
 };
 use bevy::prelude::*;
 
 ///[Plugin] taking care of functionalities corelating with ingame [Text].
 pub struct TextPlugin;
 
+///Labels the root of the in-game HUD, a transparent node over the whole window centering its [PlayfieldPanel].
+#[derive(Component)]
+struct Hud;
+
 ///[Text] in the bottom right corner showing current [Score]
 #[derive(Component)]
 struct ScoreText;
 
-///Current achieved score, increments with time.
-/// # Fields
-/// `score` - current score.
-#[derive(Resource)]
-pub struct Score {
-    pub score: f32,
+///How much larger the HUD is with [Preferences] `large_hud`.
+const LARGE_HUD_SCALE: f32 = 1.5;
+
+///Returns how much the HUD is scaled.
+/// # Arguments
+/// * `preferences` - [Preferences].
+fn hud_scale(preferences: &Preferences) -> f32 {
+    if preferences.large_hud {
+        LARGE_HUD_SCALE
+    } else {
+        1.
+    }
 }
 
-///Labels text in the bottom left corner showing current [Hidden].energy level.
+///Labels text in the bottom left corner showing current [Hidden].energy level of the [Player] with given index.
 #[derive(Component)]
-struct EnergyText;
+struct EnergyText(usize);
 
 impl Plugin for TextPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_score)
-            .add_systems(OnEnter(GameState::Game), (score_counter, energy_counter))
+        app.add_systems(OnEnter(GameState::Game), spawn_hud)
             .add_systems(
                 Update,
-                (score_update, energy_update).run_if(in_state(GameState::Game)),
+                (score_text_update, energy_update).run_if(in_state(GameState::Game)),
             )
-            .add_systems(OnExit(GameState::Game), despawn_text)
-            .add_systems(OnExit(GameState::EndScreen), reset_score);
+            .add_systems(OnExit(GameState::Game), despawn_hud);
     }
 }
 
-///Spawns [Score].
+///Spawns [Hud] with [ScoreText] and [EnergyText]s inside a [PlayfieldPanel], so the HUD stays on [Playfield](crate::playfield::Playfield)
+/// instead of the [LETTERBOX](crate::playfield::LETTERBOX) bars of a wide window.
 /// # Arguments
 /// * `commands` - [Commands]
-/// * `settings` - [Settings], used to access the `startup_score` field.
-fn spawn_score(mut commands: Commands, settings: Res<Settings>) {
-    commands.insert_resource(Score {
-        score: settings.startup_score,
-    });
+/// * `assets` - [AssetServer]. Used to load font.
+/// * `mode` - [GameMode].
+/// * `preferences` - [Preferences], the font is larger with `large_hud`.
+fn spawn_hud(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    mode: Res<GameMode>,
+    preferences: Res<Preferences>,
+) {
+    let scale = hud_scale(&preferences);
+    let font = assets.load("fonts\\Love_Letters.ttf");
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Hud)
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle::default())
+                .insert(PlayfieldPanel)
+                .with_children(|parent| {
+                    score_counter(parent, &font, scale);
+                    energy_counter(parent, &font, scale, *mode, preferences.language);
+                });
+        });
 }
 
 ///Spawns [ScoreText].
 /// # Arguments
-/// * `commands` - [Commands]
-/// * `assets` - [AssetServer]. Used to load font.
-fn score_counter(mut commands: Commands, assets: Res<AssetServer>) {
-    let font = assets.load("fonts\\Love_Letters.ttf");
+/// * `parent` - [ChildBuilder] of the [PlayfieldPanel] of [Hud].
+/// * `font` - Font of the text.
+/// * `scale` - See [hud_scale].
+fn score_counter(parent: &mut ChildBuilder, font: &Handle<Font>, scale: f32) {
     let text_style = TextStyle {
-        font,
-        font_size: 50.0,
+        font: font.clone(),
+        font_size: 50.0 * scale,
         color: Color::rgb(0.9, 0.9, 0.9),
     };
-    commands
+    parent
         .spawn((TextBundle {
             text: Text::from_section(0.to_string(), text_style)
                 .with_alignment(TextAlignment::Center),
@@ -73,94 +113,100 @@ fn score_counter(mut commands: Commands, assets: Res<AssetServer>) {
         .insert(ScoreText);
 }
 
-///Spawns [EnergyText].
+///Spawns [EnergyText] for every player of [GameMode].
 /// # Arguments
-/// * `commands` - [Commands]
-/// * `assets` - [AssetServer]. Used to load font.
-fn energy_counter(mut commands: Commands, assets: Res<AssetServer>) {
-    let font = assets.load("fonts\\Love_Letters.ttf");
+/// * `parent` - [ChildBuilder] of the [PlayfieldPanel] of [Hud].
+/// * `font` - Font of the text.
+/// * `scale` - See [hud_scale].
+/// * `mode` - [GameMode].
+/// * `language` - [Language] of the text.
+fn energy_counter(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    scale: f32,
+    mode: GameMode,
+    language: Language,
+) {
     let desc_text_style = TextStyle {
         font: font.clone(),
-        font_size: 25.,
+        font_size: 25. * scale,
         color: Color::rgb(0.9, 0.9, 0.9),
     };
-    commands.spawn((TextBundle {
-        text: Text::from_section("ENERGY", desc_text_style).with_alignment(TextAlignment::Center),
-        ..default()
-    }
-    .with_style(Style {
-        position_type: PositionType::Absolute,
-        bottom: Val::Px(5.0),
-        left: Val::Px(15.0),
-        ..default()
-    }),));
-
     let energy_text_style = TextStyle {
-        font,
-        font_size: 35.0,
+        font: font.clone(),
+        font_size: 35.0 * scale,
         color: Color::rgb(0.9, 0.9, 0.9),
     };
-    commands
-        .spawn((TextBundle {
-            text: Text::from_section("", energy_text_style).with_alignment(TextAlignment::Center),
+    for index in 0..mode.players() {
+        let left = Val::Px(15.0 + 170. * scale * index as f32);
+        let desc = match mode {
+            GameMode::Single | GameMode::Lan => language.tr("ENERGY").to_owned(),
+            GameMode::Local => format!("P{} {}", index + 1, language.tr("ENERGY")),
+        };
+        parent.spawn((TextBundle {
+            text: Text::from_section(desc, desc_text_style.clone())
+                .with_alignment(TextAlignment::Center),
             ..default()
         }
         .with_style(Style {
             position_type: PositionType::Absolute,
-            bottom: Val::Px(30.0),
-            left: Val::Px(15.0),
+            bottom: Val::Px(5.0),
+            left,
             ..default()
-        }),))
-        .insert(EnergyText);
+        }),));
+
+        parent
+            .spawn((TextBundle {
+                text: Text::from_section("", energy_text_style.clone())
+                    .with_alignment(TextAlignment::Center),
+                ..default()
+            }
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(5.0 + 25. * scale),
+                left,
+                ..default()
+            }),))
+            .insert(EnergyText(index));
+    }
 }
 
-///Increments score with time and updates [ScoreText] appropriately. Incrementing scales with [Speed].
+///Updates [ScoreText] appropriately.
 /// # Arguments
 /// * `query` - [Query] for [ScoreText].
 /// * `score` - [Score].
-/// * `speed` - [Speed].
-/// * `time` - [Time].
-fn score_update(
-    mut query: Query<&mut Text, With<ScoreText>>,
-    mut score: ResMut<Score>,
-    speed: Res<Speed>,
-    time: Res<Time>,
-) {
+fn score_text_update(mut query: Query<&mut Text, With<ScoreText>>, score: Res<Score>) {
     for mut text in &mut query {
-        score.score += time.delta_seconds() * 50. * speed.speed;
         text.sections[0].value = format!("{}", score.score as usize);
     }
 }
 
-///Updates [EnergyText] appropriately.
+///Updates [EnergyText] appropriately, showing "OUT" once its [Player] is gone.
 /// # Arguments
 /// * `query` - [Query] for [EnergyText].
 /// * `player` - [Query] for [Player].
+/// * `preferences` - [Preferences], "OUT" is in its `language`.
 fn energy_update(
-    mut query: Query<&mut Text, With<EnergyText>>,
-    player: Query<&Hidden, With<Player>>,
+    mut query: Query<(&mut Text, &EnergyText)>,
+    player: Query<(&Player, &Hidden)>,
+    preferences: Res<Preferences>,
 ) {
-    for mut text in &mut query {
-        for players in &player {
-            text.sections[0].value = format!("{}", players.energy as usize);
-        }
+    for (mut text, energy_text) in &mut query {
+        text.sections[0].value = player
+            .iter()
+            .find(|(player, _)| player.index == energy_text.0)
+            .map_or(preferences.language.tr("OUT").to_owned(), |(_, hidden)| {
+                format!("{}", hidden.energy as usize)
+            });
     }
 }
 
-///Despawns [Text] on exit from [GameState::Game].
+///Despawns [Hud] on exit from [GameState::Game].
 /// # Arguments
 /// * `commands` - [Commands].
-/// * `player_query` - [Query] for [Text].
-fn despawn_text(mut commands: Commands, query: Query<Entity, With<Text>>) {
+/// * `query` - [Query] for [Hud].
+fn despawn_hud(mut commands: Commands, query: Query<Entity, With<Hud>>) {
     for entity in query.iter() {
-        commands.entity(entity).despawn();
+        commands.entity(entity).despawn_recursive();
     }
 }
-
-///Spawns [Score].
-/// # Arguments
-/// * `settings` - [Settings], used to access the `startup_score` field.
-/// * `score` - [Score].
-fn reset_score(settings: Res<Settings>, mut score: ResMut<Score>) {
-    score.score = settings.startup_score;
-}
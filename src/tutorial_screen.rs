@@ -1,4 +1,4 @@
-use crate::{player::despawn_player, GameState};
+use crate::{player::despawn_player, playfield::Playfield, preferences::Preferences, GameState};
 use bevy::prelude::*;
 ///[Plugin] taking care of functionalities corelating with [Tutorial]
 pub struct TutorialPlugin;
@@ -7,6 +7,37 @@ pub struct TutorialPlugin;
 #[derive(Component)]
 struct Tutorial;
 
+///The whole explanation of how the game is played, in English. See [crate::preferences::Language::tr].
+pub const TUTORIAL: &str = "\n
+                You control your unicorn using your mouse,
+                keyboard or gamepad, see Controls in the menu.
+
+                There are many enemies that can inconvenience
+                you...
+                    Planes (damage unicorn)
+                    Candy (damage unicorn)
+                    Rainbows (damage unicorn)
+                    Planets (suck the unicorn closer)
+                    Black holes (suck in the unicorn)
+                    White holes (push the unicorn away)
+                    Pixies (fly in swarms, damage unicorn)
+
+                Press the left mouse button (or the hide key)
+                to hide the unicorn,
+                negating any efects.
+                Hiding uses energy, shown in the lower left corner.
+                Your energy slowly regenerates over time.
+                Collecting protein bars refills energy.
+                Collecting fruit heals the unicorn.
+
+                Score increases the further you get.
+                There is no end.
+                Press Escape or right click to pause.
+
+                Set Players to 2 in the menu to play with
+                a friend, the last unicorn flying wins.
+                Or race one on another computer, see LAN.";
+
 ///Button in the top left corner that takes player back to the main menu. When clicked, [GameState] changes
 /// to [GameState::MainMenu]. When hovered, its background color changes until unhovered.
 #[derive(Component)]
@@ -29,10 +60,11 @@ impl Plugin for TutorialPlugin {
 ///Spawns gray transparent background.
 /// # Arguments
 /// * `commands` - [Commands].
-pub fn spawn_background(mut commands: Commands) {
+/// * `playfield` - [Playfield], the background covers all of it.
+pub fn spawn_background(mut commands: Commands, playfield: Res<Playfield>) {
     let sprite = Sprite {
         color: Color::rgba(0.2, 0., 0.1, 0.75), //pink-ish gray
-        custom_size: Some(Vec2::new(1920.0 / 3., 700.0)),
+        custom_size: Some(Vec2::new(playfield.width, playfield.height)),
         ..Default::default()
     };
     let background = commands
@@ -64,7 +96,12 @@ fn despawn_tutorial(mut commands: Commands, query: Query<Entity, With<Tutorial>>
 /// # Arguments
 /// * `commands` - [Commands].
 /// * `assets` - [AssetServer]. Used to load font.
-pub fn back_button(mut commands: Commands, assets: Res<AssetServer>) {
+/// * `preferences` - [Preferences], texts are in its `language`.
+pub fn back_button(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    preferences: Res<Preferences>,
+) {
     commands
         .spawn(ButtonBundle {
             style: Style {
@@ -85,7 +122,7 @@ pub fn back_button(mut commands: Commands, assets: Res<AssetServer>) {
         })
         .with_children(|parent| {
             parent.spawn(TextBundle::from_section(
-                "Return to menu",
+                preferences.language.tr("Return to menu"),
                 TextStyle {
                     font: assets.load("fonts\\Love_Letters.ttf"),
                     font_size: 35.0,
@@ -97,11 +134,12 @@ pub fn back_button(mut commands: Commands, assets: Res<AssetServer>) {
         .insert(Tutorial);
 }
 
-///Spawns the whole explanation of how the game is played.
+///Spawns the whole explanation of how the game is played, [TUTORIAL].
 /// # Arguments
 /// * `commands` - [Commands].
 /// * `assets` - [AssetServer]. Used to load font.
-fn explain_game(mut commands: Commands, assets: Res<AssetServer>) {
+/// * `preferences` - [Preferences], the text is in its `language`.
+fn explain_game(mut commands: Commands, assets: Res<AssetServer>, preferences: Res<Preferences>) {
     let font = assets.load("fonts\\Love_Letters.ttf");
     let text_style = TextStyle {
         font,
@@ -110,29 +148,8 @@ fn explain_game(mut commands: Commands, assets: Res<AssetServer>) {
     };
     commands
         .spawn((TextBundle {
-            text: Text::from_section(
-                "\n
-                You control your unicorn solely using your mouse.
-
-                There are many enemies that can inconvenience
-                you...
-                    Planes (damage unicorn)
-                    Candy (damage unicorn)
-                    Rainbows (damage unicorn)
-                    Planets (suck the unicorn closer)
-                    Black holes (suck in the unicorn)
-
-                Press the left mouse button to hide the unicorn,
-                negating any efects.
-                Hiding uses energy, shown in the lower left corner.
-                Your energy slowly regenerates over time.
-                Collecting protein bars refills energy.
-
-                Score increases the further you get.
-                There is no end.",
-                text_style,
-            )
-            .with_alignment(TextAlignment::Left),
+            text: Text::from_section(preferences.language.tr(TUTORIAL), text_style)
+                .with_alignment(TextAlignment::Left),
             ..default()
         }
         .with_style(Style {
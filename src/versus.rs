@@ -0,0 +1,137 @@
+use crate::{
+    loading::Atlases,
+    map_layout::Enemy,
+    player::{create_player, Player},
+    replay::Playback,
+    score::Score,
+    GameState, Settings,
+};
+use bevy::prelude::*;
+
+///Most players which can play at once.
+pub const MAX_PLAYERS: usize = 2;
+
+///[Plugin] taking care of games with more than one [Player].
+pub struct VersusPlugin;
+
+///How many players play and where.
+/// * `Single` - One player.
+/// * `Local` - Two players on one machine, the second one on his own [crate::controls::InputDevice].
+/// * `Lan` - One player racing another game over LAN, see [crate::lan].
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameMode {
+    #[default]
+    Single,
+    Local,
+    Lan,
+}
+
+impl GameMode {
+    ///Number of players in [GameMode] playing on this machine.
+    pub fn players(&self) -> usize {
+        match self {
+            GameMode::Single | GameMode::Lan => 1,
+            GameMode::Local => 2,
+        }
+    }
+}
+
+///How a single [Player]'s run ended.
+/// # Fields
+/// * `score` - [crate::score::Score] at the moment he died, or when the game ended.
+/// * `hits` - How many times he was hit.
+/// * `last_hit` - Type of the [Enemy] that damaged him last.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PlayerResult {
+    pub score: f32,
+    pub hits: u32,
+    pub last_hit: Option<Enemy>,
+}
+
+///[PlayerResult] of every player who already died this run, indexed by [Player] `index`. Players still flying when
+/// the game ends get theirs on exit from [GameState::Game], see [finish_results].
+#[derive(Resource, Default)]
+pub struct PlayerResults(pub [Option<PlayerResult>; MAX_PLAYERS]);
+
+impl PlayerResults {
+    ///Index of the player with the highest score, [None] if nobody finished yet or it is a draw.
+    pub fn winner(&self) -> Option<usize> {
+        let mut finished = self
+            .0
+            .iter()
+            .enumerate()
+            .filter_map(|(index, result)| result.map(|result| (index, result.score)));
+        let (mut best, mut best_score) = finished.next()?;
+        let mut draw = false;
+        for (index, score) in finished {
+            if score > best_score {
+                (best, best_score, draw) = (index, score, false);
+            } else if score == best_score {
+                draw = true;
+            }
+        }
+        (!draw).then_some(best)
+    }
+}
+
+impl Plugin for VersusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameMode>()
+            .init_resource::<PlayerResults>()
+            .add_systems(
+                OnEnter(GameState::Game),
+                (
+                    reset_results,
+                    spawn_other_players.run_if(not(resource_exists::<Playback>())),
+                ),
+            )
+            .add_systems(OnExit(GameState::Game), finish_results);
+    }
+}
+
+///Clears [PlayerResults] on enter to [GameState::Game].
+/// # Arguments
+/// * `results` - [PlayerResults].
+fn reset_results(mut results: ResMut<PlayerResults>) {
+    *results = PlayerResults::default();
+}
+
+///Gives every [Player] still flying when the game ends, e.g. because it was quit from the pause menu, his
+/// [PlayerResult] with the current [Score].
+/// # Arguments
+/// * `player_q` - [Query] for [Player].
+/// * `score` - [Score].
+/// * `results` - [PlayerResults].
+fn finish_results(player_q: Query<&Player>, score: Res<Score>, mut results: ResMut<PlayerResults>) {
+    for player in &player_q {
+        if results.0[player.index].is_none() {
+            results.0[player.index] = Some(PlayerResult {
+                score: score.score,
+                hits: player.hits,
+                last_hit: player.last_hit,
+            });
+        }
+    }
+}
+
+///Spawns every [Player] but the first one, who is already flying around in the main menu, on enter to [GameState::Game].
+/// Replays are always played by one player.
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `mode` - [GameMode].
+/// * `atlases` - [Atlases].
+/// * `settings` - [Settings].
+/// * `player_q` - [Query] for [Player], so players are not spawned twice.
+fn spawn_other_players(
+    mut commands: Commands,
+    mode: Res<GameMode>,
+    atlases: Res<Atlases>,
+    settings: Res<Settings>,
+    player_q: Query<&Player>,
+) {
+    for index in 1..mode.players() {
+        if player_q.iter().all(|player| player.index != index) {
+            create_player(index, &mut commands, &atlases, &settings);
+        }
+    }
+}
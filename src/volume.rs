@@ -0,0 +1,171 @@
+use crate::preferences::{Language, Preferences};
+use bevy::prelude::*;
+use bevy_pkv::PkvStore;
+use serde::{Deserialize, Serialize};
+
+///[PkvStore] key [Volume] is stored under.
+pub const VOLUME_KEY: &str = "volume";
+
+///Key muting and unmuting the game anywhere. Not a letter, so typing a name or an address and binding keys never mutes.
+pub const MUTE_KEY: KeyCode = KeyCode::F10;
+
+///[Plugin] loading [Volume] and taking care of muting.
+pub struct VolumePlugin;
+
+///How loud the game is. Stored in [PkvStore] under [VOLUME_KEY], changed in the options screen, by [MUTE_KEY]
+/// or by a [MuteButton].
+/// # Fields
+/// * `master` - Multiplier of every sound, from 0 to 1.
+/// * `music` - Multiplier of music, from 0 to 1.
+/// * `sfx` - Multiplier of sound effects, from 0 to 1.
+/// * `muted` - Whether the game is muted, the other fields are kept so unmuting restores them.
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct Volume {
+    pub master: f64,
+    pub music: f64,
+    pub sfx: f64,
+    pub muted: bool,
+}
+
+impl Default for Volume {
+    fn default() -> Self {
+        Volume {
+            master: 1.,
+            music: 1.,
+            sfx: 1.,
+            muted: false,
+        }
+    }
+}
+
+impl Volume {
+    ///Loads [Volume] from [PkvStore], the default one if none is stored yet.
+    pub fn load(pkv: &PkvStore) -> Volume {
+        pkv.get::<Volume>(VOLUME_KEY).unwrap_or_default()
+    }
+
+    ///Stores [Volume] in [PkvStore].
+    pub fn save(&self, pkv: &mut PkvStore) {
+        if let Err(err) = pkv.set(VOLUME_KEY, self) {
+            warn!("Failed to store volume: {}", err);
+        }
+    }
+
+    ///Multiplier of music, 0 if muted.
+    pub fn music(&self) -> f64 {
+        if self.muted {
+            0.
+        } else {
+            self.master * self.music
+        }
+    }
+
+    ///Multiplier of sound effects, 0 if muted.
+    pub fn sfx(&self) -> f64 {
+        if self.muted {
+            0.
+        } else {
+            self.master * self.sfx
+        }
+    }
+}
+
+///Button toggling [Volume] `muted`, spawned in menus. Its [Text] child shows whether the game is muted.
+/// When hovered, its background color changes until unhovered.
+#[derive(Component)]
+pub struct MuteButton;
+
+///Text of [MuteButton].
+/// # Arguments
+/// * `volume` - [Volume].
+/// * `language` - [Language] of the text.
+pub fn mute_text(volume: &Volume, language: Language) -> &'static str {
+    if volume.muted {
+        language.tr("Sound: off")
+    } else {
+        language.tr("Sound: on")
+    }
+}
+
+impl Plugin for VolumePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreStartup, load_volume).add_systems(
+            Update,
+            (mute_hotkey, mute_button_interaction, refresh_mute_buttons).chain(),
+        );
+    }
+}
+
+///Loads [Volume] before any sound starts playing.
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `pkv` - [PkvStore].
+fn load_volume(mut commands: Commands, pkv: Res<PkvStore>) {
+    commands.insert_resource(Volume::load(&pkv));
+}
+
+///Toggles [Volume] `muted` when [MUTE_KEY] is pressed.
+/// # Arguments
+/// * `keys` - Keyboard input.
+/// * `volume` - [Volume].
+/// * `pkv` - [PkvStore].
+fn mute_hotkey(keys: Res<Input<KeyCode>>, mut volume: ResMut<Volume>, mut pkv: ResMut<PkvStore>) {
+    if keys.just_pressed(MUTE_KEY) {
+        volume.muted = !volume.muted;
+        volume.save(&mut pkv);
+    }
+}
+
+///Handles interactions with [MuteButton]s.
+/// # Arguments
+/// * `interaction_query` - [Query] for [MuteButton]s and their [Interaction] when changed.
+/// * `volume` - [Volume].
+/// * `pkv` - [PkvStore].
+fn mute_button_interaction(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<MuteButton>),
+    >,
+    mut volume: ResMut<Volume>,
+    mut pkv: ResMut<PkvStore>,
+) {
+    for (interaction, mut color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                volume.muted = !volume.muted;
+                volume.save(&mut pkv);
+            }
+            Interaction::Hovered => {
+                *color = Color::rgba(0., 0., 0., 0.7).into();
+            }
+            Interaction::None => {
+                *color = Color::rgba(0., 0., 0., 0.1).into();
+            }
+        }
+    }
+}
+
+///Updates [Text] of every [MuteButton] once [Volume] or [Preferences] change.
+/// # Arguments
+/// * `volume` - [Volume].
+/// * `preferences` - [Preferences], its `language` is used.
+/// * `button_q` - [Query] for [MuteButton]s and their children.
+/// * `text_q` - [Query] for [Text].
+fn refresh_mute_buttons(
+    volume: Res<Volume>,
+    preferences: Res<Preferences>,
+    button_q: Query<&Children, With<MuteButton>>,
+    mut text_q: Query<&mut Text>,
+) {
+    if !volume.is_changed() && !preferences.is_changed() {
+        return;
+    }
+    for children in &button_q {
+        for child in children.iter() {
+            if let Ok(mut text) = text_q.get_mut(*child) {
+                text.sections[0].value = mute_text(&volume, preferences.language).to_string();
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use crate::playfield::Playfield;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+///Collision group of every [crate::player::Player].
+pub const PLAYERS: Group = Group::GROUP_1;
+///Collision group of [Wall]s.
+pub const WALLS: Group = Group::GROUP_2;
+///Collision group of every enemy, pickups included.
+pub const ENEMIES: Group = Group::GROUP_3;
+
+///How thick [Wall]s are. Thick enough so [crate::player::Player] can't tunnel through even at high speed.
+const WALL_THICKNESS: f32 = 200.;
+
+///[Plugin] surrounding [Playfield] with [Wall]s.
+pub struct WallPlugin;
+
+///Labels solid [entities](Entity) around [Playfield], which keep [crate::player::Player] inside it and block nothing else.
+#[derive(Component)]
+pub struct Wall;
+
+impl Plugin for WallPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            build_walls.run_if(resource_changed::<Playfield>()),
+        );
+    }
+}
+
+///[CollisionGroups] of [crate::player::Player]. A hidden player only collides with [Wall]s.
+/// # Arguments
+/// * `hidden` - Whether the player is hidden, see [crate::player::Hidden].
+pub fn player_groups(hidden: bool) -> CollisionGroups {
+    if hidden {
+        CollisionGroups::new(PLAYERS, WALLS)
+    } else {
+        CollisionGroups::new(PLAYERS, PLAYERS | WALLS | ENEMIES)
+    }
+}
+
+///[CollisionGroups] of enemies, they only collide with [crate::player::Player]s.
+pub fn enemy_groups() -> CollisionGroups {
+    CollisionGroups::new(ENEMIES, PLAYERS)
+}
+
+///Returns centers and half extents of the four [Wall]s. Their inner sides lie exactly on the edges of `playfield`.
+/// # Arguments
+/// * `playfield` - [Playfield].
+pub fn wall_rects(playfield: &Playfield) -> [(Vec2, Vec2); 4] {
+    let half = WALL_THICKNESS / 2.;
+    let (w, h) = (playfield.half_width(), playfield.half_height());
+    let sides = Vec2::new(half, h + WALL_THICKNESS);
+    let ends = Vec2::new(w + WALL_THICKNESS, half);
+    [
+        (Vec2::new(-w - half, 0.), sides),
+        (Vec2::new(w + half, 0.), sides),
+        (Vec2::new(0., h + half), ends),
+        (Vec2::new(0., -h - half), ends),
+    ]
+}
+
+///(Re)spawns [Wall]s whenever [Playfield] changes.
+/// # Arguments
+/// * `commands` - [Commands].
+/// * `playfield` - [Playfield].
+/// * `wall_q` - [Query] for old [Wall]s.
+fn build_walls(
+    mut commands: Commands,
+    playfield: Res<Playfield>,
+    wall_q: Query<Entity, With<Wall>>,
+) {
+    for entity in &wall_q {
+        commands.entity(entity).despawn();
+    }
+    for (center, half_extents) in wall_rects(&playfield) {
+        commands
+            .spawn(TransformBundle::from_transform(
+                Transform::from_translation(center.extend(0.)),
+            ))
+            .insert(RigidBody::Fixed)
+            .insert(Collider::cuboid(half_extents.x, half_extents.y))
+            .insert(CollisionGroups::new(WALLS, PLAYERS))
+            .insert(Wall);
+    }
+}
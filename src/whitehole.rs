@@ -0,0 +1,59 @@
+use crate::{
+    blackhole::BLACKHOLE_SIZE, falling::FallTimer, map_layout::ObjectParams, playfield::Playfield,
+    AnimationTimer, Gravitating, Object,
+};
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng};
+
+///Labels non-damaging white holes, the opposite of [crate::blackhole::Hole]. They push the player away with negative
+/// [Gravitating] strength.
+#[derive(Component)]
+pub struct WhiteHole;
+
+///Spawns a [WhiteHole] object.
+/// # Arguments
+/// * `x` - if [None], a random `x` within [Playfield] is chosen.
+/// * `y` - if [None], it is set to [Playfield::spawn_y], above the upper bound.
+/// * `params` - [ObjectParams], `rotation`, `gravity` (how strongly it pushes) and `fall_speed` are used.
+/// * `commands` - [Commands].
+/// * `rng` - Gameplay stream of [crate::rng::GameRng].
+/// * `playfield` - [Playfield].
+/// * `texture` - Handle for white hole [TextureAtlas]
+pub fn create_white_hole(
+    x: Option<f32>,
+    y: Option<f32>,
+    params: &ObjectParams,
+    commands: &mut Commands,
+    rng: &mut StdRng,
+    playfield: &Playfield,
+    texture: &Handle<TextureAtlas>,
+) {
+    let mut sprite = TextureAtlasSprite::new(0);
+    sprite.custom_size = Some(Vec2::splat(BLACKHOLE_SIZE));
+
+    let x = x.unwrap_or(playfield.random_x(rng));
+    let y = y.unwrap_or(playfield.spawn_y());
+
+    commands
+        .spawn(SpriteSheetBundle {
+            sprite,
+            texture_atlas: texture.clone(),
+            transform: Transform {
+                translation: Vec3::new(x, y, 900. + rng.gen::<f32>()),
+                rotation: params.rotation_or(Quat::IDENTITY),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(WhiteHole)
+        .insert(FallTimer(Timer::from_seconds(7., TimerMode::Once)))
+        .insert(params.fall_speed())
+        .insert(Gravitating {
+            strength: -params.gravity.unwrap_or(2.5),
+        })
+        .insert(AnimationTimer(Timer::from_seconds(
+            0.15,
+            TimerMode::Repeating,
+        )))
+        .insert(Object);
+}